@@ -0,0 +1,106 @@
+//! Crate **ruma_identifiers_macros** contains the procedural macros behind the identifier macros
+//! of [ruma-identifiers](https://docs.rs/ruma-identifiers), like `user_id!`.
+//!
+//! They aren't meant to be used directly: ruma-identifiers wraps them in `macro_rules!` macros
+//! that pass along the path of the ruma-identifiers crate, so that the generated code works no
+//! matter under which name the crate is imported.
+
+#![warn(rust_2018_idioms)]
+#![deny(
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs
+)]
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use ruma_identifiers_validation::{event_id, room_alias_id, room_id, user_id, Error};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, LitStr, Path, Result as SynResult, Token,
+};
+
+/// The input of the macros: the path of the ruma-identifiers crate, followed by a comma and the
+/// identifier literal.
+struct Input {
+    /// The path of the ruma-identifiers crate, i.e. the `$crate` of the wrapping macro.
+    dollar_crate: Path,
+    /// The identifier literal.
+    id: LitStr,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream<'_>) -> SynResult<Self> {
+        let dollar_crate = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let id = input.parse()?;
+
+        Ok(Self { dollar_crate, id })
+    }
+}
+
+/// Validates an identifier literal, expanding to an expression of the given identifier type if it
+/// is valid, or to a compile error with the span of the literal otherwise.
+fn expand(
+    input: Input,
+    type_name: &str,
+    description: &str,
+    validate: fn(&str) -> Result<(), Error>,
+) -> TokenStream {
+    let Input { dollar_crate, id } = input;
+
+    if let Err(err) = validate(&id.value()) {
+        let message = format!("invalid {}: {}", description, err);
+        return syn::Error::new(id.span(), message)
+            .to_compile_error()
+            .into();
+    }
+
+    let ty = format_ident!("{}", type_name);
+    let expect_message = format!("{} literal was validated at compile time", description);
+
+    let output = quote! {
+        <#dollar_crate::#ty as ::core::convert::TryFrom<&str>>::try_from(#id)
+            .expect(#expect_message)
+    };
+
+    output.into()
+}
+
+/// Creates a `UserId` from a string literal, failing to compile if it is not a valid user ID.
+#[proc_macro]
+pub fn user_id(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Input);
+
+    expand(input, "UserId", "user ID", user_id::validate)
+}
+
+/// Creates a `RoomId` from a string literal, failing to compile if it is not a valid room ID.
+#[proc_macro]
+pub fn room_id(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Input);
+
+    expand(input, "RoomId", "room ID", room_id::validate)
+}
+
+/// Creates a `RoomAliasId` from a string literal, failing to compile if it is not a valid room
+/// alias ID.
+#[proc_macro]
+pub fn room_alias_id(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Input);
+
+    expand(
+        input,
+        "RoomAliasId",
+        "room alias ID",
+        room_alias_id::validate,
+    )
+}
+
+/// Creates an `EventId` from a string literal, failing to compile if it is not a valid event ID.
+#[proc_macro]
+pub fn event_id(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Input);
+
+    expand(input, "EventId", "event ID", event_id::validate)
+}
@@ -0,0 +1,165 @@
+//! Error conditions.
+
+use core::fmt::{Display, Formatter, Result as FmtResult};
+#[cfg(feature = "std")]
+use std::error::Error as StdError;
+
+use crate::host::HostError;
+
+/// An error encountered when validating an identifier.
+///
+/// Unlike the `Error` of ruma-identifiers, it doesn't contain the input, so it can be returned
+/// without allocating.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Error {
+    /// The rule the input violated.
+    kind: ErrorKind,
+    /// The error returned when parsing the server name, if any.
+    host_error: Option<HostError>,
+}
+
+impl Error {
+    /// Returns the kind of the error, i.e. the rule the input violated.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Returns the error of the host or port parser, if it rejected the server name.
+    pub fn host_error(&self) -> Option<HostError> {
+        self.host_error
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Self {
+            kind,
+            host_error: None,
+        }
+    }
+}
+
+impl From<HostError> for Error {
+    /// Creates an error for a server name that was rejected by the host or port parser.
+    ///
+    /// The kind is `InvalidPort` for port errors and `InvalidHost` otherwise.
+    fn from(host_error: HostError) -> Self {
+        let kind = match host_error {
+            HostError::InvalidPort => ErrorKind::InvalidPort,
+            _ => ErrorKind::InvalidHost,
+        };
+
+        Self {
+            kind,
+            host_error: Some(host_error),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.kind)
+    }
+}
+
+#[cfg(feature = "std")]
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.host_error.as_ref().map(|source| source as _)
+    }
+}
+
+/// The kind of an `Error`, i.e. the rule an invalid ID string violated.
+///
+/// More kinds may be added in the future without it being considered a breaking change, so
+/// matching on an `ErrorKind` requires a wildcard arm.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The address of a third-party identifier is not valid for its medium.
+    ///
+    /// Only relevant for third-party identifiers.
+    InvalidAddress,
+    /// The ID's localpart contains invalid characters.
+    ///
+    /// Only relevant for user IDs, room aliases, the media IDs of content URIs, and localparts
+    /// containing a colon that are passed to `from_parts` constructors.
+    InvalidCharacters,
+    /// The domain part of the the ID string is not a valid IP address or DNS name.
+    InvalidHost,
+    /// The algorithm or the key identifier of a key ID is empty or contains invalid characters.
+    ///
+    /// Only relevant for key IDs.
+    InvalidKeyId,
+    /// The port of the server name is not a number between 1 and 65535, or the server name
+    /// continues after its host without a colon separating the port.
+    InvalidPort,
+    /// The URI does not start with the expected scheme.
+    ///
+    /// Only relevant for content URIs, matrix.to permalinks and `matrix:` URIs.
+    InvalidUriScheme,
+    /// The URI does not contain the expected identifiers, e.g. because its path has an unknown
+    /// prefix.
+    ///
+    /// Only relevant for matrix.to permalinks and `matrix:` URIs.
+    InvalidUriPath,
+    /// The bytes an ID was parsed from are not valid UTF-8.
+    ///
+    /// Only relevant for the `TryFrom<&[u8]>` implementations.
+    InvalidUtf8,
+    /// The ID exceeds 255 bytes (or 32 codepoints for a room version ID, or 43 characters for the
+    /// reference hash of an event ID parsed with `EventId::parse_for_version`.)
+    MaximumLengthExceeded,
+    /// The ID is less than 4 characters (or is an empty room version ID or device ID, or the
+    /// reference hash of an event ID parsed with `EventId::parse_for_version` is shorter than 43
+    /// characters.)
+    MinimumLengthNotSatisfied,
+    /// The third-party identifier has an empty address.
+    ///
+    /// Only relevant for third-party identifiers.
+    MissingAddress,
+    /// The ID is missing the colon delimiter between localpart and server name.
+    MissingDelimiter,
+    /// The ID has nothing between the leading sigil and the colon delimiter, or consists of nothing
+    /// but the sigil, like `@`.
+    MissingLocalpart,
+    /// The content URI is missing the media ID after the server name.
+    MissingMediaId,
+    /// The ID is missing the leading sigil.
+    MissingSigil,
+    /// The ID starts or ends with whitespace.
+    ///
+    /// Identifiers are never trimmed before parsing. Whitespace isn't allowed in any of them, but
+    /// it is reported separately because it is usually left over from copying an identifier.
+    SurroundingWhitespace,
+    /// The medium of a third-party identifier is neither `email` nor `msisdn`.
+    ///
+    /// Only relevant for third-party identifiers.
+    UnknownMedium,
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let message = match *self {
+            ErrorKind::InvalidAddress => "address is not valid for the medium",
+            ErrorKind::InvalidCharacters => "localpart contains invalid characters",
+            ErrorKind::InvalidHost => "server name is not a valid IP address or domain name",
+            ErrorKind::InvalidKeyId => "algorithm or key identifier is empty or invalid",
+            ErrorKind::InvalidPort => "port of the server name is not a valid number",
+            ErrorKind::InvalidUriScheme => "URI has an unexpected scheme",
+            ErrorKind::InvalidUriPath => "URI path does not contain the expected identifiers",
+            ErrorKind::InvalidUtf8 => "ID is not valid UTF-8",
+            ErrorKind::MaximumLengthExceeded => "ID exceeds 255 bytes",
+            ErrorKind::MinimumLengthNotSatisfied => "ID must be at least 4 characters",
+            ErrorKind::MissingAddress => "address is missing",
+            ErrorKind::MissingDelimiter => "colon is required between localpart and server name",
+            ErrorKind::MissingLocalpart => "localpart is missing",
+            ErrorKind::MissingMediaId => "media ID is missing",
+            ErrorKind::MissingSigil => "leading sigil is missing",
+            ErrorKind::SurroundingWhitespace => "ID starts or ends with whitespace",
+            ErrorKind::UnknownMedium => "medium is not a known third-party identifier medium",
+        };
+
+        write!(f, "{}", message)
+    }
+}
@@ -0,0 +1,36 @@
+//! Validation of event IDs.
+
+use crate::{
+    has_surrounding_whitespace, parse_id, validate_opaque_id, Error, ErrorKind, MAX_BYTES,
+    SIGIL_BYTES,
+};
+
+/// Checks an event ID in any of the formats with the same rules as `EventId::try_from` in
+/// ruma-identifiers.
+pub fn validate(event_id: &str) -> Result<(), Error> {
+    if has_surrounding_whitespace(event_id) {
+        Err(ErrorKind::SurroundingWhitespace.into())
+    } else if event_id.contains(':') {
+        let (localpart, _, _) = parse_id('$', event_id)?;
+        validate_opaque_id(localpart)?;
+
+        Ok(())
+    } else if !event_id.starts_with('$') {
+        Err(ErrorKind::MissingSigil.into())
+    } else {
+        Ok(validate_reference_hash(&event_id[SIGIL_BYTES..])?)
+    }
+}
+
+/// Checks the reference hash of an event ID in the formats of room version 3 and later.
+///
+/// Like any other identifier, the event ID including its sigil must not exceed `MAX_BYTES`.
+pub fn validate_reference_hash(hash: &str) -> Result<(), ErrorKind> {
+    if hash.is_empty() {
+        Err(ErrorKind::MissingLocalpart)
+    } else if SIGIL_BYTES + hash.len() > MAX_BYTES {
+        Err(ErrorKind::MaximumLengthExceeded)
+    } else {
+        validate_opaque_id(hash)
+    }
+}
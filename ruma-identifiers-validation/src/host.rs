@@ -0,0 +1,381 @@
+//! The hosts of server names, and parsing them into their canonical form.
+
+use alloc::{borrow::Cow, string::String};
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+use idna::AsciiDenyList;
+use percent_encoding::percent_decode_str;
+
+#[cfg(not(feature = "std"))]
+pub(crate) use core::net::{Ipv4Addr, Ipv6Addr};
+#[cfg(feature = "std")]
+pub(crate) use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// The host of a server name: a domain name, an IPv4 address or an IPv6 address.
+///
+/// The type parameter is the type of the domain name. It is a `String` for the hosts of server
+/// names, and a `&str` for hosts that borrow the domain name, like the ones returned by
+/// `resolution_target` in ruma-identifiers.
+///
+/// A host is displayed like in a server name, i.e. IPv6 addresses are enclosed in brackets.
+///
+/// ```
+/// # use std::net::Ipv6Addr;
+/// # use ruma_identifiers_validation::host::Host;
+/// let host: Host = Host::Ipv6(Ipv6Addr::LOCALHOST);
+/// assert_eq!(host.to_string(), "[::1]");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Host<S = String> {
+    /// A domain name, like `example.com`.
+    Domain(S),
+    /// An IPv4 address, like `127.0.0.1`.
+    Ipv4(Ipv4Addr),
+    /// An IPv6 address, like `::1`.
+    Ipv6(Ipv6Addr),
+}
+
+impl Host<&str> {
+    /// Copies the domain name of a host that borrows it.
+    pub fn to_owned(&self) -> Host {
+        match *self {
+            Host::Domain(domain) => Host::Domain(domain.into()),
+            Host::Ipv4(address) => Host::Ipv4(address),
+            Host::Ipv6(address) => Host::Ipv6(address),
+        }
+    }
+}
+
+impl<S: AsRef<str>> Display for Host<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Host::Domain(domain) => f.write_str(domain.as_ref()),
+            Host::Ipv4(address) => write!(f, "{}", address),
+            Host::Ipv6(address) => {
+                f.write_str("[")?;
+                write_ipv6(address, f)?;
+                f.write_str("]")
+            }
+        }
+    }
+}
+
+/// Writes an IPv6 address in its shortest form, i.e. in lowercase hexadecimal with the first of
+/// the longest runs of at least two zero segments compressed to `::`.
+///
+/// Unlike the `Display` implementation of `Ipv6Addr`, this writes IPv4-mapped addresses in
+/// hexadecimal as well, so `::ffff:1.2.3.4` is written as `::ffff:102:304`.
+fn write_ipv6(address: &Ipv6Addr, f: &mut Formatter<'_>) -> FmtResult {
+    let segments = address.segments();
+    let (start, end) = longest_zero_run(&segments);
+
+    if start == end {
+        return write_segments(&segments, f);
+    }
+
+    write_segments(&segments[..start], f)?;
+    f.write_str("::")?;
+    write_segments(&segments[end..], f)
+}
+
+/// Writes segments of an IPv6 address in hexadecimal, separated by colons.
+fn write_segments(segments: &[u16], f: &mut Formatter<'_>) -> FmtResult {
+    for (index, segment) in segments.iter().enumerate() {
+        if index > 0 {
+            f.write_str(":")?;
+        }
+
+        write!(f, "{:x}", segment)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the start and end index of the first of the longest runs of zero segments of an IPv6
+/// address, or an empty range if there is no run of at least two of them.
+fn longest_zero_run(segments: &[u16; 8]) -> (usize, usize) {
+    let mut longest = (0, 0);
+    let mut start = 0;
+
+    for (index, &segment) in segments.iter().enumerate() {
+        if segment != 0 {
+            start = index + 1;
+        } else if index + 1 - start > longest.1 - longest.0 {
+            longest = (start, index + 1);
+        }
+    }
+
+    if longest.1 - longest.0 < 2 {
+        (0, 0)
+    } else {
+        longest
+    }
+}
+
+/// Why a host or a port couldn't be parsed.
+///
+/// This is the source of the errors returned for such a server name.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum HostError {
+    /// The host is empty.
+    EmptyHost,
+    /// The domain name contains characters that can't be part of a host, or can't be converted to
+    /// its ASCII form.
+    InvalidDomain,
+    /// The host ends in a number, but isn't a valid IPv4 address.
+    InvalidIpv4Address,
+    /// The host is enclosed in brackets, but isn't a valid IPv6 address.
+    InvalidIpv6Address,
+    /// The port is not a number between 1 and 65535.
+    InvalidPort,
+}
+
+impl Display for HostError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let message = match self {
+            HostError::EmptyHost => "empty host",
+            HostError::InvalidDomain => "invalid domain name",
+            HostError::InvalidIpv4Address => "invalid IPv4 address",
+            HostError::InvalidIpv6Address => "invalid IPv6 address",
+            HostError::InvalidPort => "invalid port number",
+        };
+
+        f.write_str(message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HostError {}
+
+/// Parses a host into its canonical form, like the host of a URL as described by the WHATWG URL
+/// standard.
+///
+/// IPv6 addresses must be enclosed in brackets. Other hosts are percent-decoded and converted to
+/// their ASCII form as described by IDNA, which lowercases them and encodes non-ASCII labels as
+/// punycode. If the result ends in a number, it is an IPv4 address, which may also be written
+/// with fewer than four parts or with octal or hexadecimal numbers, so `127.1` and `0x7f.0.0.1`
+/// are both `127.0.0.1`. Otherwise, it is a domain name.
+pub fn parse(input: &str) -> Result<Host, HostError> {
+    if input.starts_with('[') {
+        if !input.ends_with(']') {
+            return Err(HostError::InvalidIpv6Address);
+        }
+
+        return input[1..input.len() - 1]
+            .parse()
+            .map(Host::Ipv6)
+            .map_err(|_| HostError::InvalidIpv6Address);
+    }
+
+    let decoded: Cow<'_, [u8]> = percent_decode_str(input).into();
+    let domain = idna::domain_to_ascii_from_cow(decoded, AsciiDenyList::URL)
+        .map_err(|_| HostError::InvalidDomain)?;
+
+    if domain.is_empty() {
+        return Err(HostError::EmptyHost);
+    }
+
+    if ends_in_a_number(&domain) {
+        parse_ipv4(&domain).map(Host::Ipv4)
+    } else {
+        Ok(Host::Domain(domain.into_owned()))
+    }
+}
+
+/// Whether the last label of a domain name in ASCII form, ignoring a trailing dot, is a number,
+/// which makes it an IPv4 address.
+fn ends_in_a_number(domain: &str) -> bool {
+    let mut labels = domain.rsplit('.');
+    let last = match labels.next() {
+        Some("") => labels.next(),
+        last => last,
+    };
+
+    match last {
+        Some(last) => {
+            (!last.is_empty() && last.bytes().all(|b| b.is_ascii_digit()))
+                || ipv4_number_digits(last).is_some()
+        }
+        None => false,
+    }
+}
+
+/// Parses an IPv4 address of one to four numbers separated by dots, optionally followed by a
+/// trailing dot.
+///
+/// All numbers but the last one are a single byte of the address, the last one fills the
+/// remaining bytes, so `127.1` is `127.0.0.1`.
+fn parse_ipv4(input: &str) -> Result<Ipv4Addr, HostError> {
+    let input = input.strip_suffix('.').unwrap_or(input);
+
+    let mut numbers = [0; 4];
+    let mut count = 0;
+    for part in input.split('.') {
+        if count == numbers.len() {
+            return Err(HostError::InvalidIpv4Address);
+        }
+
+        numbers[count] = parse_ipv4_number(part).ok_or(HostError::InvalidIpv4Address)?;
+        count += 1;
+    }
+
+    let (last, bytes) = (numbers[count - 1], &numbers[..count - 1]);
+    if bytes.iter().any(|&byte| byte > 255) || u64::from(last) >= 1 << (8 * (5 - count)) {
+        return Err(HostError::InvalidIpv4Address);
+    }
+
+    let address = bytes
+        .iter()
+        .enumerate()
+        .fold(last, |address, (index, &byte)| {
+            address | byte << (8 * (3 - index))
+        });
+
+    Ok(Ipv4Addr::from(address))
+}
+
+/// Parses a number of an IPv4 address, returning `None` if it isn't a number or doesn't fit into
+/// 32 bits.
+fn parse_ipv4_number(part: &str) -> Option<u32> {
+    let (digits, radix) = ipv4_number_digits(part)?;
+
+    if digits.is_empty() {
+        Some(0)
+    } else {
+        u32::from_str_radix(digits, radix).ok()
+    }
+}
+
+/// Splits a number of an IPv4 address into its digits and their radix, or returns `None` if it
+/// isn't a number.
+///
+/// Numbers with a `0x` prefix are hexadecimal, other numbers with a leading zero are octal.
+fn ipv4_number_digits(part: &str) -> Option<(&str, u32)> {
+    if part.is_empty() {
+        return None;
+    }
+
+    let (digits, radix) = if part.starts_with("0x") || part.starts_with("0X") {
+        (&part[2..], 16)
+    } else if part.len() > 1 && part.starts_with('0') {
+        (&part[1..], 8)
+    } else {
+        (part, 10)
+    };
+
+    if digits.chars().all(|c| c.is_digit(radix)) {
+        Some((digits, radix))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use super::{parse, Host, HostError};
+
+    #[test]
+    fn parse_domain() {
+        assert_eq!(
+            parse("example.com"),
+            Ok(Host::Domain("example.com".to_string()))
+        );
+        assert_eq!(
+            parse("EXAMPLE.com"),
+            Ok(Host::Domain("example.com".to_string()))
+        );
+        assert_eq!(
+            parse("%65xample.com"),
+            Ok(Host::Domain("example.com".to_string()))
+        );
+        assert_eq!(
+            parse("café.example"),
+            Ok(Host::Domain("xn--caf-dma.example".to_string()))
+        );
+        assert_eq!(parse(""), Err(HostError::EmptyHost));
+        assert_eq!(parse("exa mple.com"), Err(HostError::InvalidDomain));
+        assert_eq!(parse("example.com:5000"), Err(HostError::InvalidDomain));
+    }
+
+    #[test]
+    fn parse_ipv4_address() {
+        for host in &[
+            "127.0.0.1",
+            "127.0.0.1.",
+            "127.1",
+            "127.0.1",
+            "2130706433",
+            "0x7f.0.0.1",
+            "0X7F.1",
+            "0177.0.0.1",
+            "127.0x0.0.0001",
+        ] {
+            assert_eq!(parse(host), Ok(Host::Ipv4(Ipv4Addr::new(127, 0, 0, 1))));
+        }
+
+        assert_eq!(parse("0x"), Ok(Host::Ipv4(Ipv4Addr::UNSPECIFIED)));
+    }
+
+    #[test]
+    fn parse_invalid_ipv4_address() {
+        for host in &[
+            "256.0.0.1",
+            "1.2.3.256",
+            "1.2.65536",
+            "1.2.3.4.5",
+            "4294967296",
+            "0x100000000",
+            "08.0.0.1",
+            "example.0x1",
+            "example.1",
+        ] {
+            assert_eq!(parse(host), Err(HostError::InvalidIpv4Address));
+        }
+    }
+
+    #[test]
+    fn parse_ipv6_address() {
+        assert_eq!(parse("[::1]"), Ok(Host::Ipv6(Ipv6Addr::LOCALHOST)));
+        assert_eq!(parse("[0:0::1]"), Ok(Host::Ipv6(Ipv6Addr::LOCALHOST)));
+        assert_eq!(
+            parse("[::FFFF:1.2.3.4]"),
+            Ok(Host::Ipv6(Ipv6Addr::new(
+                0, 0, 0, 0, 0, 0xffff, 0x102, 0x304
+            )))
+        );
+
+        for host in &["[", "[]", "[::1", "[::1::2]", "[example.com]", "[1.2.3.4]"] {
+            assert_eq!(parse(host), Err(HostError::InvalidIpv6Address));
+        }
+    }
+
+    #[test]
+    fn display_host() {
+        let display = |host: &str| parse(host).expect("Failed to parse host.").to_string();
+
+        assert_eq!(display("Example.com"), "example.com");
+        assert_eq!(display("127.1"), "127.0.0.1");
+        assert_eq!(display("[0:0:0:0:0:0:0:0]"), "[::]");
+        assert_eq!(display("[1:0:0:0:0:0:0:0]"), "[1::]");
+        assert_eq!(display("[1:0:0:1:0:0:0:1]"), "[1:0:0:1::1]");
+        assert_eq!(display("[1:0:0:2:0:0:3:4]"), "[1::2:0:0:3:4]");
+        assert_eq!(display("[1:0:2:3:4:5:6:7]"), "[1:0:2:3:4:5:6:7]");
+        assert_eq!(display("[2001:DB8::1]"), "[2001:db8::1]");
+        assert_eq!(display("[::ffff:1.2.3.4]"), "[::ffff:102:304]");
+        assert_eq!(Host::Domain("example.com").to_string(), "example.com");
+    }
+
+    #[test]
+    fn borrowed_host_to_owned() {
+        assert_eq!(
+            Host::Domain("example.com").to_owned(),
+            Host::Domain("example.com".to_string())
+        );
+        assert_eq!(
+            Host::<&str>::Ipv6(Ipv6Addr::LOCALHOST).to_owned(),
+            Host::Ipv6(Ipv6Addr::LOCALHOST)
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Crate **ruma_identifiers_validation** contains the rules that
+//! [ruma-identifiers](https://docs.rs/ruma-identifiers) applies when parsing identifiers.
+//!
+//! They are kept in a separate crate so that the macros of ruma-identifiers can check identifier
+//! literals at compile time with exactly the same rules as its `TryFrom` implementations. The
+//! functions of this crate only validate and don't construct identifiers, so most users want
+//! ruma-identifiers instead.
+//!
+//! Without the `std` feature, which is enabled by default, the crate is `no_std` and only requires
+//! `alloc`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(rust_2018_idioms)]
+#![deny(
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs
+)]
+
+extern crate alloc;
+
+use core::fmt::{Display, Result as FmtResult, Write};
+
+pub use crate::error::{Error, ErrorKind};
+
+use crate::host::Host;
+
+mod error;
+pub mod event_id;
+pub mod host;
+pub mod room_alias_id;
+pub mod room_id;
+pub mod server_name;
+pub mod user_id;
+
+/// The maximum length of an identifier in bytes, as required by the Matrix specification.
+///
+/// The limit applies to the complete identifier, including the sigil and the server name, and is
+/// checked by all identifier types when parsing or composing one. `byte_len` returns the length to
+/// compare it with, e.g. before deriving a longer identifier from an existing one.
+pub const MAX_BYTES: usize = 255;
+/// The minimum length of an identifier with a server name in bytes.
+///
+/// This isn't required by the spec, but follows from it: the shortest possible valid ID consists of
+/// a sigil, a single character localpart, a colon and a single character hostname. Identifiers
+/// that are shorter are rejected with `ErrorKind::MinimumLengthNotSatisfied` before being parsed
+/// any further, unless they consist of only the sigil.
+pub const MIN_CHARS: usize = 4;
+/// The length of the sigil of an identifier in bytes.
+///
+/// All sigils are single ASCII characters, so the localpart of an identifier starts at this byte
+/// offset.
+pub const SIGIL_BYTES: usize = 1;
+/// The port of a server name that doesn't specify one.
+///
+/// This is the default port of the Matrix federation API. It is omitted from the string
+/// representation of server names and IDs.
+pub const DEFAULT_PORT: u16 = 8448;
+
+/// Returns the length of the `Display` output of a value in bytes without allocating.
+fn display_len(value: &impl Display) -> usize {
+    /// A writer that only counts the bytes written to it.
+    struct Counter(usize);
+
+    impl Write for Counter {
+        fn write_str(&mut self, s: &str) -> FmtResult {
+            self.0 += s.len();
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    // Writing to a `Counter` never fails.
+    let _ = write!(counter, "{}", value);
+    counter.0
+}
+
+/// Checks if an identifier is within the acceptable byte lengths.
+pub fn validate_id(id: &str) -> Result<(), ErrorKind> {
+    if has_surrounding_whitespace(id) {
+        return Err(ErrorKind::SurroundingWhitespace);
+    }
+
+    if id.len() > MAX_BYTES {
+        return Err(ErrorKind::MaximumLengthExceeded);
+    }
+
+    if id.len() < MIN_CHARS {
+        return Err(ErrorKind::MinimumLengthNotSatisfied);
+    }
+
+    Ok(())
+}
+
+/// Checks whether a string consists of nothing but the given sigil.
+pub fn is_sigil_only(sigil: char, id: &str) -> bool {
+    id.len() == SIGIL_BYTES && id.starts_with(sigil)
+}
+
+/// Checks whether a string starts or ends with whitespace.
+pub fn has_surrounding_whitespace(s: &str) -> bool {
+    s.starts_with(char::is_whitespace) || s.ends_with(char::is_whitespace)
+}
+
+/// Splits a string identifier into its localpart and the unparsed server name.
+pub fn split_id(required_sigil: char, id: &str) -> Result<(&str, &str), ErrorKind> {
+    // Checked before the minimum length, which would be violated as well, but is less precise.
+    if is_sigil_only(required_sigil, id) {
+        return Err(ErrorKind::MissingLocalpart);
+    }
+
+    validate_id(id)?;
+
+    // The localpart is sliced out after `SIGIL_BYTES`, which is only a character boundary if the
+    // sigil is ASCII. All Matrix sigils are, but `parse_id` accepts any character.
+    if !required_sigil.is_ascii() || !id.starts_with(required_sigil) {
+        return Err(ErrorKind::MissingSigil);
+    }
+
+    // Localparts can't contain colons, so the first one is the delimiter, even if the server name
+    // is an IPv6 literal with colons of its own. `find` returns a byte offset, so slicing at it
+    // can't split a multibyte character.
+    let delimiter_index = match id.find(':') {
+        Some(index) => index,
+        None => return Err(ErrorKind::MissingDelimiter),
+    };
+
+    let localpart = &id[SIGIL_BYTES..delimiter_index];
+    if localpart.is_empty() {
+        return Err(ErrorKind::MissingLocalpart);
+    }
+
+    Ok((localpart, &id[delimiter_index + 1..]))
+}
+
+/// Splits a string identifier into its localpart and server name, parsing the host and the
+/// explicitly specified port, if any, of the server name.
+///
+/// The localpart is only checked to be non-empty; the identifier types apply the rules for their
+/// localparts separately.
+pub fn parse_id(required_sigil: char, id: &str) -> Result<(&str, Host, Option<u16>), Error> {
+    let (localpart, server_name) = split_id(required_sigil, id)?;
+    let (host, port) = server_name::parse(server_name)?;
+
+    // Normalizing the server name can make the identifier longer than it was given, e.g. `1.1`
+    // is written as `1.0.0.1` and `é.example` is encoded as `xn--9ca.example`, so the limit is
+    // checked against the canonical form as well, which omits the default port.
+    let port_len = match port {
+        Some(port) if port != DEFAULT_PORT => 1 + display_len(&port),
+        _ => 0,
+    };
+    if SIGIL_BYTES + localpart.len() + 1 + display_len(&host) + port_len > MAX_BYTES {
+        return Err(ErrorKind::MaximumLengthExceeded.into());
+    }
+
+    Ok((localpart, host, port))
+}
+
+/// Checks the opaque localpart of a room ID or an event ID in the original format.
+///
+/// Any character is permitted, including non-ASCII ones, except for the colon that separates the
+/// localpart from the server name, whitespace, like spaces and tabs, and control characters, like
+/// newlines.
+pub fn validate_opaque_id(opaque_id: &str) -> Result<(), ErrorKind> {
+    if opaque_id
+        .chars()
+        .any(|c| c == ':' || c.is_whitespace() || c.is_control())
+    {
+        return Err(ErrorKind::InvalidCharacters);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{host::Host, parse_id, ErrorKind};
+
+    #[test]
+    fn parse_id_with_port() {
+        assert_eq!(
+            parse_id('@', "@carl:Example.com:5000"),
+            Ok(("carl", Host::Domain("example.com".to_string()), Some(5000)))
+        );
+        assert_eq!(
+            parse_id('@', "@carl:example.com"),
+            Ok(("carl", Host::Domain("example.com".to_string()), None))
+        );
+    }
+
+    #[test]
+    fn parse_id_errors() {
+        let kind = |id| parse_id('@', id).unwrap_err().kind();
+
+        assert_eq!(kind("@"), ErrorKind::MissingLocalpart);
+        assert_eq!(kind("carl:example.com"), ErrorKind::MissingSigil);
+        assert_eq!(kind("@carl"), ErrorKind::MissingDelimiter);
+        assert_eq!(kind("@carl:example.com/path"), ErrorKind::InvalidHost);
+        assert_eq!(kind("@carl:example.com:0"), ErrorKind::InvalidPort);
+    }
+
+    #[test]
+    fn canonical_length_is_checked() {
+        let parse = |len, server_name| {
+            parse_id('@', &format!("@{}:{}", "a".repeat(len), server_name))
+                .map_err(|err| err.kind())
+                .map(|_| ())
+        };
+
+        // `1.1` is written as `1.0.0.1`, and only ports other than the default one are kept.
+        assert_eq!(parse(246, "1.1"), Ok(()));
+        assert_eq!(parse(247, "1.1"), Err(ErrorKind::MaximumLengthExceeded));
+        assert_eq!(parse(241, "1.0.0.1:8448"), Ok(()));
+        assert_eq!(
+            parse(242, "1.1:5000"),
+            Err(ErrorKind::MaximumLengthExceeded)
+        );
+    }
+}
@@ -0,0 +1,27 @@
+//! Validation of room alias IDs.
+
+use crate::{parse_id, Error, ErrorKind};
+
+/// Checks a room alias ID with the same rules as `RoomAliasId::try_from` in ruma-identifiers.
+pub fn validate(room_alias_id: &str) -> Result<(), Error> {
+    let (alias, _, _) = parse_id('#', room_alias_id)?;
+    validate_alias(alias)?;
+
+    Ok(())
+}
+
+/// Checks the characters of a room alias.
+///
+/// Any printable character is permitted, including non-ASCII ones, except for the colon that
+/// separates the alias from the server name. Whitespace, like spaces and tabs, and control
+/// characters, like newlines, are rejected.
+pub fn validate_alias(alias: &str) -> Result<(), ErrorKind> {
+    if alias
+        .chars()
+        .any(|c| c == ':' || c.is_whitespace() || c.is_control())
+    {
+        return Err(ErrorKind::InvalidCharacters);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,11 @@
+//! Validation of room IDs.
+
+use crate::{parse_id, validate_opaque_id, Error};
+
+/// Checks a room ID with the same rules as `RoomId::try_from` in ruma-identifiers.
+pub fn validate(room_id: &str) -> Result<(), Error> {
+    let (localpart, _, _) = parse_id('!', room_id)?;
+    validate_opaque_id(localpart)?;
+
+    Ok(())
+}
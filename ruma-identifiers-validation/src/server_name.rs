@@ -0,0 +1,137 @@
+//! Validation of server names.
+
+use alloc::string::String;
+
+use crate::{
+    has_surrounding_whitespace,
+    host::{self, Host, HostError},
+    Error, ErrorKind,
+};
+
+/// The maximum length of a domain name in bytes, excluding the trailing dot of a fully qualified
+/// one, as limited by DNS.
+pub const MAX_DOMAIN_BYTES: usize = 253;
+/// The maximum length of a single label of a domain name in bytes, as limited by DNS.
+const MAX_DOMAIN_LABEL_BYTES: usize = 63;
+
+/// Parses a server name into its host and the explicitly specified port, if any.
+///
+/// This applies exactly the same rules as `ServerName::try_from` in ruma-identifiers.
+///
+/// ```
+/// # use ruma_identifiers_validation::{host::Host, server_name};
+/// let (host, port) = server_name::parse("Example.com:5000").unwrap();
+/// assert_eq!(host, Host::Domain("example.com".to_string()));
+/// assert_eq!(port, Some(5000));
+/// ```
+pub fn parse(server_name: &str) -> Result<(Host, Option<u16>), Error> {
+    if has_surrounding_whitespace(server_name) {
+        return Err(ErrorKind::SurroundingWhitespace.into());
+    }
+
+    if server_name.chars().any(is_forbidden_in_server_name) {
+        return Err(ErrorKind::InvalidHost.into());
+    }
+
+    // The host might be an IPv6 literal containing colons itself, so the port follows the colon
+    // after its closing bracket.
+    let (raw_host, raw_port) = if server_name.starts_with('[') {
+        match server_name.find(']') {
+            // Zone identifiers, like in `[fe80::1%25eth0]`, only have a meaning on the host that
+            // assigned them, so they can't be part of a server name. They are rejected explicitly
+            // rather than relying on the host parser to do so.
+            Some(index) if server_name[..index].contains('%') => {
+                return Err(ErrorKind::InvalidHost.into())
+            }
+            Some(index) => server_name.split_at(index + 1),
+            None => return Err(HostError::InvalidIpv6Address.into()),
+        }
+    } else {
+        match server_name.find(':') {
+            Some(index) => server_name.split_at(index),
+            None => (server_name, ""),
+        }
+    };
+
+    let host = parse_host(raw_host)?;
+
+    let port = if raw_port.is_empty() {
+        None
+    } else {
+        match parse_port(raw_port) {
+            Some(port) => Some(port),
+            None => return Err(HostError::InvalidPort.into()),
+        }
+    };
+
+    Ok((host, port))
+}
+
+/// Parses the host of a server name into its canonical form.
+///
+/// Domain names are converted to their ASCII form as described by IDNA, i.e. lowercased, with
+/// non-ASCII labels encoded as punycode, so `Café.example` becomes `xn--caf-dma.example`, the same
+/// host as if it had been given in that form. The trailing dot of fully qualified domain names is
+/// removed.
+///
+/// The host parser doesn't limit the length of domain names, so the limits of DNS are checked
+/// against the ASCII form afterwards.
+pub fn parse_host(raw_host: &str) -> Result<Host, Error> {
+    let mut host = host::parse(raw_host)?;
+
+    if let Host::Domain(domain) = &mut host {
+        if !strip_root_label(domain) || !is_valid_dns_length(domain) {
+            return Err(ErrorKind::InvalidHost.into());
+        }
+    }
+
+    Ok(host)
+}
+
+/// Removes the trailing dot of a fully qualified domain name, so that e.g. `example.com.` and
+/// `example.com` are the same host.
+///
+/// Returns `false` without changing the domain if it is only a dot or ends with more than one dot.
+fn strip_root_label(domain: &mut String) -> bool {
+    if domain.ends_with('.') {
+        if domain.len() == 1 || domain.ends_with("..") {
+            return false;
+        }
+
+        domain.pop();
+    }
+
+    true
+}
+
+/// Whether a domain name without a trailing dot fits into DNS, i.e. it is at most 253 bytes long
+/// and none of its labels is longer than 63 bytes.
+fn is_valid_dns_length(domain: &str) -> bool {
+    domain.len() <= MAX_DOMAIN_BYTES
+        && domain
+            .split('.')
+            .all(|label| label.len() <= MAX_DOMAIN_LABEL_BYTES)
+}
+
+/// Whether a character can never be part of a server name.
+///
+/// None of these can be part of a host or port, and some of them would otherwise be interpreted or
+/// stripped by the host parser.
+pub fn is_forbidden_in_server_name(c: char) -> bool {
+    match c {
+        '/' | '\\' | '?' | '#' | '@' => true,
+        _ => c.is_whitespace() || c.is_control(),
+    }
+}
+
+/// Parses a port including its leading colon.
+///
+/// Port 0 can't be connected to, so it is rejected as well.
+fn parse_port(raw_port: &str) -> Option<u16> {
+    let digits = raw_port.strip_prefix(':')?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    digits.parse().ok().filter(|&port| port != 0)
+}
@@ -0,0 +1,32 @@
+//! Validation of user IDs.
+
+use crate::{parse_id, Error, ErrorKind};
+
+/// Checks a user ID with the same rules as `UserId::try_from` in ruma-identifiers.
+pub fn validate(user_id: &str) -> Result<(), Error> {
+    let (localpart, _, _) = parse_id('@', user_id)?;
+    validate_localpart(localpart)?;
+
+    Ok(())
+}
+
+/// Checks the characters of a user ID localpart, ignoring case, and returns whether it is a
+/// historical one.
+pub fn validate_localpart(localpart: &str) -> Result<bool, ErrorKind> {
+    // See https://matrix.org/docs/spec/appendices#user-identifiers
+    let is_fully_conforming = localpart.bytes().all(|b| {
+        matches!(
+            b.to_ascii_lowercase(),
+            b'0'..=b'9' | b'a'..=b'z' | b'-' | b'.' | b'=' | b'_' | b'/'
+        )
+    });
+
+    // If it's not fully conforming, check if it contains characters that are also disallowed
+    // for historical user IDs. If there are, return an error.
+    // See https://matrix.org/docs/spec/appendices#historical-user-ids
+    if !is_fully_conforming && localpart.bytes().any(|b| b < 0x21 || b == b':' || b > 0x7E) {
+        return Err(ErrorKind::InvalidCharacters);
+    }
+
+    Ok(!is_fully_conforming)
+}
@@ -0,0 +1,2 @@
+//! Tests of the `sqlx` feature of ruma-identifiers, which round-trip identifiers through an SQLite
+//! database. See `tests/sqlite.rs`.
@@ -0,0 +1,101 @@
+//! Round-trips identifiers through an SQLite database with sqlx.
+
+use std::convert::TryFrom;
+
+use ruma_identifiers::{EventId, RoomAliasId, RoomId, RoomVersionId, UserId};
+use sqlx::{query, query_as, query_scalar, Connection, SqliteConnection};
+
+async fn connection() -> SqliteConnection {
+    let mut connection = SqliteConnection::connect("sqlite::memory:")
+        .await
+        .expect("Failed to open SQLite database.");
+    query(
+        "CREATE TABLE identifiers (
+            id INTEGER PRIMARY KEY NOT NULL,
+            event_id TEXT NOT NULL,
+            room_id TEXT NOT NULL,
+            room_alias_id TEXT NOT NULL,
+            user_id TEXT NOT NULL,
+            room_version_id TEXT NOT NULL
+        )",
+    )
+    .execute(&mut connection)
+    .await
+    .expect("Failed to create table.");
+
+    connection
+}
+
+#[tokio::test]
+async fn round_trip_through_sqlite() {
+    let mut connection = connection().await;
+
+    let event_id =
+        EventId::try_from("$39hvsi03hlne:example.com").expect("Failed to create EventId.");
+    let room_id = RoomId::try_from("!29fhd83h92h0:example.com").expect("Failed to create RoomId.");
+    let room_alias_id =
+        RoomAliasId::try_from("#ruma:example.com").expect("Failed to create RoomAliasId.");
+    let user_id = UserId::try_from("@carl:example.com:5000").expect("Failed to create UserId.");
+    let room_version_id = RoomVersionId::version_5();
+
+    query(
+        "INSERT INTO identifiers (event_id, room_id, room_alias_id, user_id, room_version_id)
+        VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&event_id)
+    .bind(&room_id)
+    .bind(room_alias_id.clone())
+    .bind(user_id.clone())
+    .bind(&room_version_id)
+    .execute(&mut connection)
+    .await
+    .expect("Failed to insert identifiers.");
+
+    let loaded = query_as::<_, (EventId, RoomId, RoomAliasId, UserId, RoomVersionId)>(
+        "SELECT event_id, room_id, room_alias_id, user_id, room_version_id FROM identifiers",
+    )
+    .fetch_one(&mut connection)
+    .await
+    .expect("Failed to load identifiers.");
+
+    assert_eq!(
+        loaded,
+        (event_id, room_id, room_alias_id, user_id, room_version_id)
+    );
+    assert_eq!(
+        query_scalar::<_, String>("SELECT user_id FROM identifiers")
+            .fetch_one(&mut connection)
+            .await
+            .expect("Failed to load user ID."),
+        "@carl:example.com:5000"
+    );
+}
+
+#[tokio::test]
+async fn loading_invalid_identifier_fails() {
+    let mut connection = connection().await;
+
+    query(
+        "INSERT INTO identifiers (event_id, room_id, room_alias_id, user_id, room_version_id)
+        VALUES ('$39hvsi03hlne:example.com', 'not a room ID', '#ruma:example.com',
+            '@carl:example.com', '5')",
+    )
+    .execute(&mut connection)
+    .await
+    .expect("Failed to insert identifiers.");
+
+    let error = query_scalar::<_, RoomId>("SELECT room_id FROM identifiers")
+        .fetch_one(&mut connection)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, sqlx::Error::ColumnDecode { .. }));
+    assert!(error.to_string().contains("not a room ID"));
+    assert_eq!(
+        query_scalar::<_, UserId>("SELECT user_id FROM identifiers")
+            .fetch_one(&mut connection)
+            .await
+            .expect("Failed to load UserId."),
+        UserId::try_from("@carl:example.com").expect("Failed to create UserId.")
+    );
+}
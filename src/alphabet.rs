@@ -0,0 +1,147 @@
+//! Character sets for generated identifiers.
+
+use alloc::string::String;
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::{distributions::Alphanumeric as AlphanumericDistribution, Rng};
+
+/// The lowercase ASCII letters and digits.
+const LOWERCASE_ALPHANUMERIC: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Crockford's Base32 alphabet in lowercase, which leaves out i, l, o and u.
+const BASE32_UNAMBIGUOUS: &[u8] = b"0123456789abcdefghjkmnpqrstvwxyz";
+
+/// The number of Base32 characters encoding the timestamp of a sortable localpart, which is enough
+/// for 50 bits of milliseconds, i.e. more than 35,000 years after 1970.
+#[cfg(feature = "std")]
+const TIMESTAMP_CHARS: usize = 10;
+
+/// The number of random Base32 characters following the timestamp of a sortable localpart.
+#[cfg(feature = "std")]
+const SORTABLE_RANDOM_CHARS: usize = 16;
+
+/// The set of characters the random localparts of generated identifiers are drawn from.
+///
+/// Each character of a localpart is drawn uniformly from the alphabet, so a smaller alphabet needs
+/// a longer localpart for the same number of possible identifiers. All alphabets only contain
+/// characters that are valid in every identifier.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Alphabet {
+    /// The ASCII letters and digits `[A-Za-z0-9]`, which is what the `new` constructors use.
+    ///
+    /// User IDs are downcased after generating them, so this is equivalent to
+    /// `LowercaseAlphanumeric` for them, except that lowercase letters are twice as likely.
+    #[default]
+    Alphanumeric,
+    /// The lowercase ASCII letters and digits `[a-z0-9]`.
+    LowercaseAlphanumeric,
+    /// Crockford's Base32 alphabet in lowercase, i.e. the lowercase ASCII letters and digits
+    /// except for `i`, `l`, `o` and `u`, which are easily confused with `1` and `0` or each other.
+    Base32Unambiguous,
+}
+
+impl Alphabet {
+    /// Generates a string of `length` random characters from the alphabet.
+    pub(crate) fn generate(self, rng: &mut impl Rng, length: usize) -> String {
+        let chars = match self {
+            Alphabet::Alphanumeric => {
+                return (0..length)
+                    .map(|_| rng.sample(AlphanumericDistribution))
+                    .collect()
+            }
+            Alphabet::LowercaseAlphanumeric => LOWERCASE_ALPHANUMERIC,
+            Alphabet::Base32Unambiguous => BASE32_UNAMBIGUOUS,
+        };
+
+        (0..length)
+            .map(|_| chars[rng.gen_range(0, chars.len())] as char)
+            .collect()
+    }
+}
+
+/// Generates a localpart that starts with the given time as milliseconds since the Unix epoch,
+/// followed by random characters, both encoded with `Alphabet::Base32Unambiguous`.
+///
+/// This is the layout of a ULID in lowercase. Since the alphabet is in ASCII order and the
+/// timestamp has a fixed width, localparts generated at later milliseconds sort after earlier ones.
+/// Times before the epoch are encoded as the epoch itself.
+#[cfg(feature = "std")]
+pub(crate) fn generate_sortable_localpart(rng: &mut impl Rng, time: SystemTime) -> String {
+    let mut millis = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or(0);
+    let mut timestamp = [0; TIMESTAMP_CHARS];
+
+    for digit in timestamp.iter_mut().rev() {
+        *digit = BASE32_UNAMBIGUOUS[(millis % 32) as usize];
+        millis /= 32;
+    }
+
+    let mut localpart = String::with_capacity(TIMESTAMP_CHARS + SORTABLE_RANDOM_CHARS);
+    localpart.extend(timestamp.iter().map(|&digit| digit as char));
+    localpart.push_str(&Alphabet::Base32Unambiguous.generate(rng, SORTABLE_RANDOM_CHARS));
+
+    localpart
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{
+        generate_sortable_localpart, Alphabet, BASE32_UNAMBIGUOUS, LOWERCASE_ALPHANUMERIC,
+    };
+
+    #[test]
+    fn generate_with_alphabets() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let alphanumeric = Alphabet::Alphanumeric.generate(&mut rng, 100);
+        assert_eq!(alphanumeric.len(), 100);
+        assert!(alphanumeric.bytes().all(|b| b.is_ascii_alphanumeric()));
+
+        let lowercase = Alphabet::LowercaseAlphanumeric.generate(&mut rng, 100);
+        assert_eq!(lowercase.len(), 100);
+        assert!(lowercase
+            .bytes()
+            .all(|b| LOWERCASE_ALPHANUMERIC.contains(&b)));
+
+        let base32 = Alphabet::Base32Unambiguous.generate(&mut rng, 100);
+        assert_eq!(base32.len(), 100);
+        assert!(base32.bytes().all(|b| BASE32_UNAMBIGUOUS.contains(&b)));
+        assert!(!base32.contains(['i', 'l', 'o', 'u']));
+    }
+
+    #[test]
+    fn sortable_localpart_timestamps() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let epoch = generate_sortable_localpart(&mut rng, UNIX_EPOCH);
+        assert_eq!(epoch.len(), 26);
+        assert!(epoch.starts_with("0000000000"));
+
+        let localpart = generate_sortable_localpart(
+            &mut rng,
+            UNIX_EPOCH + Duration::from_millis(1_469_922_850_259),
+        );
+        assert!(localpart.starts_with("01arz3ndek"));
+        assert!(localpart.bytes().all(|b| BASE32_UNAMBIGUOUS.contains(&b)));
+    }
+
+    #[test]
+    fn sortable_localparts_sort_by_time() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut previous = generate_sortable_localpart(&mut rng, UNIX_EPOCH);
+
+        for &millis in &[1, 31, 32, 1_000, 1_469_922_850_259, 1_u64 << 49] {
+            let localpart =
+                generate_sortable_localpart(&mut rng, UNIX_EPOCH + Duration::from_millis(millis));
+            assert!(localpart > previous);
+            previous = localpart;
+        }
+    }
+}
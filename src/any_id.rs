@@ -0,0 +1,337 @@
+//! Matrix identifiers of any kind, for places where the kind is only known from the sigil.
+
+use alloc::string::String;
+use core::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    deserialize_id,
+    error::{Error, ErrorKind},
+    event_id::EventId,
+    group_id::GroupId,
+    has_surrounding_whitespace,
+    room_alias_id::RoomAliasId,
+    room_id::RoomId,
+    user_id::UserId,
+};
+
+/// A Matrix identifier of any of the kinds that have a sigil.
+///
+/// `AnyId` is useful for logging or routing identifiers taken from fields that may contain any
+/// kind of identifier. It is converted from a string slice, and can be converted back into a
+/// string as needed. When converted from a string slice, the variant is determined by the leading
+/// sigil character.
+///
+/// ```
+/// # use ruma_identifiers::{parse_any, AnyId};
+/// match parse_any("@carl:example.com").unwrap() {
+///     AnyId::UserId(user_id) => assert_eq!(user_id.localpart(), "carl"),
+///     _ => panic!("Expected a user ID."),
+/// }
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum AnyId {
+    /// A Matrix event ID, with the `$` sigil.
+    EventId(EventId),
+    /// A Matrix group ID, with the `+` sigil.
+    GroupId(GroupId),
+    /// A Matrix room alias ID, with the `#` sigil.
+    RoomAliasId(RoomAliasId),
+    /// A Matrix room ID, with the `!` sigil.
+    RoomId(RoomId),
+    /// A Matrix user ID, with the `@` sigil.
+    UserId(UserId),
+}
+
+impl AnyId {
+    /// Returns the identifier as a string slice.
+    pub fn as_str(&self) -> &str {
+        match self {
+            AnyId::EventId(event_id) => event_id.as_str(),
+            AnyId::GroupId(group_id) => group_id.as_str(),
+            AnyId::RoomAliasId(room_alias_id) => room_alias_id.as_str(),
+            AnyId::RoomId(room_id) => room_id.as_str(),
+            AnyId::UserId(user_id) => user_id.as_str(),
+        }
+    }
+
+    /// Converts the identifier into its string representation, without allocating.
+    pub fn into_string(self) -> String {
+        match self {
+            AnyId::EventId(event_id) => event_id.into_string(),
+            AnyId::GroupId(group_id) => group_id.into_string(),
+            AnyId::RoomAliasId(room_alias_id) => room_alias_id.into_string(),
+            AnyId::RoomId(room_id) => room_id.into_string(),
+            AnyId::UserId(user_id) => user_id.into_string(),
+        }
+    }
+
+    /// Returns the sigil of the identifier, which determines its kind.
+    pub fn sigil(&self) -> char {
+        match self {
+            AnyId::EventId(_) => EventId::SIGIL,
+            AnyId::GroupId(_) => GroupId::SIGIL,
+            AnyId::RoomAliasId(_) => RoomAliasId::SIGIL,
+            AnyId::RoomId(_) => RoomId::SIGIL,
+            AnyId::UserId(_) => UserId::SIGIL,
+        }
+    }
+}
+
+impl Display for AnyId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            AnyId::EventId(event_id) => event_id.fmt(f),
+            AnyId::GroupId(group_id) => group_id.fmt(f),
+            AnyId::RoomAliasId(room_alias_id) => room_alias_id.fmt(f),
+            AnyId::RoomId(room_id) => room_id.fmt(f),
+            AnyId::UserId(user_id) => user_id.fmt(f),
+        }
+    }
+}
+
+impl PartialOrd for AnyId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Identifiers of any kind are ordered lexicographically by their string representation, so they
+/// are grouped by their sigil.
+impl Ord for AnyId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Serialize for AnyId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for AnyId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_id(deserializer, "a Matrix identifier as a string")
+    }
+}
+
+impl From<EventId> for AnyId {
+    fn from(event_id: EventId) -> Self {
+        AnyId::EventId(event_id)
+    }
+}
+
+impl From<GroupId> for AnyId {
+    fn from(group_id: GroupId) -> Self {
+        AnyId::GroupId(group_id)
+    }
+}
+
+impl From<RoomAliasId> for AnyId {
+    fn from(room_alias_id: RoomAliasId) -> Self {
+        AnyId::RoomAliasId(room_alias_id)
+    }
+}
+
+impl From<RoomId> for AnyId {
+    fn from(room_id: RoomId) -> Self {
+        AnyId::RoomId(room_id)
+    }
+}
+
+impl From<UserId> for AnyId {
+    fn from(user_id: UserId) -> Self {
+        AnyId::UserId(user_id)
+    }
+}
+
+/// Parses a borrowed or owned identifier of any kind, depending on its leading sigil.
+fn parse_any_id<S>(id: S) -> Result<AnyId, Error>
+where
+    S: AsRef<str>,
+    EventId: TryFrom<S, Error = Error>,
+    GroupId: TryFrom<S, Error = Error>,
+    RoomAliasId: TryFrom<S, Error = Error>,
+    RoomId: TryFrom<S, Error = Error>,
+    UserId: TryFrom<S, Error = Error>,
+{
+    let raw_id = id.as_ref();
+
+    if has_surrounding_whitespace(raw_id) {
+        return Err(Error::new(ErrorKind::SurroundingWhitespace, raw_id));
+    }
+
+    match raw_id.chars().next() {
+        Some('$') => EventId::try_from(id).map(AnyId::EventId),
+        Some('+') => GroupId::try_from(id).map(AnyId::GroupId),
+        Some('#') => RoomAliasId::try_from(id).map(AnyId::RoomAliasId),
+        Some('!') => RoomId::try_from(id).map(AnyId::RoomId),
+        Some('@') => UserId::try_from(id).map(AnyId::UserId),
+        _ => Err(Error::new(ErrorKind::MissingSigil, raw_id)),
+    }
+}
+
+/// Attempts to parse an identifier of any kind, determining its kind from the leading sigil.
+///
+/// This is equivalent to `AnyId::try_from`. Strings without a leading sigil, or with one that
+/// doesn't belong to any of the kinds of `AnyId`, are rejected with `ErrorKind::MissingSigil`.
+/// Otherwise, the string must be valid for the kind of identifier the sigil belongs to.
+pub fn parse_any(id: &str) -> Result<AnyId, Error> {
+    parse_any_id(id)
+}
+
+impl TryFrom<&str> for AnyId {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix identifier of any kind from a string representation.
+    ///
+    /// The kind is determined by the leading sigil, and the string must be valid for that kind.
+    fn try_from(id: &str) -> Result<Self, Error> {
+        parse_any_id(id)
+    }
+}
+
+impl TryFrom<String> for AnyId {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix identifier of any kind from an owned string representation.
+    ///
+    /// This performs the same checks as the `TryFrom<&str>` implementation, but reuses the string
+    /// if it already is the canonical string representation of the ID.
+    fn try_from(id: String) -> Result<Self, Error> {
+        parse_any_id(id)
+    }
+}
+
+impl FromStr for AnyId {
+    type Err = Error;
+
+    /// Attempts to create a new Matrix identifier of any kind from a string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use serde_json::{from_str, to_string};
+
+    use super::{parse_any, AnyId};
+    use crate::{error::ErrorKind, EventId, GroupId, RoomAliasId, RoomId, UserId};
+
+    #[test]
+    fn parse_any_with_all_sigils() {
+        assert_eq!(
+            parse_any("$39hvsi03hlne:example.com").expect("Failed to parse AnyId."),
+            AnyId::EventId(
+                EventId::try_from("$39hvsi03hlne:example.com").expect("Failed to create EventId.")
+            )
+        );
+        assert_eq!(
+            parse_any("$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk")
+                .expect("Failed to parse AnyId.")
+                .sigil(),
+            '$'
+        );
+        assert_eq!(
+            parse_any("+ruma:example.com").expect("Failed to parse AnyId."),
+            AnyId::GroupId(
+                GroupId::try_from("+ruma:example.com").expect("Failed to create GroupId.")
+            )
+        );
+        assert_eq!(
+            parse_any("#ruma:example.com").expect("Failed to parse AnyId."),
+            AnyId::RoomAliasId(
+                RoomAliasId::try_from("#ruma:example.com").expect("Failed to create RoomAliasId.")
+            )
+        );
+        assert_eq!(
+            parse_any("!29fhd83h92h0:example.com").expect("Failed to parse AnyId."),
+            AnyId::RoomId(
+                RoomId::try_from("!29fhd83h92h0:example.com").expect("Failed to create RoomId.")
+            )
+        );
+        assert_eq!(
+            parse_any("@carl:example.com").expect("Failed to parse AnyId."),
+            AnyId::UserId(UserId::try_from("@carl:example.com").expect("Failed to create UserId."))
+        );
+    }
+
+    #[test]
+    fn any_id_displays_inner_id() {
+        for id in &[
+            "$39hvsi03hlne:example.com",
+            "+ruma:example.com",
+            "#ruma:example.com",
+            "!29fhd83h92h0:example.com:5000",
+            "@carl:example.com",
+        ] {
+            let any_id = AnyId::try_from(*id).expect("Failed to create AnyId.");
+
+            assert_eq!(any_id.to_string(), *id);
+            assert_eq!(any_id.as_str(), *id);
+            assert_eq!(any_id.sigil(), id.chars().next().unwrap());
+        }
+    }
+
+    #[test]
+    fn parse_any_with_unknown_or_missing_sigil() {
+        for id in &["carl:example.com", "%carl:example.com", ""] {
+            assert_eq!(parse_any(id).unwrap_err().kind(), ErrorKind::MissingSigil);
+        }
+    }
+
+    #[test]
+    fn parse_any_with_invalid_id() {
+        assert_eq!(
+            parse_any("@carl").unwrap_err().kind(),
+            ErrorKind::MissingDelimiter
+        );
+        assert_eq!(
+            parse_any("!29fhd83h92h0:exa mple.com").unwrap_err().kind(),
+            ErrorKind::InvalidHost
+        );
+        assert_eq!(
+            parse_any(" @carl:example.com").unwrap_err().kind(),
+            ErrorKind::SurroundingWhitespace
+        );
+    }
+
+    #[test]
+    fn serialize_and_deserialize_any_id() {
+        let any_id = AnyId::try_from("#ruma:example.com").expect("Failed to create AnyId.");
+
+        assert_eq!(
+            to_string(&any_id).expect("Failed to convert AnyId to JSON."),
+            r##""#ruma:example.com""##
+        );
+        assert_eq!(
+            from_str::<AnyId>(r##""#ruma:example.com""##).expect("Failed to convert JSON to AnyId"),
+            any_id
+        );
+    }
+
+    #[test]
+    fn any_id_into_string() {
+        let any_id = AnyId::try_from("@carl:example.com:8448").expect("Failed to create AnyId.");
+
+        assert_eq!(any_id.into_string(), "@carl:example.com");
+    }
+}
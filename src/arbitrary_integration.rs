@@ -0,0 +1,218 @@
+//! Implements `Arbitrary` from arbitrary, generating valid identifiers for fuzzing and property
+//! tests.
+//!
+//! The parts of an identifier are generated from the characters its rules permit and the result is
+//! parsed with its `TryFrom<&str>` implementation, so every generated identifier is valid and in
+//! its canonical form. Hosts are domain names, IPv4 addresses or IPv6 addresses, with no port, the
+//! default port or any other port. The parts are kept short enough that no identifier exceeds
+//! `MAX_BYTES`.
+
+use alloc::{format, string::String, vec::Vec};
+use core::convert::TryFrom;
+
+use arbitrary::{Arbitrary, Error as ArbitraryError, Result, Unstructured};
+
+use crate::{
+    host::Host, EventId, RoomAlias, RoomAliasId, RoomId, RoomVersionId, ServerName, UserId,
+    UserLocalpart, DEFAULT_PORT,
+};
+
+/// The maximum number of characters of a generated localpart, alias or custom room version.
+const MAX_LOCALPART_CHARS: usize = 32;
+/// The maximum number of labels of a generated domain name.
+const MAX_DOMAIN_LABELS: usize = 4;
+/// The maximum number of characters of a label of a generated domain name.
+const MAX_DOMAIN_LABEL_CHARS: usize = 16;
+/// The number of characters of a generated reference hash, i.e. of an unpadded base64-encoded
+/// SHA-256 hash.
+const REFERENCE_HASH_CHARS: usize = 43;
+
+/// The characters of user ID localparts as required by the spec, ignoring uppercase letters,
+/// which are downcased when parsing.
+const LOCALPART_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789-.=_/";
+/// The characters of domain name labels: letters, followed by digits and the hyphen.
+const DOMAIN_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789-";
+/// The number of letters at the start of `DOMAIN_CHARS`.
+const DOMAIN_LETTERS: usize = 26;
+/// The number of letters and digits at the start of `DOMAIN_CHARS`.
+const DOMAIN_ALPHANUMERICS: usize = 36;
+/// The characters of the reference hashes of event IDs in the format of room version 3.
+const BASE64_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+/// The characters of the reference hashes of event IDs in the format of room version 4 and later.
+const URL_SAFE_BASE64_CHARS: &[u8] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Parses a generated string, which only fails if the generator is out of sync with the rules of
+/// the identifier type.
+fn parse<T: for<'a> TryFrom<&'a str>>(generated: &str) -> Result<T> {
+    T::try_from(generated).map_err(|_| ArbitraryError::IncorrectFormat)
+}
+
+/// Generates a string of between `min_len` and `max_len` characters drawn from the given ASCII
+/// characters.
+fn ascii_string(
+    u: &mut Unstructured<'_>,
+    chars: &[u8],
+    min_len: usize,
+    max_len: usize,
+) -> Result<String> {
+    let len = u.int_in_range(min_len..=max_len)?;
+
+    (0..len)
+        .map(|_| u.choose(chars).map(|&b| char::from(b)))
+        .collect()
+}
+
+/// Generates a non-empty string of printable characters, including non-ASCII ones, without colons
+/// or whitespace, like the localparts of room IDs and the aliases of room alias IDs.
+fn opaque_string(u: &mut Unstructured<'_>) -> Result<String> {
+    let len = u.int_in_range(1..=MAX_LOCALPART_CHARS)?;
+
+    (0..len)
+        .map(|_| {
+            let c = char::arbitrary(u)?;
+
+            Ok(if c == ':' || c.is_whitespace() || c.is_control() {
+                'x'
+            } else {
+                c
+            })
+        })
+        .collect()
+}
+
+/// Generates the localpart of a user ID, either one as required by the spec or a historical one,
+/// which may contain any printable ASCII character except for the colon.
+fn user_localpart(u: &mut Unstructured<'_>) -> Result<String> {
+    if u.arbitrary()? {
+        return ascii_string(u, LOCALPART_CHARS, 1, MAX_LOCALPART_CHARS);
+    }
+
+    let len = u.int_in_range(1..=MAX_LOCALPART_CHARS)?;
+
+    (0..len)
+        .map(|_| {
+            let b = u.int_in_range(b'!'..=b'~')?;
+
+            Ok(if b == b':' { '_' } else { char::from(b) })
+        })
+        .collect()
+}
+
+/// Generates a domain name of one or more labels, the last of which starts with a letter, so that
+/// it isn't mistaken for an IPv4 address.
+fn domain(u: &mut Unstructured<'_>) -> Result<String> {
+    let label_count = u.int_in_range(1..=MAX_DOMAIN_LABELS)?;
+    let mut domain = String::new();
+
+    for i in 0..label_count {
+        if i > 0 {
+            domain.push('.');
+        }
+
+        let len = u.int_in_range(1..=MAX_DOMAIN_LABEL_CHARS)?;
+        for j in 0..len {
+            // Hyphens are only used inside of labels, and never as the third or fourth character,
+            // which would make a label starting with `xn` a punycode label.
+            let chars = if j == 0 && i + 1 == label_count {
+                &DOMAIN_CHARS[..DOMAIN_LETTERS]
+            } else if j == 0 || j == 2 || j == 3 || j + 1 == len {
+                &DOMAIN_CHARS[..DOMAIN_ALPHANUMERICS]
+            } else {
+                DOMAIN_CHARS
+            };
+
+            domain.push(char::from(*u.choose(chars)?));
+        }
+    }
+
+    Ok(domain)
+}
+
+/// Generates the reference hash of an event ID from the given base64 alphabet.
+fn reference_hash(u: &mut Unstructured<'_>, chars: &[u8]) -> Result<String> {
+    ascii_string(u, chars, REFERENCE_HASH_CHARS, REFERENCE_HASH_CHARS)
+}
+
+/// Generates the host of a server name.
+fn host(u: &mut Unstructured<'_>) -> Result<Host> {
+    Ok(match u.int_in_range(0..=2)? {
+        0 => Host::Domain(domain(u)?),
+        1 => Host::Ipv4(<[u8; 4]>::arbitrary(u)?.into()),
+        _ => Host::Ipv6(<[u16; 8]>::arbitrary(u)?.into()),
+    })
+}
+
+/// Generates a server name with no port, the default port or any other port.
+fn server_name(u: &mut Unstructured<'_>) -> Result<String> {
+    let host = host(u)?;
+
+    Ok(match u.int_in_range(0..=2)? {
+        0 => format!("{}", host),
+        1 => format!("{}:{}", host, DEFAULT_PORT),
+        _ => format!("{}:{}", host, u.int_in_range(1..=u16::MAX)?),
+    })
+}
+
+impl<'a> Arbitrary<'a> for ServerName {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        parse(&server_name(u)?)
+    }
+}
+
+impl<'a> Arbitrary<'a> for UserLocalpart {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        parse(&user_localpart(u)?)
+    }
+}
+
+impl<'a> Arbitrary<'a> for UserId {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        parse(&format!("@{}:{}", user_localpart(u)?, server_name(u)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for RoomId {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        parse(&format!("!{}:{}", opaque_string(u)?, server_name(u)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for RoomAlias {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        parse(&opaque_string(u)?)
+    }
+}
+
+impl<'a> Arbitrary<'a> for RoomAliasId {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        parse(&format!("#{}:{}", opaque_string(u)?, server_name(u)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for EventId {
+    /// Generates an event ID in the original format of room versions 1 and 2, or with a reference
+    /// hash in the format of room version 3 or of later versions.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let event_id = match u.int_in_range(0..=2)? {
+            0 => format!("${}:{}", opaque_string(u)?, server_name(u)?),
+            1 => format!("${}", reference_hash(u, BASE64_CHARS)?),
+            _ => format!("${}", reference_hash(u, URL_SAFE_BASE64_CHARS)?),
+        };
+
+        parse(&event_id)
+    }
+}
+
+impl<'a> Arbitrary<'a> for RoomVersionId {
+    /// Generates an official room version, or a custom one of printable characters.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        if u.arbitrary()? {
+            let official_versions: Vec<_> = RoomVersionId::official_versions().collect();
+
+            u.choose(&official_versions).cloned()
+        } else {
+            parse(&opaque_string(u)?)
+        }
+    }
+}
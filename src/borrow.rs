@@ -0,0 +1,196 @@
+//! Implements `Borrow<str>` for identifiers that store their string representation, along with
+//! the comparison traits that have to agree with it.
+//!
+//! `Borrow<str>` allows looking up identifiers in maps and sets with a string slice. This requires
+//! `Eq` and `Hash` to behave exactly like they do for the borrowed string, so they are implemented
+//! in terms of it as well. Since the string representation is the canonical one, which omits an
+//! explicit default port, IDs that only differ in whether the default port was given explicitly
+//! are equal and hash identically.
+//!
+//! `AsRef<str>` is also implemented for the other types that store their string representation,
+//! so all of them can be passed to functions taking `impl AsRef<str>`.
+
+use alloc::borrow::Borrow;
+use core::hash::{Hash, Hasher};
+
+macro_rules! as_ref_impl {
+    ($name:ident) => {
+        impl AsRef<str> for $crate::$name {
+            fn as_ref(&self) -> &str {
+                self.as_str()
+            }
+        }
+    };
+}
+
+macro_rules! borrow_impl {
+    ($name:ident) => {
+        as_ref_impl!($name);
+
+        impl Borrow<str> for $crate::$name {
+            fn borrow(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl PartialEq for $crate::$name {
+            fn eq(&self, other: &Self) -> bool {
+                self.as_str() == other.as_str()
+            }
+        }
+
+        impl Eq for $crate::$name {}
+
+        impl Hash for $crate::$name {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.as_str().hash(state);
+            }
+        }
+    };
+}
+
+borrow_impl!(EventId);
+borrow_impl!(GroupId);
+borrow_impl!(KeyId);
+borrow_impl!(RoomAliasId);
+borrow_impl!(RoomId);
+borrow_impl!(UserId);
+
+as_ref_impl!(AnyId);
+as_ref_impl!(DeviceId);
+as_ref_impl!(RoomAlias);
+as_ref_impl!(RoomIdOrAliasId);
+as_ref_impl!(RoomVersionId);
+as_ref_impl!(UserLocalpart);
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{hash_map::DefaultHasher, HashMap, HashSet},
+        convert::TryFrom,
+        hash::{Hash, Hasher},
+    };
+
+    use crate::{
+        DeviceId, EventId, GroupId, RoomAlias, RoomAliasId, RoomId, RoomIdOrAliasId, ServerName,
+        UserId, UserLocalpart,
+    };
+
+    fn as_ref_str(id: &impl AsRef<str>) -> &str {
+        id.as_ref()
+    }
+
+    fn hash_of(value: &impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn assert_equal_and_same_hash<T>(implicit: &str, explicit: &str)
+    where
+        T: for<'a> TryFrom<&'a str> + Hash + Eq + std::fmt::Debug,
+    {
+        let implicit =
+            T::try_from(implicit).unwrap_or_else(|_| panic!("Failed to parse {}.", implicit));
+        let explicit =
+            T::try_from(explicit).unwrap_or_else(|_| panic!("Failed to parse {}.", explicit));
+
+        assert_eq!(implicit, explicit);
+        assert_eq!(hash_of(&implicit), hash_of(&explicit));
+    }
+
+    #[test]
+    fn as_ref_matches_to_string() {
+        let user_id = UserId::try_from("@CARL:example.com:8448").expect("Failed to create UserId.");
+        assert_eq!(as_ref_str(&user_id), user_id.to_string());
+
+        let room_id =
+            RoomId::try_from("!29fhd83h92h0:example.com:5000").expect("Failed to create RoomId.");
+        assert_eq!(as_ref_str(&room_id), room_id.to_string());
+
+        let room_alias_id =
+            RoomAliasId::try_from("#ruma:example.com").expect("Failed to create RoomAliasId.");
+        assert_eq!(as_ref_str(&room_alias_id), room_alias_id.to_string());
+
+        let event_id =
+            EventId::try_from("$39hvsi03hlne:example.com").expect("Failed to create EventId.");
+        assert_eq!(as_ref_str(&event_id), event_id.to_string());
+
+        let room = RoomIdOrAliasId::try_from("#ruma:example.com")
+            .expect("Failed to create RoomIdOrAliasId.");
+        assert_eq!(as_ref_str(&room), room.to_string());
+
+        let device_id = DeviceId::try_from("ABCDEFGH").expect("Failed to create DeviceId.");
+        assert_eq!(as_ref_str(&device_id), device_id.to_string());
+
+        let alias = RoomAlias::try_from("ruma").expect("Failed to create RoomAlias.");
+        assert_eq!(as_ref_str(&alias), alias.to_string());
+
+        let localpart = UserLocalpart::try_from("Carl").expect("Failed to create UserLocalpart.");
+        assert_eq!(as_ref_str(&localpart), localpart.to_string());
+    }
+
+    #[test]
+    fn look_up_user_id_by_str() {
+        let mut sessions = HashMap::new();
+        sessions.insert(
+            UserId::try_from("@carl:example.com").expect("Failed to create UserId."),
+            1,
+        );
+
+        assert_eq!(sessions.get("@carl:example.com"), Some(&1));
+        assert_eq!(sessions.get("@carl:example.org"), None);
+    }
+
+    #[test]
+    fn look_up_ids_with_explicit_default_port_by_str() {
+        let mut rooms = HashSet::new();
+        rooms.insert(
+            RoomId::try_from("!29fhd83h92h0:example.com:8448").expect("Failed to create RoomId."),
+        );
+
+        assert!(rooms.contains("!29fhd83h92h0:example.com"));
+        assert!(!rooms.contains("!29fhd83h92h0:example.com:8448"));
+    }
+
+    #[test]
+    fn explicit_default_port_is_equal_and_hashes_identically() {
+        assert_equal_and_same_hash::<UserId>("@carl:example.com", "@carl:example.com:8448");
+        assert_equal_and_same_hash::<RoomId>(
+            "!29fhd83h92h0:example.com",
+            "!29fhd83h92h0:example.com:8448",
+        );
+        assert_equal_and_same_hash::<RoomAliasId>("#ruma:example.com", "#ruma:example.com:8448");
+        assert_equal_and_same_hash::<EventId>(
+            "$39hvsi03hlne:example.com",
+            "$39hvsi03hlne:example.com:8448",
+        );
+        assert_equal_and_same_hash::<GroupId>("+ruma:example.com", "+ruma:example.com:8448");
+        assert_equal_and_same_hash::<RoomIdOrAliasId>("#ruma:[::1]", "#ruma:[::1]:8448");
+        assert_equal_and_same_hash::<ServerName>("example.com", "example.com:8448");
+    }
+
+    #[test]
+    fn non_default_port_is_not_equal() {
+        assert_ne!(
+            UserId::try_from("@carl:example.com").expect("Failed to create UserId."),
+            UserId::try_from("@carl:example.com:443").expect("Failed to create UserId.")
+        );
+    }
+
+    #[test]
+    fn look_up_other_ids_by_str() {
+        let mut aliases = HashSet::new();
+        aliases.insert(
+            RoomAliasId::try_from("#ruma:example.com").expect("Failed to create RoomAliasId."),
+        );
+        assert!(aliases.contains("#ruma:example.com"));
+
+        let mut events = HashSet::new();
+        events.insert(
+            EventId::try_from("$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk")
+                .expect("Failed to create EventId."),
+        );
+        assert!(events.contains("$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk"));
+    }
+}
@@ -0,0 +1,60 @@
+//! Implements `byte_len` for identifiers that store their string representation.
+
+macro_rules! byte_len_impl {
+    ($name:ident) => {
+        impl $crate::$name {
+            /// Returns the length of the canonical string representation in bytes, i.e. of
+            /// `as_str` and `to_string`, which is what the `MAX_BYTES` limit applies to.
+            ///
+            /// This is the length after normalization, so e.g. an explicitly given default port
+            /// doesn't count towards it.
+            pub fn byte_len(&self) -> usize {
+                self.as_str().len()
+            }
+        }
+    };
+}
+
+byte_len_impl!(EventId);
+byte_len_impl!(GroupId);
+byte_len_impl!(KeyId);
+byte_len_impl!(RoomAliasId);
+byte_len_impl!(RoomId);
+byte_len_impl!(RoomIdOrAliasId);
+byte_len_impl!(UserId);
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::{error::ErrorKind, RoomId, UserId, MAX_BYTES};
+
+    #[test]
+    fn byte_len_of_canonical_representation() {
+        let user_id = UserId::try_from("@carl:example.com:8448").expect("Failed to create UserId.");
+
+        assert_eq!(user_id.byte_len(), "@carl:example.com".len());
+        assert_eq!(user_id.byte_len(), user_id.to_string().len());
+        assert_eq!(
+            RoomId::try_from("!老虎:example.com")
+                .expect("Failed to create RoomId.")
+                .byte_len(),
+            19
+        );
+    }
+
+    #[test]
+    fn byte_len_at_maximum_length() {
+        let localpart = "a".repeat(MAX_BYTES - "!:example.com".len());
+        let room_id = RoomId::try_from(format!("!{}:example.com", localpart))
+            .expect("Failed to create RoomId.");
+
+        assert_eq!(room_id.byte_len(), MAX_BYTES);
+        assert_eq!(
+            RoomId::try_from(format!("!{}a:example.com", localpart))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+    }
+}
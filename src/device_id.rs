@@ -1,24 +1,246 @@
 //! Matrix device identifiers.
 
+use alloc::string::String;
+use core::{
+    convert::TryFrom,
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
+#[cfg(feature = "diesel")]
+use diesel::sql_types::Text;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "std")]
 use crate::generate_localpart;
+use crate::{
+    deserialize_id,
+    error::{Error, ErrorKind},
+    MAX_BYTES,
+};
+
+/// The number of characters in a generated device ID.
+#[cfg(feature = "std")]
+const GENERATED_LENGTH: usize = 8;
 
-///  A Matrix device ID.
+/// A Matrix device ID.
 ///
-///  Device identifiers in Matrix are completely opaque character sequences. This type alias is
-///  provided simply for its semantic value.
-pub type DeviceId = String;
+/// Device identifiers in Matrix are completely opaque character sequences. The only constraints
+/// are that a device ID is not empty and doesn't exceed 255 bytes. A `DeviceId` is generated
+/// randomly or converted from a string slice, and can be converted back into a string as needed.
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use ruma_identifiers::DeviceId;
+/// assert_eq!(DeviceId::try_from("GHTYAJCE").unwrap().as_str(), "GHTYAJCE");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "diesel", derive(FromSqlRow, QueryId, AsExpression, SqlType))]
+#[cfg_attr(feature = "diesel", sql_type = "Text")]
+pub struct DeviceId(String);
+
+impl DeviceId {
+    /// Generates a random `DeviceId`, suitable for assignment to a new device.
+    ///
+    /// The device ID consists of 8 random ASCII letters and digits.
+    #[cfg(feature = "std")]
+    pub fn new() -> Self {
+        Self(generate_localpart(GENERATED_LENGTH))
+    }
+
+    /// Returns the device ID as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Converts the device ID into its string representation, without allocating.
+    ///
+    /// This returns the same string as `as_str` and `to_string`, but takes the stored string
+    /// instead of copying it.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for DeviceId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 /// Generates a random `DeviceId`, suitable for assignment to a new device.
+#[cfg(feature = "std")]
+#[deprecated(note = "use `DeviceId::new` instead")]
 pub fn generate() -> DeviceId {
-    generate_localpart(8)
+    DeviceId::new()
+}
+
+impl Display for DeviceId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for DeviceId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for DeviceId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_id(deserializer, "a Matrix device ID as a string")
+    }
+}
+
+/// Validates a borrowed or owned device ID, only allocating if it is valid and borrowed.
+fn parse_device_id<S>(device_id: S) -> Result<DeviceId, Error>
+where
+    S: AsRef<str> + Into<String>,
+{
+    let raw_device_id = device_id.as_ref();
+
+    if raw_device_id.is_empty() {
+        Err(Error::new(
+            ErrorKind::MinimumLengthNotSatisfied,
+            raw_device_id,
+        ))
+    } else if raw_device_id.len() > MAX_BYTES {
+        Err(Error::new(ErrorKind::MaximumLengthExceeded, raw_device_id))
+    } else {
+        Ok(DeviceId(device_id.into()))
+    }
+}
+
+impl TryFrom<&str> for DeviceId {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix device ID from a string representation.
+    ///
+    /// The string must not be empty and must not exceed 255 bytes.
+    fn try_from(device_id: &str) -> Result<Self, Error> {
+        parse_device_id(device_id)
+    }
+}
+
+impl TryFrom<String> for DeviceId {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix device ID from an owned string representation.
+    ///
+    /// This performs the same checks as the `TryFrom<&str>` implementation, but reuses the string.
+    fn try_from(device_id: String) -> Result<Self, Error> {
+        parse_device_id(device_id)
+    }
+}
+
+impl FromStr for DeviceId {
+    type Err = Error;
+
+    /// Attempts to create a new Matrix device ID from a string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::generate;
+    use std::convert::TryFrom;
+
+    use serde_json::{from_str, to_string};
+
+    use super::DeviceId;
+    use crate::error::ErrorKind;
 
     #[test]
     fn generate_device_id() {
-        assert_eq!(generate().len(), 8);
+        let device_id = DeviceId::new();
+
+        assert_eq!(device_id.as_str().len(), 8);
+        assert!(device_id
+            .as_str()
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric()));
+    }
+
+    #[test]
+    fn valid_device_id() {
+        assert_eq!(
+            DeviceId::try_from("GHTYAJCE")
+                .expect("Failed to create DeviceId.")
+                .to_string(),
+            "GHTYAJCE"
+        );
+    }
+
+    #[test]
+    fn device_id_from_owned_string() {
+        let raw_id = "GHTYAJCE".to_string();
+        let raw_ptr = raw_id.as_ptr();
+
+        assert_eq!(
+            DeviceId::try_from(raw_id)
+                .expect("Failed to create DeviceId.")
+                .as_str()
+                .as_ptr(),
+            raw_ptr
+        );
+        assert_eq!(
+            DeviceId::try_from(String::new()).unwrap_err().kind(),
+            ErrorKind::MinimumLengthNotSatisfied
+        );
+    }
+
+    #[test]
+    fn serialize_valid_device_id() {
+        assert_eq!(
+            to_string(&DeviceId::try_from("GHTYAJCE").expect("Failed to create DeviceId."))
+                .expect("Failed to convert DeviceId to JSON."),
+            r#""GHTYAJCE""#
+        );
+    }
+
+    #[test]
+    fn deserialize_valid_device_id() {
+        assert_eq!(
+            from_str::<DeviceId>(r#""GHTYAJCE""#).expect("Failed to convert JSON to DeviceId"),
+            DeviceId::try_from("GHTYAJCE").expect("Failed to create DeviceId.")
+        );
+    }
+
+    #[test]
+    fn empty_device_id() {
+        assert_eq!(
+            DeviceId::try_from("").unwrap_err().kind(),
+            ErrorKind::MinimumLengthNotSatisfied
+        );
+    }
+
+    #[test]
+    fn over_max_bytes_device_id() {
+        assert_eq!(
+            DeviceId::try_from("a".repeat(256).as_str())
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+        assert!(DeviceId::try_from("a".repeat(255).as_str()).is_ok());
+    }
+
+    #[test]
+    fn device_id_into_string() {
+        let id = DeviceId::try_from("ABCDEFG").expect("Failed to create DeviceId.");
+        let string = id.to_string();
+
+        assert_eq!(id.into_string(), string);
     }
 }
@@ -27,16 +27,21 @@ macro_rules! diesel_impl {
         {
             fn from_sql(value: Option<&<DB as Backend>::RawValue>) -> DeserializeResult<Self> {
                 let string = <String as FromSql<Text, DB>>::from_sql(value)?;
-                Self::try_from(string.as_str())
+                Self::try_from(string)
                     .map_err(|error| Box::new(error) as Box<dyn StdError + Send + Sync>)
             }
         }
     };
 }
 
+diesel_impl!(DeviceId);
 diesel_impl!(EventId);
+diesel_impl!(GroupId);
+diesel_impl!(KeyId);
+diesel_impl!(RoomAlias);
 diesel_impl!(RoomAliasId);
 diesel_impl!(RoomId);
 diesel_impl!(RoomIdOrAliasId);
 diesel_impl!(RoomVersionId);
 diesel_impl!(UserId);
+diesel_impl!(UserLocalpart);
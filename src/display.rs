@@ -0,0 +1,108 @@
+//! Implements alternative `Display` formats for identifiers that always contain a server name.
+
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::Host;
+
+/// Displays an identifier with a server name in a format other than the canonical one.
+pub(crate) struct IdDisplay<'a> {
+    /// The sigil and the localpart of the ID.
+    pub(crate) prefix: &'a str,
+    /// The host of the ID's server name.
+    pub(crate) host: &'a Host,
+    /// The port to display after the host, if any.
+    pub(crate) port: Option<u16>,
+}
+
+impl Display for IdDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}:{}", self.prefix, self.host)?;
+
+        if let Some(port) = self.port {
+            write!(f, ":{}", port)?;
+        }
+
+        Ok(())
+    }
+}
+
+macro_rules! display_impl {
+    ($name:ident) => {
+        impl $crate::$name {
+            /// Returns a value that displays the ID with the port of its server name, even if it
+            /// is `DEFAULT_PORT` or wasn't given explicitly, like `@carl:example.com:8448`.
+            ///
+            /// The `Display` implementation of the ID itself omits the default port.
+            pub fn display_with_port(&self) -> impl core::fmt::Display + '_ {
+                $crate::display::IdDisplay {
+                    prefix: &self.as_str()[..$crate::SIGIL_BYTES + self.localpart().len()],
+                    host: self.hostname(),
+                    port: Some(self.port()),
+                }
+            }
+
+            /// Returns a value that displays the ID with the host of its server name but without
+            /// any port, like `@carl:example.com` for `@carl:example.com:5000`.
+            ///
+            /// The result identifies the host, but not necessarily the homeserver, so it isn't
+            /// a valid representation of the ID if the port isn't `DEFAULT_PORT`.
+            pub fn display_host_only(&self) -> impl core::fmt::Display + '_ {
+                $crate::display::IdDisplay {
+                    prefix: &self.as_str()[..$crate::SIGIL_BYTES + self.localpart().len()],
+                    host: self.hostname(),
+                    port: None,
+                }
+            }
+        }
+    };
+}
+
+display_impl!(GroupId);
+display_impl!(RoomAliasId);
+display_impl!(RoomId);
+display_impl!(UserId);
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::{RoomAliasId, RoomId, UserId};
+
+    #[test]
+    fn display_with_port() {
+        let user_id = UserId::try_from("@carl:example.com").expect("Failed to create UserId.");
+
+        assert_eq!(user_id.to_string(), "@carl:example.com");
+        assert_eq!(
+            user_id.display_with_port().to_string(),
+            "@carl:example.com:8448"
+        );
+        assert_eq!(
+            RoomId::try_from("!n8f893n9:[::1]:5000")
+                .expect("Failed to create RoomId.")
+                .display_with_port()
+                .to_string(),
+            "!n8f893n9:[::1]:5000"
+        );
+    }
+
+    #[test]
+    fn display_host_only() {
+        assert_eq!(
+            UserId::try_from("@carl:example.com:5000")
+                .expect("Failed to create UserId.")
+                .display_host_only()
+                .to_string(),
+            "@carl:example.com"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                RoomAliasId::try_from("#ruma:127.0.0.1:8448")
+                    .expect("Failed to create RoomAliasId.")
+                    .display_host_only()
+            ),
+            "#ruma:127.0.0.1"
+        );
+    }
+}
@@ -1,50 +1,118 @@
 //! Error conditions.
 
-use std::{
-    error::Error as StdError,
-    fmt::{Display, Formatter, Result as FmtResult},
-};
+use alloc::string::{String, ToString};
+use core::fmt::{Display, Formatter, Result as FmtResult};
+#[cfg(feature = "std")]
+use std::error::Error as StdError;
 
-use url::ParseError;
+pub use ruma_identifiers_validation::ErrorKind;
+use ruma_identifiers_validation::{host::HostError, Error as ValidationError};
 
 /// An error encountered when trying to parse an invalid ID string.
-#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
-pub enum Error {
-    /// The ID's localpart contains invalid characters.
-    ///
-    /// Only relevant for user IDs.
-    InvalidCharacters,
-    /// The domain part of the the ID string is not a valid IP address or DNS name.
-    InvalidHost,
-    /// The ID exceeds 255 bytes (or 32 codepoints for a room version ID.)
-    MaximumLengthExceeded,
-    /// The ID is less than 4 characters (or is an empty room version ID.)
-    MinimumLengthNotSatisfied,
-    /// The ID is missing the colon delimiter between localpart and server name.
-    MissingDelimiter,
-    /// The ID is missing the leading sigil.
-    MissingSigil,
+///
+/// The error contains the kind of rule that was violated as well as the entire string that was
+/// being parsed.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Error {
+    /// The rule the input violated.
+    kind: ErrorKind,
+    /// The string that failed to parse.
+    input: String,
+    /// The error returned when parsing the server name, if any.
+    source: Option<HostError>,
+}
+
+impl Error {
+    /// Creates an error of the given kind for the given input.
+    pub(crate) fn new(kind: ErrorKind, input: &str) -> Self {
+        Self {
+            kind,
+            input: input.to_string(),
+            source: None,
+        }
+    }
+
+    /// Creates an error for the given input from an error of the validation crate, keeping the
+    /// error of the host or port parser as the source.
+    pub(crate) fn from_validation(err: ValidationError, input: &str) -> Self {
+        Self {
+            source: err.host_error(),
+            ..Self::new(err.kind(), input)
+        }
+    }
+
+    /// Replaces the input of the error, e.g. with the entire ID when parsing one of its parts
+    /// failed.
+    pub(crate) fn with_input(self, input: &str) -> Self {
+        Self {
+            input: input.to_string(),
+            ..self
+        }
+    }
+
+    /// Returns the kind of the error, i.e. the rule the input violated.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Returns the string that failed to parse.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        let message = match *self {
-            Error::InvalidCharacters => "localpart contains invalid characters",
-            Error::InvalidHost => "server name is not a valid IP address or domain name",
-            Error::MaximumLengthExceeded => "ID exceeds 255 bytes",
-            Error::MinimumLengthNotSatisfied => "ID must be at least 4 characters",
-            Error::MissingDelimiter => "colon is required between localpart and server name",
-            Error::MissingSigil => "leading sigil is missing",
-        };
+        write!(f, "{}: {:?}", self.kind, self.input)
+    }
+}
 
-        write!(f, "{}", message)
+#[cfg(feature = "std")]
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.source.as_ref().map(|source| source as _)
     }
 }
 
-impl StdError for Error {}
+#[cfg(test)]
+mod tests {
+    use std::{convert::TryFrom, error::Error as _};
+
+    use crate::{ErrorKind, ServerName, UserId};
+
+    #[test]
+    fn error_contains_input() {
+        let error = UserId::try_from("@carl:example.com/path").unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidHost);
+        assert_eq!(error.input(), "@carl:example.com/path");
+        assert_eq!(
+            error.to_string(),
+            r#"server name is not a valid IP address or domain name: "@carl:example.com/path""#
+        );
+    }
+
+    #[test]
+    fn error_source() {
+        let error = ServerName::try_from("[::1::2]").unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidHost);
+        assert!(error.source().is_some());
+        assert!(ServerName::try_from("example.com/path")
+            .unwrap_err()
+            .source()
+            .is_none());
+    }
+
+    #[test]
+    fn invalid_port_error() {
+        let error = UserId::try_from("@carl:example.com:notaport").unwrap_err();
 
-impl From<ParseError> for Error {
-    fn from(_: ParseError) -> Self {
-        Error::InvalidHost
+        assert_eq!(error.kind(), ErrorKind::InvalidPort);
+        assert_eq!(
+            error.to_string(),
+            r#"port of the server name is not a valid number: "@carl:example.com:notaport""#
+        );
+        assert!(error.source().is_some());
     }
 }
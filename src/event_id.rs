@@ -1,22 +1,57 @@
 //! Matrix event identifiers.
 
-use std::{
+use alloc::{
+    borrow::Cow,
+    format,
+    string::{String, ToString},
+};
+use core::{
+    cmp::Ordering,
     convert::TryFrom,
     fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
 };
+#[cfg(feature = "std")]
+use std::time::SystemTime;
 
 #[cfg(feature = "diesel")]
 use diesel::sql_types::Text;
+#[cfg(feature = "std")]
+use rand::thread_rng;
+use rand::Rng;
+use ruma_identifiers_validation::event_id::{self as validation, validate_reference_hash};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use url::Host;
 
-use crate::{deserialize_id, display, error::Error, generate_localpart, parse_id};
+#[cfg(feature = "std")]
+use crate::alphabet::generate_sortable_localpart;
+use crate::{
+    alphabet::Alphabet,
+    canonical_host, canonical_id, deserialize_id,
+    error::{Error, ErrorKind},
+    format_id, generate_id_localpart, has_surrounding_whitespace,
+    host::Host,
+    id_from_host, id_from_parts, parse_id, parse_owned_id,
+    room_version_id::RoomVersionId,
+    server_name::ServerName,
+    validate_opaque_id, SIGIL_BYTES,
+};
+
+/// The number of characters in the localpart of a generated event ID.
+const GENERATED_LENGTH: usize = 18;
+
+/// The number of characters of a reference hash, i.e. of a SHA-256 hash encoded as unpadded
+/// Base64.
+const REFERENCE_HASH_CHARS: usize = 43;
 
 /// A Matrix event ID.
 ///
 /// An `EventId` is generated randomly or converted from a string slice, and can be converted back
 /// into a string as needed.
 ///
+/// Besides its components, an `EventId` stores its canonical string representation. This takes up
+/// roughly twice the memory, but allows it to be borrowed as a `&str`, e.g. to look it up in a
+/// `HashMap` or `HashSet` with a string slice.
+///
 /// # Room versions
 ///
 /// Matrix specifies multiple [room versions](https://matrix.org/docs/spec/#room-versions) and the
@@ -26,6 +61,10 @@ use crate::{deserialize_id, display, error::Error, generate_localpart, parse_id}
 /// encoded with Base64. Some of the methods provided by `EventId` are only relevant to the
 /// original event format.
 ///
+/// In either format, the part after the sigil may contain any printable character, including
+/// non-ASCII ones, except for whitespace and control characters. In the original format it ends at
+/// the first colon; reference hashes can't contain colons.
+///
 /// ```
 /// # use std::convert::TryFrom;
 /// # use ruma_identifiers::EventId;
@@ -45,10 +84,15 @@ use crate::{deserialize_id, display, error::Error, generate_localpart, parse_id}
 ///     "$Rqnc-F-dvnEYJTyHq_iKxU2bZ1CI92-kuZq3a5lr5Zg"
 /// );
 /// ```
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "diesel", derive(FromSqlRow, QueryId, AsExpression, SqlType))]
 #[cfg_attr(feature = "diesel", sql_type = "Text")]
-pub struct EventId(Format);
+pub struct EventId {
+    /// The event ID in its canonical string representation.
+    full_id: String,
+    /// The format of the event ID.
+    format: Format,
+}
 
 /// Different event ID formats from the different Matrix room versions.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -56,37 +100,260 @@ enum Format {
     /// The original format as used by Matrix room versions 1 and 2.
     Original(Original),
     /// The format used by Matrix room version 3.
-    Base64(String),
+    Base64,
     /// The format used by Matrix room version 4.
-    UrlSafeBase64(String),
+    UrlSafeBase64,
+}
+
+/// The format of the event IDs in a room, which depends on its room version.
+///
+/// See `RoomVersionId::event_id_format`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum EventIdFormat {
+    /// The original format as used by Matrix room versions 1 and 2, consisting of a localpart and
+    /// the server name of the originating homeserver, like `$39hvsi03hlne:example.com`.
+    Original,
+    /// The format used by Matrix room version 3, consisting of the reference hash of the event
+    /// encoded with standard Base64, which may contain `+` and `/`.
+    Base64,
+    /// The format used by Matrix room version 4 and later, consisting of the reference hash of the
+    /// event encoded with URL-safe Base64, which uses `-` and `_` instead.
+    UrlSafeBase64,
 }
 
 /// An event in the original format as used by Matrix room versions 1 and 2.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 struct Original {
-    /// The hostname of the homeserver.
-    pub hostname: Host,
-    /// The event's unique ID.
-    pub localpart: String,
-    /// The network port of the homeserver.
-    pub port: u16,
+    /// The index of the colon separating the localpart and the server name in the event ID.
+    pub colon_idx: usize,
+    /// The server name of the homeserver.
+    pub server_name: ServerName,
 }
 
 impl EventId {
     /// Attempts to generate an `EventId` for the given origin server with a localpart consisting
-    /// of 18 random ASCII characters. This should only be used for events in the original format
-    /// as used by Matrix room versions 1 and 2.
+    /// of 18 random ASCII letters and digits. This should only be used for events in the original
+    /// format as used by Matrix room versions 1 and 2.
     ///
     /// Fails if the homeserver cannot be parsed as a valid host.
+    #[cfg(feature = "std")]
     pub fn new(homeserver_host: &str) -> Result<Self, Error> {
-        let event_id = format!("${}:{}", generate_localpart(18), homeserver_host);
-        let (localpart, host, port) = parse_id('$', &event_id)?;
+        Self::new_with_length(homeserver_host, GENERATED_LENGTH)
+    }
+
+    /// Attempts to generate an `EventId` for the given origin server with a localpart consisting
+    /// of `length` random ASCII letters and digits. This should only be used for events in the
+    /// original format as used by Matrix room versions 1 and 2.
+    ///
+    /// Fails if the homeserver cannot be parsed as a valid host, if `length` is zero, or if the
+    /// event ID would exceed 255 bytes.
+    #[cfg(feature = "std")]
+    pub fn new_with_length(homeserver_host: &str, length: usize) -> Result<Self, Error> {
+        Self::generate(
+            &mut thread_rng(),
+            homeserver_host,
+            length,
+            Alphabet::Alphanumeric,
+        )
+    }
+
+    /// Attempts to generate an `EventId` for the given origin server with a localpart consisting
+    /// of 18 random ASCII letters and digits drawn from the given random number generator. This
+    /// should only be used for events in the original format as used by Matrix room versions 1
+    /// and 2.
+    ///
+    /// With a seeded generator, this produces reproducible event IDs, e.g. for tests. Unless the
+    /// generator is cryptographically secure, the event IDs are predictable.
+    ///
+    /// Fails if the homeserver cannot be parsed as a valid host.
+    pub fn new_with_rng(homeserver_host: &str, rng: &mut impl Rng) -> Result<Self, Error> {
+        Self::generate(
+            rng,
+            homeserver_host,
+            GENERATED_LENGTH,
+            Alphabet::Alphanumeric,
+        )
+    }
+
+    /// Attempts to generate an `EventId` for the given origin server with a localpart consisting of
+    /// 18 random characters from the given alphabet, e.g. to match the other identifiers of the
+    /// homeserver. `new` is equivalent to passing `Alphabet::Alphanumeric`.
+    ///
+    /// This should only be used for events in the original format as used by Matrix room versions
+    /// 1 and 2.
+    ///
+    /// Fails if the homeserver cannot be parsed as a valid host.
+    #[cfg(feature = "std")]
+    pub fn new_with_alphabet(homeserver_host: &str, alphabet: Alphabet) -> Result<Self, Error> {
+        Self::generate(
+            &mut thread_rng(),
+            homeserver_host,
+            GENERATED_LENGTH,
+            alphabet,
+        )
+    }
+
+    /// Attempts to generate an `EventId` for the given origin server whose localpart starts with
+    /// the current time, so that event IDs generated later sort after earlier ones.
+    ///
+    /// The localpart consists of 10 characters encoding the milliseconds since the Unix epoch,
+    /// followed by 16 random characters, both from `Alphabet::Base32Unambiguous`. Event IDs
+    /// generated within the same millisecond are ordered randomly. This is merely a convenience
+    /// for servers that want event IDs to sort roughly chronologically, e.g. in logs and
+    /// databases; the Matrix specification doesn't require any particular format of localparts,
+    /// and other servers' event IDs can't be assumed to be sortable.
+    ///
+    /// This should only be used for events in the original format as used by Matrix room versions
+    /// 1 and 2.
+    ///
+    /// Fails if the homeserver cannot be parsed as a valid host.
+    #[cfg(feature = "std")]
+    pub fn new_sortable(homeserver_host: &str) -> Result<Self, Error> {
+        let localpart = generate_sortable_localpart(&mut thread_rng(), SystemTime::now());
+
+        Self::from_parts(&localpart, homeserver_host).map_err(|err| err.with_input(homeserver_host))
+    }
+
+    /// Generates an `EventId` with a random localpart of the given length.
+    fn generate(
+        rng: &mut impl Rng,
+        homeserver_host: &str,
+        length: usize,
+        alphabet: Alphabet,
+    ) -> Result<Self, Error> {
+        let localpart = generate_id_localpart(rng, length, alphabet, homeserver_host)?;
+
+        Self::from_parts(&localpart, homeserver_host).map_err(|err| err.with_input(homeserver_host))
+    }
+
+    /// Creates an `EventId` in the original format as used by Matrix room versions 1 and 2 from its
+    /// localpart and the server name of the originating homeserver.
+    ///
+    /// The localpart is validated like when parsing a string, so it must be non-empty and must not
+    /// contain colons, whitespace or control characters. The server name is validated like a
+    /// `ServerName`.
+    pub fn from_parts(localpart: &str, server_name: &str) -> Result<Self, Error> {
+        let (full_id, colon_idx, server_name) =
+            id_from_parts('$', localpart, server_name, validate_opaque_id)?;
 
-        Ok(Self(Format::Original(Original {
-            hostname: host,
-            localpart: localpart.to_string(),
-            port,
-        })))
+        Ok(Self {
+            full_id,
+            format: Format::Original(Original {
+                colon_idx,
+                server_name,
+            }),
+        })
+    }
+
+    /// Creates an `EventId` in the original format as used by Matrix room versions 1 and 2 from its
+    /// localpart and an already parsed host and port of the originating homeserver.
+    ///
+    /// The localpart is validated like in `from_parts`. Of the host and port, only the checks of
+    /// `ServerName::from_host` apply.
+    pub fn from_host(localpart: &str, host: Host, port: u16) -> Result<Self, Error> {
+        let (full_id, colon_idx, server_name) =
+            id_from_host('$', localpart, host, port, validate_opaque_id)?;
+
+        Ok(Self {
+            full_id,
+            format: Format::Original(Original {
+                colon_idx,
+                server_name,
+            }),
+        })
+    }
+
+    /// Creates an `EventId` in the format used by Matrix room version 3 and later from the
+    /// reference hash of an event, i.e. the entire ID without the leading $ sigil.
+    ///
+    /// The hash is expected to be encoded with either standard or URL-safe unpadded Base64, which
+    /// determines the format of the created ID.
+    ///
+    /// Fails if the hash is empty or contains a colon, which would make it an ID in the original
+    /// format.
+    pub fn from_reference_hash(hash: &str) -> Result<Self, Error> {
+        let format = reference_hash_format(hash).map_err(|kind| Error::new(kind, hash))?;
+
+        Ok(Self {
+            full_id: format!("${}", hash),
+            format,
+        })
+    }
+
+    /// Attempts to parse an event ID of a room with the given version, only accepting the event ID
+    /// format of that room version.
+    ///
+    /// Rooms of versions 1 and 2 require the original format with a server name, which is rejected
+    /// with `ErrorKind::MissingDelimiter` otherwise. Rooms of version 3 require a reference hash
+    /// encoded with standard Base64, and rooms of version 4 and later one encoded with URL-safe
+    /// Base64; reference hashes with characters of any other alphabet, including the other Base64
+    /// alphabet, are rejected with `ErrorKind::InvalidCharacters`. A reference hash is the
+    /// unpadded Base64 encoding of a SHA-256 hash, so it must have exactly 43 characters; shorter
+    /// and longer ones are rejected with `ErrorKind::MinimumLengthNotSatisfied` and
+    /// `ErrorKind::MaximumLengthExceeded` respectively. For custom room versions, whose event ID
+    /// format isn't known, this is equivalent to `EventId::try_from`, which accepts any of the
+    /// formats.
+    pub fn parse_for_version(event_id: &str, version: &RoomVersionId) -> Result<Self, Error> {
+        let parsed = Self::try_from(event_id)?;
+        let format = match version.event_id_format() {
+            Some(format) => format,
+            None => return Ok(parsed),
+        };
+
+        if format == EventIdFormat::Original {
+            return if parsed.matches_format(format) {
+                Ok(parsed)
+            } else {
+                Err(Error::new(ErrorKind::MissingDelimiter, event_id))
+            };
+        }
+
+        let is_in_alphabet = |b: u8| match b {
+            b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' => true,
+            b'+' | b'/' => format == EventIdFormat::Base64,
+            b'-' | b'_' => format == EventIdFormat::UrlSafeBase64,
+            _ => false,
+        };
+        let hash = parsed.localpart();
+
+        if !parsed.matches_format(format) || !hash.bytes().all(is_in_alphabet) {
+            Err(Error::new(ErrorKind::InvalidCharacters, event_id))
+        } else if hash.len() < REFERENCE_HASH_CHARS {
+            Err(Error::new(ErrorKind::MinimumLengthNotSatisfied, event_id))
+        } else if hash.len() > REFERENCE_HASH_CHARS {
+            Err(Error::new(ErrorKind::MaximumLengthExceeded, event_id))
+        } else {
+            Ok(parsed)
+        }
+    }
+
+    /// Creates an `EventId` in the original format from its components.
+    fn original(localpart: &str, server_name: ServerName) -> Self {
+        let (full_id, colon_idx) = format_id('$', localpart, &server_name);
+
+        Self {
+            full_id,
+            format: Format::Original(Original {
+                colon_idx,
+                server_name,
+            }),
+        }
+    }
+
+    /// Returns the event ID as a string slice.
+    ///
+    /// The canonical string representation is stored when the event ID is created, so this doesn't
+    /// allocate, returns the same string as `to_string`, and can be called repeatedly for free.
+    pub fn as_str(&self) -> &str {
+        &self.full_id
+    }
+
+    /// Converts the event ID into its string representation, without allocating.
+    ///
+    /// This returns the same string as `as_str` and `to_string`, but takes the stored string
+    /// instead of copying it.
+    pub fn into_string(self) -> String {
+        self.full_id
     }
 
     /// Returns a `Host` for the event ID, containing the server name (minus the port) of the
@@ -95,8 +362,30 @@ impl EventId {
     ///
     /// The host can be either a domain name, an IPv4 address, or an IPv6 address.
     pub fn hostname(&self) -> Option<&Host> {
-        if let Format::Original(original) = &self.0 {
-            Some(&original.hostname)
+        if let Format::Original(original) = &self.format {
+            Some(original.server_name.host())
+        } else {
+            None
+        }
+    }
+
+    /// Whether the server name of the event ID is an IPv4 or IPv6 address rather than a domain
+    /// name. Always `false` for event IDs in the formats used by Matrix room versions 3 and later,
+    /// which don't contain a server name.
+    pub fn is_ip_literal(&self) -> bool {
+        match &self.format {
+            Format::Original(original) => original.server_name.is_ip_literal(),
+            _ => false,
+        }
+    }
+
+    /// Returns the host of the event ID's server name as a string slice, exactly as it appears in
+    /// the canonical string representation, i.e. without the port. IPv6 addresses are enclosed in
+    /// brackets. Only applicable to events in the original format as used by Matrix room versions
+    /// 1 and 2.
+    pub fn host_str(&self) -> Option<&str> {
+        if let Format::Original(original) = &self.format {
+            Some(canonical_host(&self.full_id[original.colon_idx + 1..]))
         } else {
             None
         }
@@ -106,35 +395,143 @@ impl EventId {
     /// versions 1 and 2, this is the "localpart" that precedes the homeserver. For later formats,
     /// this is the entire ID without the leading $ sigil.
     pub fn localpart(&self) -> &str {
-        match &self.0 {
-            Format::Original(original) => &original.localpart,
-            Format::Base64(id) | Format::UrlSafeBase64(id) => id,
+        match &self.format {
+            Format::Original(original) => &self.full_id[SIGIL_BYTES..original.colon_idx],
+            Format::Base64 | Format::UrlSafeBase64 => &self.full_id[SIGIL_BYTES..],
         }
     }
 
     /// Returns the port the originating homeserver can be accessed on. Only applicable to events
     /// in the original format as used by Matrix room versions 1 and 2.
     pub fn port(&self) -> Option<u16> {
-        if let Format::Original(original) = &self.0 {
-            Some(original.port)
+        if let Format::Original(original) = &self.format {
+            Some(original.server_name.port())
         } else {
             None
         }
     }
+
+    /// Returns the port of the originating homeserver if it was given explicitly, even if it is
+    /// `DEFAULT_PORT`, or `None` if the event ID doesn't specify one. Always `None` for event IDs
+    /// in the formats used by Matrix room versions 3 and later.
+    pub fn explicit_port(&self) -> Option<u16> {
+        if let Format::Original(original) = &self.format {
+            original.server_name.explicit_port()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the server name of the originating homeserver, i.e. its hostname followed by the
+    /// port if it isn't `DEFAULT_PORT`. Only applicable to events in the original format as used
+    /// by Matrix room versions 1 and 2.
+    pub fn server_name(&self) -> Option<String> {
+        if let Format::Original(original) = &self.format {
+            Some(original.server_name.to_string())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the HTTPS base URL of the originating homeserver's federation API, like
+    /// `https://example.com:8448/`. Only applicable to events in the original format as used by
+    /// Matrix room versions 1 and 2.
+    ///
+    /// See `ServerName::federation_base_url`.
+    pub fn federation_base_url(&self) -> Option<String> {
+        if let Format::Original(original) = &self.format {
+            Some(original.server_name.federation_base_url())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the host and the port of the originating homeserver in one call, with the domain
+    /// borrowed instead of being copied. Only applicable to events in the original format as used
+    /// by Matrix room versions 1 and 2.
+    ///
+    /// See `ServerName::resolution_target`.
+    pub fn resolution_target(&self) -> Option<(Host<&str>, u16)> {
+        if let Format::Original(original) = &self.format {
+            Some(original.server_name.resolution_target())
+        } else {
+            None
+        }
+    }
+
+    /// Whether the event ID is valid in the given format, e.g. the one of a room's version as
+    /// returned by `RoomVersionId::event_id_format`.
+    ///
+    /// Reference hashes that contain neither `+` and `/` nor `-` and `_` are valid in both Base64
+    /// formats.
+    pub fn matches_format(&self, format: EventIdFormat) -> bool {
+        match (&self.format, format) {
+            (Format::Original(_), EventIdFormat::Original)
+            | (Format::Base64, EventIdFormat::Base64)
+            | (Format::UrlSafeBase64, EventIdFormat::UrlSafeBase64) => true,
+            (Format::UrlSafeBase64, EventIdFormat::Base64) => !self.full_id.contains(['-', '_']),
+            _ => false,
+        }
+    }
+
+    /// Whether both event IDs originate from the same homeserver, i.e. have the same host and
+    /// port, regardless of their localparts.
+    ///
+    /// Like the equality of server names, this treats an explicitly given `DEFAULT_PORT` the same
+    /// as an omitted port. Event IDs in the formats used by Matrix room versions 3 and later don't
+    /// contain a server name, so this is always `false` if either of them uses such a format.
+    pub fn same_server(&self, other: &Self) -> bool {
+        match (&self.format, &other.format) {
+            (Format::Original(original), Format::Original(other_original)) => {
+                original.server_name == other_original.server_name
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the event's unique ID and the server name of the originating homeserver in one call.
+    ///
+    /// Like `server_name`, the server name omits the port if it is `DEFAULT_PORT`, and is only
+    /// present for events in the original format as used by Matrix room versions 1 and 2.
+    pub fn parts(&self) -> (&str, Option<String>) {
+        (self.localpart(), self.server_name())
+    }
+
+    /// Returns the string representation of the ID, including the port if it was given explicitly,
+    /// even if it is the default port. For formats other than the original one, this is the same
+    /// as the regular string representation.
+    ///
+    /// Only the port is preserved, the rest of the ID is canonical. See
+    /// `ServerName::to_string_preserving_port`.
+    pub fn to_string_preserving_port(&self) -> String {
+        match &self.format {
+            Format::Original(original) => format!(
+                "${}:{}",
+                self.localpart(),
+                original.server_name.to_string_preserving_port()
+            ),
+            Format::Base64 | Format::UrlSafeBase64 => self.full_id.clone(),
+        }
+    }
 }
 
 impl Display for EventId {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        match &self.0 {
-            Format::Original(original) => display(
-                f,
-                '$',
-                &original.localpart,
-                &original.hostname,
-                original.port,
-            ),
-            Format::Base64(id) | Format::UrlSafeBase64(id) => write!(f, "${}", id),
-        }
+        f.write_str(&self.full_id)
+    }
+}
+
+impl PartialOrd for EventId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Event IDs are ordered lexicographically by their string representation, not by
+/// their individual components.
+impl Ord for EventId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
     }
 }
 
@@ -143,7 +540,7 @@ impl Serialize for EventId {
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        serializer.serialize_str(self.as_str())
     }
 }
 
@@ -156,6 +553,34 @@ impl<'de> Deserialize<'de> for EventId {
     }
 }
 
+/// Checks a reference hash and returns the format of the event ID it belongs to.
+///
+/// Like any other identifier, the event ID including its sigil must not exceed `MAX_BYTES`.
+fn reference_hash_format(hash: &str) -> Result<Format, ErrorKind> {
+    validate_reference_hash(hash)?;
+
+    if hash.contains(['+', '/']) {
+        Ok(Format::Base64)
+    } else {
+        Ok(Format::UrlSafeBase64)
+    }
+}
+
+/// Parses an event ID without copying it, returning its canonical string representation.
+pub(crate) fn canonical_event_id(event_id: &str) -> Result<Cow<'_, str>, Error> {
+    if has_surrounding_whitespace(event_id) {
+        Err(Error::new(ErrorKind::SurroundingWhitespace, event_id))
+    } else if event_id.contains(':') {
+        canonical_id('$', event_id)
+    } else if !event_id.starts_with('$') {
+        Err(Error::new(ErrorKind::MissingSigil, event_id))
+    } else {
+        reference_hash_format(&event_id[SIGIL_BYTES..])
+            .map(|_| Cow::Borrowed(event_id))
+            .map_err(|kind| Error::new(kind, event_id))
+    }
+}
+
 impl TryFrom<&str> for EventId {
     type Error = Error;
 
@@ -163,34 +588,112 @@ impl TryFrom<&str> for EventId {
     ///
     /// If using the original event format as used by Matrix room versions 1 and 2, the string must
     /// include the leading $ sigil, the localpart, a literal colon, and a valid homeserver
-    /// hostname.
+    /// hostname. Otherwise, the string must include the leading $ sigil followed by the reference
+    /// hash of the event.
     fn try_from(event_id: &str) -> Result<Self, Self::Error> {
-        if event_id.contains(':') {
-            let (localpart, host, port) = parse_id('$', event_id)?;
-
-            Ok(Self(Format::Original(Original {
-                hostname: host,
-                localpart: localpart.to_owned(),
-                port,
-            })))
+        if has_surrounding_whitespace(event_id) {
+            Err(Error::new(ErrorKind::SurroundingWhitespace, event_id))
+        } else if event_id.contains(':') {
+            let (localpart, server_name) = parse_id('$', event_id)?;
+            validate_opaque_id(localpart).map_err(|kind| Error::new(kind, event_id))?;
+
+            Ok(Self::original(localpart, server_name))
+        } else if !event_id.starts_with('$') {
+            Err(Error::new(ErrorKind::MissingSigil, event_id))
+        } else {
+            Self::from_reference_hash(&event_id[SIGIL_BYTES..])
+                .map_err(|err| err.with_input(event_id))
+        }
+    }
+}
+
+impl TryFrom<String> for EventId {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix event ID from an owned string representation.
+    ///
+    /// This performs the same checks as the `TryFrom<&str>` implementation, but reuses the string
+    /// if it already is the canonical string representation of the event ID.
+    fn try_from(event_id: String) -> Result<Self, Self::Error> {
+        if has_surrounding_whitespace(&event_id) {
+            Err(Error::new(ErrorKind::SurroundingWhitespace, &event_id))
+        } else if event_id.contains(':') {
+            let (full_id, colon_idx, server_name, ()) =
+                parse_owned_id('$', event_id, validate_opaque_id)?;
+
+            Ok(Self {
+                full_id,
+                format: Format::Original(Original {
+                    colon_idx,
+                    server_name,
+                }),
+            })
         } else if !event_id.starts_with('$') {
-            Err(Error::MissingSigil)
-        } else if event_id.contains(|chr| chr == '+' || chr == '/') {
-            Ok(Self(Format::Base64(event_id[1..].to_string())))
+            Err(Error::new(ErrorKind::MissingSigil, &event_id))
         } else {
-            Ok(Self(Format::UrlSafeBase64(event_id[1..].to_string())))
+            let format = reference_hash_format(&event_id[SIGIL_BYTES..])
+                .map_err(|kind| Error::new(kind, &event_id))?;
+
+            Ok(Self {
+                full_id: event_id,
+                format,
+            })
         }
     }
 }
 
+/// Checks whether a string is a valid event ID in any of the formats, without constructing an
+/// `EventId`.
+///
+/// This performs the same checks as `EventId::try_from`.
+pub fn is_valid_event_id(event_id: &str) -> bool {
+    validation::validate(event_id).is_ok()
+}
+
+impl FromStr for EventId {
+    type Err = Error;
+
+    /// Attempts to create a new Matrix event ID from a string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::convert::TryFrom;
+    use std::{convert::TryFrom, net::Ipv4Addr, thread::sleep, time::Duration};
 
+    use rand::{rngs::StdRng, SeedableRng};
     use serde_json::{from_str, to_string};
 
-    use super::EventId;
-    use crate::error::Error;
+    use super::{is_valid_event_id, EventId, EventIdFormat};
+    use crate::{error::ErrorKind, Alphabet, EventIdRef, Host, RoomVersionId};
+
+    #[test]
+    fn event_id_from_owned_string() {
+        let raw_id = "$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk".to_string();
+        let raw_ptr = raw_id.as_ptr();
+        let event_id = EventId::try_from(raw_id).expect("Failed to create EventId.");
+
+        assert_eq!(event_id.as_str().as_ptr(), raw_ptr);
+        assert_eq!(
+            event_id,
+            EventId::try_from("$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk")
+                .expect("Failed to create EventId.")
+        );
+        assert_eq!(
+            EventId::try_from("$39hvsi03hlne:example.com:8448".to_string())
+                .expect("Failed to create EventId.")
+                .as_str(),
+            "$39hvsi03hlne:example.com"
+        );
+        assert_eq!(
+            EventId::try_from("$".to_string()).unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+    }
 
     #[test]
     fn valid_original_event_id() {
@@ -222,6 +725,120 @@ mod tests {
         )
     }
 
+    #[test]
+    fn parse_valid_original_event_id() {
+        assert_eq!(
+            "$39hvsi03hlne:example.com"
+                .parse::<EventId>()
+                .expect("Failed to parse EventId."),
+            EventId::try_from("$39hvsi03hlne:example.com").expect("Failed to create EventId.")
+        );
+    }
+
+    #[test]
+    fn parse_invalid_event_id() {
+        assert_eq!(
+            "39hvsi03hlne:example.com"
+                .parse::<EventId>()
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingSigil
+        );
+    }
+
+    #[test]
+    fn event_id_from_reference_hash() {
+        let base64 = EventId::from_reference_hash("acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk")
+            .expect("Failed to create EventId.");
+        let url_safe_base64 =
+            EventId::from_reference_hash("Rqnc-F-dvnEYJTyHq_iKxU2bZ1CI92-kuZq3a5lr5Zg")
+                .expect("Failed to create EventId.");
+
+        assert_eq!(
+            base64.to_string(),
+            "$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk"
+        );
+        assert_eq!(base64.hostname(), None);
+        assert_eq!(base64.port(), None);
+        assert_eq!(
+            url_safe_base64,
+            EventId::try_from("$Rqnc-F-dvnEYJTyHq_iKxU2bZ1CI92-kuZq3a5lr5Zg")
+                .expect("Failed to create EventId.")
+        );
+    }
+
+    #[test]
+    fn invalid_reference_hash() {
+        assert_eq!(
+            EventId::from_reference_hash("").unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+        assert_eq!(
+            EventId::from_reference_hash("39hvsi03hlne:example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidCharacters
+        );
+    }
+
+    #[test]
+    fn reference_hash_at_maximum_length() {
+        let event_id = format!("${}", "a".repeat(254));
+
+        assert_eq!(
+            EventId::try_from(event_id.as_str())
+                .expect("Failed to create EventId.")
+                .byte_len(),
+            255
+        );
+        assert!(EventId::try_from(event_id.clone()).is_ok());
+        assert!(is_valid_event_id(&event_id));
+    }
+
+    #[test]
+    fn reference_hash_exceeding_maximum_length() {
+        let event_id = format!("${}", "a".repeat(1000));
+
+        assert_eq!(
+            EventId::try_from(event_id.as_str()).unwrap_err().kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+        assert_eq!(
+            EventId::try_from(event_id.clone()).unwrap_err().kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+        assert_eq!(
+            EventId::from_reference_hash(&"a".repeat(255))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+        assert_eq!(
+            EventIdRef::try_from(event_id.as_str()).unwrap_err().kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+        assert!(!is_valid_event_id(&event_id));
+    }
+
+    #[test]
+    fn round_trip_event_ids() {
+        for id in &[
+            "$39hvsi03hlne:example.com",
+            "$39hvsi03hlne:example.com:5000",
+            "$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk",
+            "$Rqnc-F-dvnEYJTyHq_iKxU2bZ1CI92-kuZq3a5lr5Zg",
+        ] {
+            let event_id = EventId::try_from(*id).expect("Failed to create EventId.");
+            let json = to_string(&event_id).expect("Failed to convert EventId to JSON.");
+
+            assert_eq!(event_id.to_string(), *id);
+            assert_eq!(
+                from_str::<EventId>(&json).expect("Failed to convert JSON to EventId"),
+                event_id
+            );
+        }
+    }
+
     #[test]
     fn generate_random_valid_event_id() {
         let event_id = EventId::new("example.com")
@@ -232,11 +849,59 @@ mod tests {
         assert_eq!(event_id.len(), 31);
     }
 
+    #[test]
+    fn generate_seeded_event_id() {
+        let first = EventId::new_with_rng("example.com", &mut StdRng::seed_from_u64(42))
+            .expect("Failed to generate EventId.");
+        let second = EventId::new_with_rng("example.com", &mut StdRng::seed_from_u64(42))
+            .expect("Failed to generate EventId.");
+        let other = EventId::new_with_rng("example.com", &mut StdRng::seed_from_u64(43))
+            .expect("Failed to generate EventId.");
+
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+        assert!(EventId::try_from(first.as_str()).is_ok());
+    }
+
     #[test]
     fn generate_random_invalid_event_id() {
         assert!(EventId::new("").is_err());
     }
 
+    #[test]
+    fn generate_random_event_id_with_length() {
+        let event_id =
+            EventId::new_with_length("example.com", 32).expect("Failed to generate EventId.");
+
+        assert_eq!(event_id.localpart().len(), 32);
+        assert!(event_id
+            .localpart()
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric()));
+    }
+
+    #[test]
+    fn generate_random_event_id_with_invalid_length() {
+        assert_eq!(
+            EventId::new_with_length("example.com", 0)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingLocalpart
+        );
+        assert_eq!(
+            EventId::new_with_length("example.com", 250)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+        assert_eq!(
+            EventId::new_with_length("example.com", usize::MAX)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+    }
+
     #[test]
     fn serialize_valid_original_event_id() {
         assert_eq!(
@@ -304,7 +969,7 @@ mod tests {
     #[test]
     fn valid_original_event_id_with_explicit_standard_port() {
         assert_eq!(
-            EventId::try_from("$39hvsi03hlne:example.com:443")
+            EventId::try_from("$39hvsi03hlne:example.com:8448")
                 .expect("Failed to create EventId.")
                 .to_string(),
             "$39hvsi03hlne:example.com"
@@ -321,43 +986,590 @@ mod tests {
         );
     }
 
+    #[test]
+    fn server_name_of_original_event_id() {
+        assert_eq!(
+            EventId::try_from("$39hvsi03hlne:example.com:8448")
+                .expect("Failed to create EventId.")
+                .server_name(),
+            Some("example.com".to_string())
+        );
+        assert_eq!(
+            EventId::try_from("$39hvsi03hlne:example.com:5000")
+                .expect("Failed to create EventId.")
+                .server_name(),
+            Some("example.com:5000".to_string())
+        );
+    }
+
+    #[test]
+    fn server_name_of_url_safe_base64_event_id() {
+        assert_eq!(
+            EventId::try_from("$Rqnc-F-dvnEYJTyHq_iKxU2bZ1CI92-kuZq3a5lr5Zg")
+                .expect("Failed to create EventId.")
+                .server_name(),
+            None
+        );
+    }
+
+    #[test]
+    fn valid_original_event_id_with_multibyte_localpart() {
+        assert_eq!(
+            EventId::try_from("$老虎Â£я:example.com")
+                .expect("Failed to create EventId.")
+                .localpart(),
+            "老虎Â£я"
+        );
+    }
+
+    #[test]
+    fn missing_original_event_id_localpart() {
+        assert_eq!(
+            EventId::try_from("$:example.com").unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+    }
+
+    #[test]
+    fn missing_base64_event_id_localpart() {
+        assert_eq!(
+            EventId::try_from("$").unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+    }
+
     #[test]
     fn missing_original_event_id_sigil() {
         assert_eq!(
-            EventId::try_from("39hvsi03hlne:example.com").unwrap_err(),
-            Error::MissingSigil
+            EventId::try_from("39hvsi03hlne:example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingSigil
         );
     }
 
     #[test]
     fn missing_base64_event_id_sigil() {
         assert_eq!(
-            EventId::try_from("acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk").unwrap_err(),
-            Error::MissingSigil
+            EventId::try_from("acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingSigil
         );
     }
 
     #[test]
     fn missing_url_safe_base64_event_id_sigil() {
         assert_eq!(
-            EventId::try_from("Rqnc-F-dvnEYJTyHq_iKxU2bZ1CI92-kuZq3a5lr5Zg").unwrap_err(),
-            Error::MissingSigil
+            EventId::try_from("Rqnc-F-dvnEYJTyHq_iKxU2bZ1CI92-kuZq3a5lr5Zg")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingSigil
         );
     }
 
     #[test]
     fn invalid_event_id_host() {
         assert_eq!(
-            EventId::try_from("$39hvsi03hlne:/").unwrap_err(),
-            Error::InvalidHost
+            EventId::try_from("$39hvsi03hlne:/").unwrap_err().kind(),
+            ErrorKind::InvalidHost
         );
     }
 
     #[test]
     fn invalid_event_id_port() {
+        for id in &[
+            "$39hvsi03hlne:example.com:notaport",
+            "$39hvsi03hlne:example.com:65536",
+            "$39hvsi03hlne:example.com:0",
+        ] {
+            assert_eq!(
+                EventId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::InvalidPort
+            );
+        }
+    }
+
+    #[test]
+    fn event_ids_are_ordered_by_string_representation() {
+        let original =
+            EventId::try_from("$39hvsi03hlne:example.com").expect("Failed to create EventId.");
+        let url_safe_base64 = EventId::try_from("$Rqnc-F-dvnEYJTyHq_iKxU2bZ1CI92-kuZq3a5lr5Zg")
+            .expect("Failed to create EventId.");
+
+        assert!(original < url_safe_base64);
+    }
+
+    #[test]
+    fn event_id_preserving_port() {
+        for id in &[
+            "$39hvsi03hlne:example.com",
+            "$39hvsi03hlne:example.com:8448",
+            "$39hvsi03hlne:example.com:5000",
+            "$Rqnc-F-dvnEYJTyHq_iKxU2bZ1CI92-kuZq3a5lr5Zg",
+        ] {
+            assert_eq!(
+                EventId::try_from(*id)
+                    .expect("Failed to create EventId.")
+                    .to_string_preserving_port(),
+                *id
+            );
+        }
+    }
+
+    #[test]
+    fn is_valid_event_id_matches_try_from() {
+        assert!(is_valid_event_id("$39hvsi03hlne:example.com"));
+        assert!(is_valid_event_id(
+            "$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk"
+        ));
+        assert!(is_valid_event_id(
+            "$Rqnc-F-dvnEYJTyHq_iKxU2bZ1CI92-kuZq3a5lr5Zg"
+        ));
+        assert!(!is_valid_event_id("$"));
+        assert!(!is_valid_event_id("39hvsi03hlne:example.com"));
+        assert!(!is_valid_event_id(
+            "acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk"
+        ));
+    }
+
+    #[test]
+    fn event_id_from_parts() {
+        let event_id =
+            EventId::from_parts("39hvsi03hlne", "example.com").expect("Failed to create EventId.");
+
+        assert_eq!(
+            event_id,
+            EventId::try_from("$39hvsi03hlne:example.com").expect("Failed to create EventId.")
+        );
+        assert_eq!(event_id.localpart(), "39hvsi03hlne");
+        assert_eq!(event_id.server_name(), Some("example.com".to_string()));
+    }
+
+    #[test]
+    fn event_id_is_ip_literal() {
+        assert!(!EventId::try_from("$39hvsi03hlne:example.com")
+            .expect("Failed to create EventId.")
+            .is_ip_literal());
+        assert!(EventId::try_from("$39hvsi03hlne:1.2.3.4:5000")
+            .expect("Failed to create EventId.")
+            .is_ip_literal());
+        assert!(EventId::try_from("$39hvsi03hlne:[::1]")
+            .expect("Failed to create EventId.")
+            .is_ip_literal());
+        assert!(
+            !EventId::try_from("$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk")
+                .expect("Failed to create EventId.")
+                .is_ip_literal()
+        );
+    }
+
+    #[test]
+    fn event_id_parts() {
+        assert_eq!(
+            EventId::try_from("$39hvsi03hlne:example.com:8448")
+                .expect("Failed to create EventId.")
+                .parts(),
+            ("39hvsi03hlne", Some("example.com".to_string()))
+        );
+        assert_eq!(
+            EventId::try_from("$39hvsi03hlne:example.com:5000")
+                .expect("Failed to create EventId.")
+                .parts(),
+            ("39hvsi03hlne", Some("example.com:5000".to_string()))
+        );
+        assert_eq!(
+            EventId::try_from("$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk")
+                .expect("Failed to create EventId.")
+                .parts(),
+            ("acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk", None)
+        );
+    }
+
+    #[test]
+    fn event_id_with_surrounding_whitespace() {
+        for id in &[
+            " $39hvsi03hlne:example.com",
+            "$39hvsi03hlne:example.com ",
+            "\t$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk",
+            "$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk\t",
+        ] {
+            assert_eq!(
+                EventId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::SurroundingWhitespace
+            );
+            assert_eq!(
+                EventId::try_from(id.to_string()).unwrap_err().kind(),
+                ErrorKind::SurroundingWhitespace
+            );
+        }
+    }
+
+    #[test]
+    fn event_id_host_str() {
+        for (event_id, host) in &[
+            ("$39hvsi03hlne:Example.com", "example.com"),
+            ("$39hvsi03hlne:1.2.3.4:5000", "1.2.3.4"),
+            ("$39hvsi03hlne:[::1]:5000", "[::1]"),
+        ] {
+            assert_eq!(
+                EventId::try_from(*event_id)
+                    .expect("Failed to create EventId.")
+                    .host_str(),
+                Some(*host)
+            );
+        }
+        assert_eq!(
+            EventId::try_from("$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk")
+                .expect("Failed to create EventId.")
+                .host_str(),
+            None
+        );
+    }
+
+    #[test]
+    fn event_id_from_host() {
+        let event_id =
+            EventId::from_host("39hvsi03hlne", Host::Ipv4(Ipv4Addr::new(1, 2, 3, 4)), 5000)
+                .expect("Failed to create EventId.");
+
+        assert_eq!(event_id, "$39hvsi03hlne:1.2.3.4:5000");
+        assert_eq!(event_id.port(), Some(5000));
+        assert_eq!(
+            EventId::from_host(
+                "39hvsi03hlne",
+                Host::Domain("exa/mple.com".to_string()),
+                5000
+            )
+            .unwrap_err()
+            .kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+
+    #[test]
+    fn generate_random_event_id_with_alphabet() {
+        for &(alphabet, chars) in &[
+            (
+                Alphabet::LowercaseAlphanumeric,
+                "abcdefghijklmnopqrstuvwxyz0123456789",
+            ),
+            (
+                Alphabet::Base32Unambiguous,
+                "0123456789abcdefghjkmnpqrstvwxyz",
+            ),
+        ] {
+            let generated_id = EventId::new_with_alphabet("example.com", alphabet)
+                .expect("Failed to generate EventId.");
+            let id_str = generated_id.to_string();
+
+            assert!(id_str.ends_with(":example.com"));
+            assert_eq!(id_str.len(), 31);
+            assert!(generated_id.localpart().chars().all(|c| chars.contains(c)));
+            assert_eq!(
+                EventId::try_from(id_str.as_str()).expect("Failed to create EventId."),
+                generated_id
+            );
+        }
+    }
+
+    #[test]
+    fn generate_sortable_event_id() {
+        let first = EventId::new_sortable("example.com").expect("Failed to generate EventId.");
+        sleep(Duration::from_millis(2));
+        let second = EventId::new_sortable("example.com").expect("Failed to generate EventId.");
+
+        assert_eq!(first.localpart().len(), 26);
+        assert!(second > first);
+        assert_eq!(
+            EventId::try_from(second.as_str()).expect("Failed to create EventId."),
+            second
+        );
+    }
+
+    #[test]
+    fn generate_sortable_event_id_with_invalid_homeserver() {
+        assert!(EventId::new_sortable("").is_err());
+    }
+
+    #[test]
+    fn sigil_only_event_id() {
+        assert_eq!(
+            EventId::try_from("$").unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+        assert_eq!(
+            EventId::try_from("$".to_string()).unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+        assert!(!is_valid_event_id("$"));
+    }
+
+    #[test]
+    fn event_id_with_leading_multibyte_character() {
+        for id in &["é:example.com", "老虎:example.com", "éa:example.com"] {
+            assert_eq!(
+                EventId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::MissingSigil
+            );
+            assert_eq!(
+                EventId::try_from(id.to_string()).unwrap_err().kind(),
+                ErrorKind::MissingSigil
+            );
+            assert!(!is_valid_event_id(id));
+        }
+    }
+
+    #[test]
+    fn event_id_into_string() {
+        let id =
+            EventId::try_from("$39hvsi03hlne:example.com:8448").expect("Failed to create EventId.");
+        let string = id.to_string();
+
+        assert_eq!(id.into_string(), string);
+    }
+
+    #[test]
+    fn event_id_same_server() {
+        let event_id =
+            EventId::try_from("$39hvsi03hlne:example.com").expect("Failed to create EventId.");
+
+        assert!(event_id.same_server(
+            &EventId::try_from("$acR1l0raoZnm60CBwAVgqbZqoO:example.com:8448")
+                .expect("Failed to create EventId.")
+        ));
+        assert!(!event_id.same_server(
+            &EventId::try_from("$39hvsi03hlne:example.com:5000")
+                .expect("Failed to create EventId.")
+        ));
+
+        let base64_event_id = EventId::try_from("$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk")
+            .expect("Failed to create EventId.");
+
+        assert!(!base64_event_id.same_server(&base64_event_id));
+        assert!(!event_id.same_server(&base64_event_id));
+    }
+
+    #[test]
+    fn deserialize_invalid_event_id_error_message() {
+        assert_eq!(
+            from_str::<EventId>(r#""$39hvsi03hlne:exa mple.com""#)
+                .unwrap_err()
+                .to_string(),
+            "invalid Matrix ID: server name is not a valid IP address or domain name: \
+             \"$39hvsi03hlne:exa mple.com\", expected a Matrix event ID as a string at line 1 \
+             column 28"
+        );
+    }
+
+    #[test]
+    fn event_id_explicit_port() {
         assert_eq!(
-            EventId::try_from("$39hvsi03hlne:example.com:notaport").unwrap_err(),
-            Error::InvalidHost
+            EventId::try_from("$39hvsi03hlne:example.com")
+                .expect("Failed to create EventId.")
+                .explicit_port(),
+            None
         );
+        assert_eq!(
+            EventId::try_from("$39hvsi03hlne:example.com:8448")
+                .expect("Failed to create EventId.")
+                .explicit_port(),
+            Some(8448)
+        );
+        assert_eq!(
+            EventId::try_from("$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk")
+                .expect("Failed to create EventId.")
+                .explicit_port(),
+            None
+        );
+    }
+
+    #[test]
+    fn invalid_characters_in_event_id_localpart() {
+        for event_id in &[
+            "$bad event:example.com",
+            "$bad\tevent:example.com",
+            "$bad\u{1}event:example.com",
+            "$acR1l0raoZnm60CBwAVgqbZ qoO",
+            "$acR1l0raoZnm60CBwAVgqbZ\u{7f}qoO",
+        ] {
+            assert_eq!(
+                EventId::try_from(*event_id).unwrap_err().kind(),
+                ErrorKind::InvalidCharacters
+            );
+            assert_eq!(
+                EventId::try_from(event_id.to_string()).unwrap_err().kind(),
+                ErrorKind::InvalidCharacters
+            );
+            assert!(!is_valid_event_id(event_id));
+        }
+    }
+
+    #[test]
+    fn event_id_matches_format() {
+        let original =
+            EventId::try_from("$39hvsi03hlne:example.com").expect("Failed to create EventId.");
+        let base64 = EventId::try_from("$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk")
+            .expect("Failed to create EventId.");
+        let url_safe_base64 = EventId::try_from("$Rqnc-F-dvnEYJTyHq_iKxU2bZ1CI92-kuZq3a5lr5Zg")
+            .expect("Failed to create EventId.");
+        let ambiguous =
+            EventId::try_from("$acR1l0raoZnm60CBwAVgqbZqoO").expect("Failed to create EventId.");
+
+        assert!(original.matches_format(EventIdFormat::Original));
+        assert!(!original.matches_format(EventIdFormat::UrlSafeBase64));
+        assert!(base64.matches_format(EventIdFormat::Base64));
+        assert!(!base64.matches_format(EventIdFormat::UrlSafeBase64));
+        assert!(url_safe_base64.matches_format(EventIdFormat::UrlSafeBase64));
+        assert!(!url_safe_base64.matches_format(EventIdFormat::Base64));
+        assert!(ambiguous.matches_format(EventIdFormat::Base64));
+        assert!(ambiguous.matches_format(EventIdFormat::UrlSafeBase64));
+        assert!(!ambiguous.matches_format(EventIdFormat::Original));
+    }
+
+    #[test]
+    fn parse_event_id_for_version_with_original_format() {
+        for version in &[RoomVersionId::version_1(), RoomVersionId::version_2()] {
+            assert!(EventId::parse_for_version("$h29iv0s8:example.com", version).is_ok());
+            assert_eq!(
+                EventId::parse_for_version("$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk", version)
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::MissingDelimiter
+            );
+        }
+    }
+
+    #[test]
+    fn parse_event_id_for_version_3() {
+        let version = RoomVersionId::version_3();
+
+        assert_eq!(
+            EventId::parse_for_version("$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk", &version)
+                .expect("Failed to create EventId."),
+            EventId::try_from("$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk")
+                .expect("Failed to create EventId.")
+        );
+        for event_id in &[
+            "$h29iv0s8:example.com",
+            "$Rqnc-F-dvnEYJTyHq_iKxU2bZ1CI92-kuZq3a5lr5Zg",
+            "$acR1l0raoZnm60CBwAVgqbZqoO=",
+            "$ab+c-d_e",
+            "$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7X_Jk",
+        ] {
+            assert_eq!(
+                EventId::parse_for_version(event_id, &version)
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::InvalidCharacters
+            );
+        }
+    }
+
+    #[test]
+    fn parse_event_id_for_version_4_and_later() {
+        for version in RoomVersionId::official_versions().skip(3) {
+            assert!(EventId::parse_for_version(
+                "$Rqnc-F-dvnEYJTyHq_iKxU2bZ1CI92-kuZq3a5lr5Zg",
+                &version
+            )
+            .is_ok());
+            for event_id in &[
+                "$h29iv0s8:example.com",
+                "$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk",
+                "$ab+c-d_e",
+                "$Rqnc-F-dvnEYJTyHq_iKxU2bZ1CI92-kuZq3a5lr5/g",
+            ] {
+                assert_eq!(
+                    EventId::parse_for_version(event_id, &version)
+                        .unwrap_err()
+                        .kind(),
+                    ErrorKind::InvalidCharacters
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn parse_event_id_for_version_with_wrong_hash_length() {
+        for (version, hash) in &[
+            (
+                RoomVersionId::version_3(),
+                "acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk",
+            ),
+            (
+                RoomVersionId::version_5(),
+                "Rqnc-F-dvnEYJTyHq_iKxU2bZ1CI92-kuZq3a5lr5Zg",
+            ),
+        ] {
+            assert_eq!(
+                EventId::parse_for_version(&format!("${}", &hash[1..]), version)
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::MinimumLengthNotSatisfied
+            );
+            assert_eq!(
+                EventId::parse_for_version(&format!("${}A", hash), version)
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::MaximumLengthExceeded
+            );
+        }
+
+        assert_eq!(
+            EventId::parse_for_version("$abc", &RoomVersionId::version_4())
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MinimumLengthNotSatisfied
+        );
+    }
+
+    #[test]
+    fn parse_event_id_for_custom_version() {
+        let version = RoomVersionId::custom("io.ruma.1");
+
+        assert!(EventId::parse_for_version("$h29iv0s8:example.com", &version).is_ok());
+        assert!(EventId::parse_for_version(
+            "$Rqnc-F-dvnEYJTyHq_iKxU2bZ1CI92-kuZq3a5lr5Zg",
+            &version
+        )
+        .is_ok());
+        assert_eq!(
+            EventId::parse_for_version("h29iv0s8:example.com", &version)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingSigil
+        );
+    }
+
+    #[test]
+    fn event_id_from_parts_with_invalid_characters() {
+        for localpart in &["bad ev", "bad\tev", "bad ev\n", "bad\u{7f}"] {
+            assert_eq!(
+                EventId::from_parts(localpart, "example.com")
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::InvalidCharacters
+            );
+            assert_eq!(
+                EventId::from_host(localpart, Host::Domain("example.com".to_string()), 5000)
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::InvalidCharacters
+            );
+        }
+    }
+
+    #[test]
+    fn event_id_from_parts_round_trip() {
+        for localpart in &["39hvsi03hlne", "老虎", "a-b_c.d$"] {
+            let event_id =
+                EventId::from_parts(localpart, "example.com").expect("Failed to create EventId.");
+
+            assert_eq!(
+                EventId::try_from(event_id.as_str()).expect("Failed to create EventId."),
+                event_id
+            );
+        }
     }
 }
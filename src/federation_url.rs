@@ -0,0 +1,147 @@
+//! Implements `federation_base_url` and `resolution_target` for identifiers that always contain
+//! a server name.
+
+use alloc::{format, string::String};
+
+use crate::Host;
+
+/// Formats the HTTPS base URL of a homeserver's federation API, always including the port.
+pub(crate) fn federation_base_url(host: &Host, port: u16) -> String {
+    format!("https://{}:{}/", host, port)
+}
+
+/// Borrows the domain of a host, copying IP addresses.
+pub(crate) fn borrow_host(host: &Host) -> Host<&str> {
+    match host {
+        Host::Domain(domain) => Host::Domain(domain),
+        Host::Ipv4(address) => Host::Ipv4(*address),
+        Host::Ipv6(address) => Host::Ipv6(*address),
+    }
+}
+
+macro_rules! federation_base_url_impl {
+    ($name:ident) => {
+        impl $crate::$name {
+            /// Returns the HTTPS base URL of the originating homeserver's federation API, like
+            /// `https://example.com:8448/`.
+            ///
+            /// See `ServerName::federation_base_url`.
+            pub fn federation_base_url(&self) -> String {
+                $crate::federation_url::federation_base_url(self.hostname(), self.port())
+            }
+
+            /// Returns the host and the port of the originating homeserver in one call, with the
+            /// domain borrowed instead of being copied.
+            ///
+            /// See `ServerName::resolution_target`.
+            pub fn resolution_target(&self) -> ($crate::Host<&str>, u16) {
+                (
+                    $crate::federation_url::borrow_host(self.hostname()),
+                    self.port(),
+                )
+            }
+        }
+    };
+}
+
+federation_base_url_impl!(GroupId);
+federation_base_url_impl!(RoomAliasId);
+federation_base_url_impl!(RoomId);
+federation_base_url_impl!(UserId);
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use crate::{EventId, Host, RoomAliasId, RoomId, ServerName, UserId};
+
+    #[test]
+    fn federation_base_url_with_default_port() {
+        assert_eq!(
+            ServerName::try_from("example.com")
+                .expect("Failed to create ServerName.")
+                .federation_base_url(),
+            "https://example.com:8448/"
+        );
+        assert_eq!(
+            UserId::try_from("@carl:example.com:8448")
+                .expect("Failed to create UserId.")
+                .federation_base_url(),
+            "https://example.com:8448/"
+        );
+    }
+
+    #[test]
+    fn federation_base_url_with_explicit_port() {
+        assert_eq!(
+            ServerName::try_from("example.com:443")
+                .expect("Failed to create ServerName.")
+                .federation_base_url(),
+            "https://example.com:443/"
+        );
+        assert_eq!(
+            RoomAliasId::try_from("#ruma:[::1]:5000")
+                .expect("Failed to create RoomAliasId.")
+                .federation_base_url(),
+            "https://[::1]:5000/"
+        );
+    }
+
+    #[test]
+    fn federation_base_url_of_event_ids() {
+        assert_eq!(
+            EventId::try_from("$39hvsi03hlne:example.com")
+                .expect("Failed to create EventId.")
+                .federation_base_url()
+                .as_deref(),
+            Some("https://example.com:8448/")
+        );
+        assert_eq!(
+            EventId::try_from("$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk")
+                .expect("Failed to create EventId.")
+                .federation_base_url(),
+            None
+        );
+    }
+
+    #[test]
+    fn resolution_target_of_domain() {
+        let server_name =
+            ServerName::try_from("Example.com:5000").expect("Failed to create ServerName.");
+
+        assert_eq!(
+            server_name.resolution_target(),
+            (Host::Domain("example.com"), 5000)
+        );
+        assert_eq!(
+            UserId::try_from("@carl:example.com")
+                .expect("Failed to create UserId.")
+                .resolution_target(),
+            (Host::Domain("example.com"), 8448)
+        );
+    }
+
+    #[test]
+    fn resolution_target_of_ip_address() {
+        assert_eq!(
+            RoomId::try_from("!n8f893n9:127.0.0.1:5000")
+                .expect("Failed to create RoomId.")
+                .resolution_target(),
+            (Host::Ipv4(Ipv4Addr::LOCALHOST), 5000)
+        );
+        assert_eq!(
+            ServerName::try_from("[::1]")
+                .expect("Failed to create ServerName.")
+                .resolution_target(),
+            (Host::Ipv6(Ipv6Addr::LOCALHOST), 8448)
+        );
+        assert_eq!(
+            EventId::try_from("$39hvsi03hlne:[::1]:5000")
+                .expect("Failed to create EventId.")
+                .resolution_target(),
+            Some((Host::Ipv6(Ipv6Addr::LOCALHOST), 5000))
+        );
+    }
+}
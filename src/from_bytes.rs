@@ -0,0 +1,95 @@
+//! Implements `TryFrom<&[u8]>` for identifiers, for parsing them straight out of byte buffers.
+
+use alloc::string::String;
+use core::{convert::TryFrom, str};
+
+use crate::error::{Error, ErrorKind};
+
+/// Interprets bytes as a string, failing with `ErrorKind::InvalidUtf8` if they aren't valid UTF-8.
+///
+/// The input of the error is the bytes with invalid sequences replaced by U+FFFD.
+fn str_from_utf8(bytes: &[u8]) -> Result<&str, Error> {
+    str::from_utf8(bytes)
+        .map_err(|_| Error::new(ErrorKind::InvalidUtf8, &String::from_utf8_lossy(bytes)))
+}
+
+macro_rules! try_from_bytes_impl {
+    ($name:ident) => {
+        impl TryFrom<&[u8]> for $crate::$name {
+            type Error = Error;
+
+            /// Attempts to create an ID from the bytes of its string representation.
+            ///
+            /// The bytes must be valid UTF-8, and are then parsed like with the `TryFrom<&str>`
+            /// implementation.
+            fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+                Self::try_from(str_from_utf8(bytes)?)
+            }
+        }
+    };
+}
+
+try_from_bytes_impl!(EventId);
+try_from_bytes_impl!(GroupId);
+try_from_bytes_impl!(RoomAliasId);
+try_from_bytes_impl!(RoomId);
+try_from_bytes_impl!(UserId);
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::{error::ErrorKind, EventId, RoomAliasId, RoomId, UserId};
+
+    #[test]
+    fn valid_ids_from_bytes() {
+        assert_eq!(
+            EventId::try_from(&b"$39hvsi03hlne:example.com"[..])
+                .expect("Failed to create EventId."),
+            EventId::try_from("$39hvsi03hlne:example.com").expect("Failed to create EventId.")
+        );
+        assert_eq!(
+            RoomId::try_from(&b"!29fhd83h92h0:example.com"[..]).expect("Failed to create RoomId."),
+            "!29fhd83h92h0:example.com"
+        );
+        assert_eq!(
+            RoomAliasId::try_from("#老虎Â£я:example.com".as_bytes())
+                .expect("Failed to create RoomAliasId."),
+            "#老虎Â£я:example.com"
+        );
+        assert_eq!(
+            UserId::try_from(&b"@carl:example.com"[..]).expect("Failed to create UserId."),
+            "@carl:example.com"
+        );
+    }
+
+    #[test]
+    fn invalid_utf8_id_from_bytes() {
+        let error = UserId::try_from(&b"@ca\xffrl:example.com"[..]).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidUtf8);
+        assert_eq!(error.input(), "@ca\u{fffd}rl:example.com");
+        assert_eq!(
+            RoomId::try_from(&b"!29fhd83h92h0:example.\xc3"[..])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidUtf8
+        );
+    }
+
+    #[test]
+    fn invalid_id_from_bytes() {
+        assert_eq!(
+            UserId::try_from(&b"carl:example.com"[..])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingSigil
+        );
+        assert_eq!(
+            EventId::try_from(&b"$39hvsi03hlne:exa mple.com"[..])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+}
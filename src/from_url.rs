@@ -0,0 +1,214 @@
+//! Implements `TryFrom<&Url>` for identifiers, extracting them from matrix.to permalinks and
+//! `matrix:` URIs.
+
+use core::convert::TryFrom;
+
+use url::Url;
+
+use crate::{
+    error::{Error, ErrorKind},
+    EventId, MatrixId, MatrixToUri, MatrixUri, RoomAliasId, RoomId, RoomIdOrAliasId, UserId,
+};
+
+impl TryFrom<&Url> for MatrixToUri {
+    type Error = Error;
+
+    /// Attempts to create a new matrix.to permalink from a parsed URL.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation with the serialization of the URL.
+    fn try_from(url: &Url) -> Result<Self, Error> {
+        Self::try_from(url.as_str())
+    }
+}
+
+impl TryFrom<&Url> for MatrixUri {
+    type Error = Error;
+
+    /// Attempts to create a new `matrix:` URI from a parsed URL.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation with the serialization of the URL.
+    fn try_from(url: &Url) -> Result<Self, Error> {
+        Self::try_from(url.as_str())
+    }
+}
+
+/// Parses the identifier a matrix.to permalink or a `matrix:` URI refers to, depending on the
+/// scheme of the URL.
+fn linked_id(url: &Url) -> Result<MatrixId, Error> {
+    if url.scheme() == "matrix" {
+        return MatrixUri::try_from(url).map(|uri| uri.id().clone());
+    }
+
+    let uri = MatrixToUri::try_from(url)?;
+
+    Ok(match (uri.user_id(), uri.room(), uri.event_id()) {
+        (Some(user_id), _, _) => MatrixId::User(user_id.clone()),
+        (None, Some(room), Some(event_id)) => MatrixId::Event(room.clone(), event_id.clone()),
+        (None, Some(room), None) => room.clone().into(),
+        // A permalink points to either a user or a room.
+        (None, None, _) => return Err(Error::new(ErrorKind::InvalidUriPath, url.as_str())),
+    })
+}
+
+/// Implements `TryFrom<&Url>` for an identifier that is extracted from the linked `MatrixId` with
+/// the given patterns.
+macro_rules! try_from_url_impl {
+    ($(#[$doc:meta])* $name:ident, $($pattern:pat => $id:expr),+ $(,)?) => {
+        impl TryFrom<&Url> for $name {
+            type Error = Error;
+
+            $(#[$doc])*
+            fn try_from(url: &Url) -> Result<Self, Error> {
+                match linked_id(url)? {
+                    $($pattern => Ok($id),)+
+                    _ => Err(Error::new(ErrorKind::InvalidUriPath, url.as_str())),
+                }
+            }
+        }
+    };
+}
+
+try_from_url_impl!(
+    /// Attempts to extract the event ID from a matrix.to permalink or a `matrix:` URI.
+    ///
+    /// The URL must be an event permalink. Other valid links are rejected with
+    /// `ErrorKind::InvalidUriPath`.
+    EventId,
+    MatrixId::Event(_, event_id) => event_id,
+);
+try_from_url_impl!(
+    /// Attempts to extract the room alias ID from a matrix.to permalink or a `matrix:` URI.
+    ///
+    /// The URL must be a link to a room alias or an event in it. Other valid links are rejected
+    /// with `ErrorKind::InvalidUriPath`.
+    RoomAliasId,
+    MatrixId::RoomAlias(room_alias_id) => room_alias_id,
+    MatrixId::Event(RoomIdOrAliasId::RoomAliasId(room_alias_id), _) => room_alias_id,
+);
+try_from_url_impl!(
+    /// Attempts to extract the room ID from a matrix.to permalink or a `matrix:` URI.
+    ///
+    /// The URL must be a link to a room ID or an event in it. Other valid links are rejected with
+    /// `ErrorKind::InvalidUriPath`.
+    RoomId,
+    MatrixId::Room(room_id) => room_id,
+    MatrixId::Event(RoomIdOrAliasId::RoomId(room_id), _) => room_id,
+);
+try_from_url_impl!(
+    /// Attempts to extract the room ID or room alias ID from a matrix.to permalink or a `matrix:`
+    /// URI.
+    ///
+    /// The URL must be a link to a room or an event in it. Other valid links are rejected with
+    /// `ErrorKind::InvalidUriPath`.
+    RoomIdOrAliasId,
+    MatrixId::Room(room_id) => RoomIdOrAliasId::RoomId(room_id),
+    MatrixId::RoomAlias(room_alias_id) => RoomIdOrAliasId::RoomAliasId(room_alias_id),
+    MatrixId::Event(room, _) => room,
+);
+try_from_url_impl!(
+    /// Attempts to extract the user ID from a matrix.to permalink or a `matrix:` URI.
+    ///
+    /// The URL must be a link to a user. Other valid links are rejected with
+    /// `ErrorKind::InvalidUriPath`.
+    UserId,
+    MatrixId::User(user_id) => user_id,
+);
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use url::Url;
+
+    use crate::{error::ErrorKind, EventId, RoomAliasId, RoomId, RoomIdOrAliasId, UserId};
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).expect("Failed to parse URL.")
+    }
+
+    #[test]
+    fn room_id_from_matrix_to_url() {
+        assert_eq!(
+            RoomId::try_from(&url(
+                "https://matrix.to/#/!n8f893n9:example.com?via=example.org"
+            ))
+            .expect("Failed to create RoomId."),
+            RoomId::try_from("!n8f893n9:example.com").expect("Failed to create RoomId.")
+        );
+    }
+
+    #[test]
+    fn event_id_from_matrix_to_url() {
+        let event_url = url(
+            "https://matrix.to/#/%21n8f893n9%3Aexample.com/%2439hvsi03hlne%3Aexample.com\
+             ?via=example.org",
+        );
+
+        assert_eq!(
+            EventId::try_from(&event_url).expect("Failed to create EventId."),
+            EventId::try_from("$39hvsi03hlne:example.com").expect("Failed to create EventId.")
+        );
+        assert_eq!(
+            RoomId::try_from(&event_url).expect("Failed to create RoomId."),
+            RoomId::try_from("!n8f893n9:example.com").expect("Failed to create RoomId.")
+        );
+    }
+
+    #[test]
+    fn user_id_from_matrix_to_url() {
+        let user_url = url("https://matrix.to/#/@carl:example.com");
+
+        assert_eq!(
+            UserId::try_from(&user_url).expect("Failed to create UserId."),
+            UserId::try_from("@carl:example.com").expect("Failed to create UserId.")
+        );
+        assert_eq!(
+            UserId::try_from(&url("https://matrix.to/#/%40carl%3Aexample.com"))
+                .expect("Failed to create UserId."),
+            UserId::try_from("@carl:example.com").expect("Failed to create UserId.")
+        );
+        assert_eq!(
+            RoomId::try_from(&user_url).unwrap_err().kind(),
+            ErrorKind::InvalidUriPath
+        );
+    }
+
+    #[test]
+    fn ids_from_matrix_scheme_url() {
+        assert_eq!(
+            UserId::try_from(&url("matrix:u/carl:example.com?action=chat"))
+                .expect("Failed to create UserId."),
+            UserId::try_from("@carl:example.com").expect("Failed to create UserId.")
+        );
+        assert_eq!(
+            RoomIdOrAliasId::try_from(&url("matrix:r/ruma:example.com"))
+                .expect("Failed to create RoomIdOrAliasId."),
+            RoomIdOrAliasId::try_from("#ruma:example.com")
+                .expect("Failed to create RoomIdOrAliasId.")
+        );
+    }
+
+    #[test]
+    fn mismatched_id_from_url() {
+        let room_url = "https://matrix.to/#/%23ruma:example.com";
+
+        let error = EventId::try_from(&url(room_url)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidUriPath);
+        assert_eq!(error.input(), room_url);
+        assert_eq!(
+            RoomId::try_from(&url(room_url)).unwrap_err().kind(),
+            ErrorKind::InvalidUriPath
+        );
+        assert!(RoomAliasId::try_from(&url(room_url)).is_ok());
+    }
+
+    #[test]
+    fn id_from_unrelated_url() {
+        assert_eq!(
+            RoomId::try_from(&url("https://example.com/#/!n8f893n9:example.com"))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidUriScheme
+        );
+    }
+}
@@ -0,0 +1,609 @@
+//! Matrix group identifiers.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
+#[cfg(feature = "diesel")]
+use diesel::sql_types::Text;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    canonical_host, deserialize_id, error::Error, format_id, host::Host, id_from_host,
+    id_from_parts, is_valid_id, parse_id, parse_owned_id, server_name::ServerName, SIGIL_BYTES,
+};
+
+/// A Matrix group ID.
+///
+/// Groups, also known as communities, are deprecated in favor of spaces, but group IDs are still
+/// found in existing data. A `GroupId` is converted from a string slice, and can be converted back
+/// into a string as needed.
+///
+/// Besides its components, a `GroupId` stores its canonical string representation. This takes up
+/// roughly twice the memory, but allows it to be borrowed as a `&str`, e.g. to look it up in a
+/// `HashMap` or `HashSet` with a string slice.
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use ruma_identifiers::GroupId;
+/// assert_eq!(
+///     GroupId::try_from("+ruma:example.com").unwrap().to_string(),
+///     "+ruma:example.com"
+/// );
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "diesel", derive(FromSqlRow, QueryId, AsExpression, SqlType))]
+#[cfg_attr(feature = "diesel", sql_type = "Text")]
+pub struct GroupId {
+    /// The group ID in its canonical string representation.
+    full_id: String,
+    /// The index of the colon separating the localpart and the server name in `full_id`.
+    colon_idx: usize,
+    /// The server name of the homeserver.
+    server_name: ServerName,
+}
+
+impl GroupId {
+    /// Creates a `GroupId` from its localpart and the server name of the homeserver.
+    ///
+    /// Unlike parsing a string, this only validates the server name. The localpart is trusted to be
+    /// valid, and only needs to be non-empty and free of colons.
+    pub fn from_parts(localpart: &str, server_name: &str) -> Result<Self, Error> {
+        let (full_id, colon_idx, server_name) =
+            id_from_parts('+', localpart, server_name, |_| Ok(()))?;
+
+        Ok(Self {
+            full_id,
+            colon_idx,
+            server_name,
+        })
+    }
+
+    /// Creates a `GroupId` from its localpart and an already parsed host and port of the
+    /// homeserver.
+    ///
+    /// Like `from_parts`, this only checks that the localpart is non-empty and free of colons. Of
+    /// the host and port, only the checks of `ServerName::from_host` apply.
+    pub fn from_host(localpart: &str, host: Host, port: u16) -> Result<Self, Error> {
+        let (full_id, colon_idx, server_name) =
+            id_from_host('+', localpart, host, port, |_| Ok(()))?;
+
+        Ok(Self {
+            full_id,
+            colon_idx,
+            server_name,
+        })
+    }
+
+    /// Creates a copy of the group ID with the same localpart, but the given server name.
+    ///
+    /// Fails if the server name is invalid.
+    pub fn with_server_name(&self, server_name: &str) -> Result<Self, Error> {
+        Self::from_parts(self.localpart(), server_name)
+    }
+
+    /// Returns a `Host` for the group ID, containing the server name (minus the port) of the
+    /// originating homeserver.
+    ///
+    /// The host can be either a domain name, an IPv4 address, or an IPv6 address.
+    pub fn hostname(&self) -> &Host {
+        self.server_name.host()
+    }
+
+    /// Whether the server name of the group ID is an IPv4 or IPv6 address rather than a domain
+    /// name.
+    pub fn is_ip_literal(&self) -> bool {
+        self.server_name.is_ip_literal()
+    }
+
+    /// Returns the host of the group ID's server name as a string slice, exactly as it appears in
+    /// the canonical string representation, i.e. without the port. IPv6 addresses are enclosed in
+    /// brackets.
+    pub fn host_str(&self) -> &str {
+        canonical_host(&self.full_id[self.colon_idx + 1..])
+    }
+
+    /// Returns the group ID as a string slice.
+    ///
+    /// The canonical string representation is stored when the group ID is created, so this
+    /// doesn't allocate, returns the same string as `to_string`, and can be called repeatedly for
+    /// free.
+    pub fn as_str(&self) -> &str {
+        &self.full_id
+    }
+
+    /// Converts the group ID into its string representation, without allocating.
+    ///
+    /// This returns the same string as `as_str` and `to_string`, but takes the stored string
+    /// instead of copying it.
+    pub fn into_string(self) -> String {
+        self.full_id
+    }
+
+    /// Returns the group's localpart.
+    pub fn localpart(&self) -> &str {
+        &self.full_id[SIGIL_BYTES..self.colon_idx]
+    }
+
+    /// Returns the port the originating homeserver can be accessed on.
+    pub fn port(&self) -> u16 {
+        self.server_name.port()
+    }
+
+    /// Returns the port of the originating homeserver if it was given explicitly, even if it is
+    /// `DEFAULT_PORT`, or `None` if the group ID doesn't specify one.
+    pub fn explicit_port(&self) -> Option<u16> {
+        self.server_name.explicit_port()
+    }
+
+    /// Returns the server name of the originating homeserver, i.e. its hostname followed by the
+    /// port if it isn't `DEFAULT_PORT`.
+    pub fn server_name(&self) -> String {
+        self.server_name.to_string()
+    }
+
+    /// Returns the localpart and the server name of the group ID in one call.
+    ///
+    /// Like `server_name`, the server name omits the port if it is `DEFAULT_PORT`.
+    pub fn parts(&self) -> (&str, String) {
+        (self.localpart(), self.server_name())
+    }
+
+    /// Returns the string representation of the ID, including the port if it was given explicitly,
+    /// even if it is the default port.
+    ///
+    /// Only the port is preserved, the rest of the ID is canonical. See
+    /// `ServerName::to_string_preserving_port`.
+    pub fn to_string_preserving_port(&self) -> String {
+        format!(
+            "+{}:{}",
+            self.localpart(),
+            self.server_name.to_string_preserving_port()
+        )
+    }
+}
+
+impl Display for GroupId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(&self.full_id)
+    }
+}
+
+impl PartialOrd for GroupId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Group IDs are ordered lexicographically by their string representation, not by their
+/// individual components.
+impl Ord for GroupId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Serialize for GroupId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for GroupId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_id(deserializer, "a Matrix group ID as a string")
+    }
+}
+
+impl TryFrom<&str> for GroupId {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix group ID from a string representation.
+    ///
+    /// The string must include the leading + sigil, the localpart, a literal colon, and a valid
+    /// server name.
+    fn try_from(group_id: &str) -> Result<Self, Error> {
+        let (localpart, server_name) = parse_id('+', group_id)?;
+
+        let (full_id, colon_idx) = format_id('+', localpart, &server_name);
+
+        Ok(Self {
+            full_id,
+            colon_idx,
+            server_name,
+        })
+    }
+}
+
+impl TryFrom<String> for GroupId {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix group ID from an owned string representation.
+    ///
+    /// This performs the same checks as the `TryFrom<&str>` implementation, but reuses the string
+    /// if it already is the canonical string representation of the group ID.
+    fn try_from(group_id: String) -> Result<Self, Error> {
+        let (full_id, colon_idx, server_name, ()) = parse_owned_id('+', group_id, |_| Ok(()))?;
+
+        Ok(Self {
+            full_id,
+            colon_idx,
+            server_name,
+        })
+    }
+}
+
+/// Checks whether a string is a valid group ID, without constructing a `GroupId`.
+///
+/// This performs the same checks as `GroupId::try_from`.
+pub fn is_valid_group_id(group_id: &str) -> bool {
+    is_valid_id('+', group_id, |_| Ok(()))
+}
+
+impl FromStr for GroupId {
+    type Err = Error;
+
+    /// Attempts to create a new Matrix group ID from a string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::TryFrom, net::Ipv6Addr};
+
+    use serde_json::{from_str, to_string};
+
+    use super::{is_valid_group_id, GroupId};
+    use crate::{error::ErrorKind, Host};
+
+    #[test]
+    fn valid_group_id() {
+        let group_id = GroupId::try_from("+ruma:example.com").expect("Failed to create GroupId.");
+
+        assert_eq!(group_id.to_string(), "+ruma:example.com");
+        assert_eq!(group_id.localpart(), "ruma");
+        assert_eq!(
+            group_id.hostname(),
+            &Host::Domain("example.com".to_string())
+        );
+        assert_eq!(group_id.port(), 8448);
+    }
+
+    #[test]
+    fn parse_valid_group_id() {
+        assert_eq!(
+            "+ruma:example.com"
+                .parse::<GroupId>()
+                .expect("Failed to parse GroupId."),
+            GroupId::try_from("+ruma:example.com").expect("Failed to create GroupId.")
+        );
+    }
+
+    #[test]
+    fn group_id_from_owned_string() {
+        let raw_id = "+ruma:example.com".to_string();
+        let raw_ptr = raw_id.as_ptr();
+        let group_id = GroupId::try_from(raw_id).expect("Failed to create GroupId.");
+
+        assert_eq!(group_id.as_str().as_ptr(), raw_ptr);
+    }
+
+    #[test]
+    fn serialize_valid_group_id() {
+        assert_eq!(
+            to_string(&GroupId::try_from("+ruma:example.com").expect("Failed to create GroupId."))
+                .expect("Failed to convert GroupId to JSON."),
+            r#""+ruma:example.com""#
+        );
+    }
+
+    #[test]
+    fn deserialize_valid_group_id() {
+        assert_eq!(
+            from_str::<GroupId>(r#""+ruma:example.com""#)
+                .expect("Failed to convert JSON to GroupId"),
+            GroupId::try_from("+ruma:example.com").expect("Failed to create GroupId.")
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid_group_id() {
+        let error = from_str::<GroupId>(r##""#ruma:example.com""##).unwrap_err();
+
+        assert!(error
+            .to_string()
+            .contains("expected a Matrix group ID as a string"));
+    }
+
+    #[test]
+    fn valid_group_id_with_explicit_standard_port() {
+        assert_eq!(
+            GroupId::try_from("+ruma:example.com:8448")
+                .expect("Failed to create GroupId.")
+                .to_string(),
+            "+ruma:example.com"
+        );
+    }
+
+    #[test]
+    fn valid_group_id_with_non_standard_port() {
+        let group_id =
+            GroupId::try_from("+ruma:example.com:5000").expect("Failed to create GroupId.");
+
+        assert_eq!(group_id.port(), 5000);
+        assert_eq!(group_id.server_name(), "example.com:5000");
+        assert_eq!(group_id.to_string(), "+ruma:example.com:5000");
+    }
+
+    #[test]
+    fn valid_group_id_with_ipv6_host() {
+        let group_id = GroupId::try_from("+ruma:[::1]:8448").expect("Failed to create GroupId.");
+
+        assert_eq!(group_id.localpart(), "ruma");
+        assert_eq!(
+            group_id.hostname(),
+            &Host::<String>::Ipv6(Ipv6Addr::LOCALHOST)
+        );
+        assert_eq!(group_id.to_string(), "+ruma:[::1]");
+        assert_eq!(group_id.to_string_preserving_port(), "+ruma:[::1]:8448");
+    }
+
+    #[test]
+    fn missing_group_id_sigil() {
+        assert_eq!(
+            GroupId::try_from("ruma:example.com").unwrap_err().kind(),
+            ErrorKind::MissingSigil
+        );
+        assert_eq!(
+            GroupId::try_from("#ruma:example.com").unwrap_err().kind(),
+            ErrorKind::MissingSigil
+        );
+    }
+
+    #[test]
+    fn missing_group_id_delimiter() {
+        assert_eq!(
+            GroupId::try_from("+ruma").unwrap_err().kind(),
+            ErrorKind::MissingDelimiter
+        );
+    }
+
+    #[test]
+    fn missing_group_id_localpart() {
+        assert_eq!(
+            GroupId::try_from("+:example.com").unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+    }
+
+    #[test]
+    fn invalid_group_id_host() {
+        assert_eq!(
+            GroupId::try_from("+ruma:/").unwrap_err().kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+
+    #[test]
+    fn invalid_group_id_port() {
+        for id in &[
+            "+ruma:example.com:notaport",
+            "+ruma:example.com:65536",
+            "+ruma:example.com:0",
+        ] {
+            assert_eq!(
+                GroupId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::InvalidPort
+            );
+        }
+    }
+
+    #[test]
+    fn group_id_too_long() {
+        let group_id = format!("+{}:example.com", "a".repeat(255));
+
+        assert_eq!(
+            GroupId::try_from(group_id.as_str()).unwrap_err().kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+    }
+
+    #[test]
+    fn group_id_from_parts() {
+        assert_eq!(
+            GroupId::from_parts("ruma", "example.com").expect("Failed to create GroupId."),
+            GroupId::try_from("+ruma:example.com").expect("Failed to create GroupId.")
+        );
+        assert_eq!(
+            GroupId::from_parts("ru:ma", "example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidCharacters
+        );
+    }
+
+    #[test]
+    fn is_valid_group_id_matches_try_from() {
+        assert!(is_valid_group_id("+ruma:example.com"));
+        assert!(!is_valid_group_id("#ruma:example.com"));
+        assert!(!is_valid_group_id("+ruma"));
+        assert!(!is_valid_group_id("+ruma:"));
+    }
+
+    #[test]
+    fn group_id_is_ip_literal() {
+        assert!(!GroupId::try_from("+ruma:example.com")
+            .expect("Failed to create GroupId.")
+            .is_ip_literal());
+        assert!(GroupId::try_from("+ruma:1.2.3.4:5000")
+            .expect("Failed to create GroupId.")
+            .is_ip_literal());
+        assert!(GroupId::try_from("+ruma:[::1]")
+            .expect("Failed to create GroupId.")
+            .is_ip_literal());
+    }
+
+    #[test]
+    fn group_id_parts() {
+        assert_eq!(
+            GroupId::try_from("+ruma:example.com:8448")
+                .expect("Failed to create GroupId.")
+                .parts(),
+            ("ruma", "example.com".to_string())
+        );
+        assert_eq!(
+            GroupId::try_from("+ruma:example.com:5000")
+                .expect("Failed to create GroupId.")
+                .parts(),
+            ("ruma", "example.com:5000".to_string())
+        );
+    }
+
+    #[test]
+    fn group_id_with_surrounding_whitespace() {
+        for id in &[
+            " +ruma:example.com",
+            "+ruma:example.com ",
+            "\t+ruma:example.com",
+            "+ruma:example.com\t",
+        ] {
+            assert_eq!(
+                GroupId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::SurroundingWhitespace
+            );
+            assert_eq!(
+                GroupId::try_from(id.to_string()).unwrap_err().kind(),
+                ErrorKind::SurroundingWhitespace
+            );
+        }
+    }
+
+    #[test]
+    fn group_id_with_server_name() {
+        let id = GroupId::try_from("+ruma:example.com").expect("Failed to create GroupId.");
+        let moved = id
+            .with_server_name("example.org:5000")
+            .expect("Failed to create GroupId.");
+
+        assert_eq!(moved.to_string(), "+ruma:example.org:5000");
+        assert_eq!(moved.parts(), ("ruma", "example.org:5000".to_string()));
+        assert_eq!(id.to_string(), "+ruma:example.com");
+        assert_eq!(
+            id.with_server_name("exa mple.org").unwrap_err().kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+
+    #[test]
+    fn group_id_host_str() {
+        assert_eq!(
+            GroupId::try_from("+ruma:Example.com")
+                .expect("Failed to create GroupId.")
+                .host_str(),
+            "example.com"
+        );
+        assert_eq!(
+            GroupId::try_from("+ruma:example.com:5000")
+                .expect("Failed to create GroupId.")
+                .host_str(),
+            "example.com"
+        );
+        assert_eq!(
+            GroupId::try_from("+ruma:1.2.3.4:8448")
+                .expect("Failed to create GroupId.")
+                .host_str(),
+            "1.2.3.4"
+        );
+        assert_eq!(
+            GroupId::try_from("+ruma:[::1]")
+                .expect("Failed to create GroupId.")
+                .host_str(),
+            "[::1]"
+        );
+        assert_eq!(
+            GroupId::try_from("+ruma:[0:0::1]:5000")
+                .expect("Failed to create GroupId.")
+                .host_str(),
+            "[::1]"
+        );
+    }
+
+    #[test]
+    fn group_id_from_host() {
+        assert_eq!(
+            GroupId::from_host("ruma", Host::Ipv6(Ipv6Addr::LOCALHOST), 5000)
+                .expect("Failed to create GroupId."),
+            "+ruma:[::1]:5000"
+        );
+        assert_eq!(
+            GroupId::from_host("ruma", Host::Domain("example.com".to_string()), 8448)
+                .expect("Failed to create GroupId."),
+            GroupId::try_from("+ruma:example.com").expect("Failed to create GroupId.")
+        );
+        assert_eq!(
+            GroupId::from_host("", Host::Domain("example.com".to_string()), 8448)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingLocalpart
+        );
+        assert_eq!(
+            GroupId::from_host("ruma", Host::Domain("example.com".to_string()), 0)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidPort
+        );
+    }
+
+    #[test]
+    fn sigil_only_group_id() {
+        assert_eq!(
+            GroupId::try_from("+").unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+        assert_eq!(
+            GroupId::try_from("+".to_string()).unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+        assert!(!is_valid_group_id("+"));
+    }
+
+    #[test]
+    fn group_id_with_leading_multibyte_character() {
+        for id in &["é:example.com", "老虎:example.com", "éa:example.com"] {
+            assert_eq!(
+                GroupId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::MissingSigil
+            );
+            assert_eq!(
+                GroupId::try_from(id.to_string()).unwrap_err().kind(),
+                ErrorKind::MissingSigil
+            );
+            assert!(!is_valid_group_id(id));
+        }
+    }
+
+    #[test]
+    fn group_id_into_string() {
+        let id = GroupId::try_from("+ruma:example.com:8448").expect("Failed to create GroupId.");
+        let string = id.to_string();
+
+        assert_eq!(id.into_string(), string);
+    }
+}
@@ -0,0 +1,257 @@
+//! Borrowed views of identifiers, for looking up owned identifiers without allocating.
+
+use alloc::borrow::{Borrow, Cow};
+use core::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt::{Display, Formatter, Result as FmtResult},
+    hash::{Hash, Hasher},
+};
+
+use crate::{canonical_id, error::Error, event_id::canonical_event_id, EventId, RoomId};
+
+macro_rules! id_ref {
+    ($(#[$attr:meta])* $name:ident, $owned:ident, $parse:expr) => {
+        $(#[$attr])*
+        #[derive(Clone, Debug)]
+        pub struct $name<'a>(Cow<'a, str>);
+
+        impl<'a> $name<'a> {
+            /// Returns the canonical string representation of the ID as a string slice.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// Converts the view into an owned ID.
+            ///
+            /// This allocates the owned ID's string, unless the view already holds a
+            /// canonicalized copy, which is reused.
+            pub fn to_owned(&self) -> $owned {
+                $owned::try_from(self.0.clone().into_owned())
+                    .expect("ID of a view failed to parse again.")
+            }
+        }
+
+        impl<'a> TryFrom<&'a str> for $name<'a> {
+            type Error = Error;
+
+            /// Attempts to create a view of an ID from a string representation.
+            ///
+            /// This performs the same checks as the owned ID's `TryFrom<&str>` implementation.
+            /// The string is only copied if it isn't canonical, e.g. because its server name has
+            /// an explicit default port.
+            fn try_from(id: &'a str) -> Result<Self, Error> {
+                $parse(id).map(Self)
+            }
+        }
+
+        impl<'a> From<&'a $owned> for $name<'a> {
+            fn from(id: &'a $owned) -> Self {
+                Self(Cow::Borrowed(id.as_str()))
+            }
+        }
+
+        impl Display for $name<'_> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl AsRef<str> for $name<'_> {
+            fn as_ref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl Borrow<str> for $name<'_> {
+            fn borrow(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl PartialEq for $name<'_> {
+            fn eq(&self, other: &Self) -> bool {
+                self.as_str() == other.as_str()
+            }
+        }
+
+        impl Eq for $name<'_> {}
+
+        impl PartialEq<$owned> for $name<'_> {
+            fn eq(&self, other: &$owned) -> bool {
+                self.as_str() == other.as_str()
+            }
+        }
+
+        impl PartialEq<$name<'_>> for $owned {
+            fn eq(&self, other: &$name<'_>) -> bool {
+                self.as_str() == other.as_str()
+            }
+        }
+
+        /// Hashes like the owned ID, so that either can be used to look up the other by its
+        /// string representation.
+        impl Hash for $name<'_> {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.as_str().hash(state);
+            }
+        }
+
+        impl PartialOrd for $name<'_> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        /// Views are ordered like the owned IDs, lexicographically by their string
+        /// representation.
+        impl Ord for $name<'_> {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.as_str().cmp(other.as_str())
+            }
+        }
+    };
+}
+
+id_ref!(
+    /// A borrowed view of a Matrix event ID.
+    ///
+    /// An `EventIdRef` is validated like an `EventId`, but borrows the string it is created from
+    /// instead of copying it. It compares equal to and hashes like the `EventId` with the same
+    /// string representation, so it can be used as a transient key, e.g. to look up event IDs in
+    /// a `HashSet<EventId>` by passing `as_str` to `contains`.
+    ///
+    /// ```
+    /// # use std::{collections::HashSet, convert::TryFrom};
+    /// # use ruma_identifiers::{EventId, EventIdRef};
+    /// let mut event_ids = HashSet::new();
+    /// event_ids.insert(EventId::try_from("$h29iv0s8:example.com").unwrap());
+    ///
+    /// let event_id = EventIdRef::try_from("$h29iv0s8:example.com").unwrap();
+    /// assert!(event_ids.contains(event_id.as_str()));
+    /// ```
+    EventIdRef,
+    EventId,
+    canonical_event_id
+);
+
+id_ref!(
+    /// A borrowed view of a Matrix room ID.
+    ///
+    /// A `RoomIdRef` is validated like a `RoomId`, but borrows the string it is created from
+    /// instead of copying it. It compares equal to and hashes like the `RoomId` with the same
+    /// string representation, so it can be used as a transient key, e.g. to look up room IDs in a
+    /// `HashSet<RoomId>` by passing `as_str` to `contains`.
+    RoomIdRef,
+    RoomId,
+    |id| canonical_id('!', id)
+);
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{hash_map::DefaultHasher, HashSet},
+        convert::TryFrom,
+        hash::{Hash, Hasher},
+    };
+
+    use super::{EventIdRef, RoomIdRef};
+    use crate::{error::ErrorKind, EventId, RoomId};
+
+    fn hash(value: &impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn event_id_ref_borrows_canonical_input() {
+        for id in &[
+            "$39hvsi03hlne:example.com",
+            "$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk",
+            "$Rqnc-F-dvnEYJTyHq_iKxU2bZ1CI92-kuZq3a5lr5Zg",
+        ] {
+            let event_id = EventIdRef::try_from(*id).expect("Failed to create EventIdRef.");
+
+            assert_eq!(event_id.as_str().as_ptr(), id.as_ptr());
+            assert_eq!(
+                event_id.to_owned(),
+                EventId::try_from(*id).expect("Failed to create EventId.")
+            );
+        }
+    }
+
+    #[test]
+    fn room_id_ref_canonicalizes_input() {
+        let room_id = RoomIdRef::try_from("!29fhd83h92h0:Example.com:8448")
+            .expect("Failed to create RoomIdRef.");
+
+        assert_eq!(room_id.as_str(), "!29fhd83h92h0:example.com");
+        assert_eq!(room_id.to_string(), "!29fhd83h92h0:example.com");
+        assert_eq!(
+            room_id,
+            RoomId::try_from("!29fhd83h92h0:example.com").expect("Failed to create RoomId.")
+        );
+    }
+
+    #[test]
+    fn id_refs_match_owned_ids() {
+        let owned =
+            EventId::try_from("$39hvsi03hlne:example.com").expect("Failed to create EventId.");
+        let view = EventIdRef::try_from("$39hvsi03hlne:example.com:8448")
+            .expect("Failed to create EventIdRef.");
+
+        assert_eq!(view, owned);
+        assert_eq!(owned, view);
+        assert_eq!(hash(&view), hash(&owned));
+        assert_eq!(EventIdRef::from(&owned), view);
+
+        let owned_ids: HashSet<_> = vec![owned.clone()].into_iter().collect();
+        assert!(owned_ids.contains(view.as_str()));
+
+        let views: HashSet<_> = vec![view].into_iter().collect();
+        assert!(views.contains(owned.as_str()));
+    }
+
+    #[test]
+    fn invalid_id_refs() {
+        assert_eq!(
+            EventIdRef::try_from("39hvsi03hlne:example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingSigil
+        );
+        assert_eq!(
+            EventIdRef::try_from("$39hvsi03hlne:exa mple.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidHost
+        );
+        assert_eq!(
+            RoomIdRef::try_from("!29fhd83h92h0").unwrap_err().kind(),
+            ErrorKind::MissingDelimiter
+        );
+        assert_eq!(
+            RoomIdRef::try_from(" !29fhd83h92h0:example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::SurroundingWhitespace
+        );
+    }
+
+    #[test]
+    fn id_ref_with_invalid_characters() {
+        assert_eq!(
+            RoomIdRef::try_from("!bad room:example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidCharacters
+        );
+        assert_eq!(
+            EventIdRef::try_from("$bad\nevent:example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidCharacters
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! A trait for identifiers that contain the server name of their originating homeserver.
+
+use alloc::string::String;
+
+use crate::Host;
+
+/// Common accessors of identifiers that always contain a localpart and the server name of their
+/// originating homeserver, like user IDs and room IDs.
+///
+/// The types implementing this trait have inherent methods of the same names, which behave
+/// identically. The trait allows writing code that is generic over these types:
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use ruma_identifiers::{Identifier, RoomId, UserId};
+/// fn federation_target<T: Identifier>(id: &T) -> String {
+///     format!("https://{}", id.server_name())
+/// }
+///
+/// let user_id = UserId::try_from("@carl:example.com").unwrap();
+/// let room_id = RoomId::try_from("!n8f893n9:example.org:5000").unwrap();
+/// assert_eq!(federation_target(&user_id), "https://example.com");
+/// assert_eq!(federation_target(&room_id), "https://example.org:5000");
+/// ```
+///
+/// `EventId` doesn't implement it, because event IDs in the formats used by Matrix room versions 3
+/// and later don't contain a server name.
+pub trait Identifier {
+    /// Returns the localpart of the ID, i.e. the part between the leading sigil and the colon.
+    fn localpart(&self) -> &str;
+
+    /// Returns a `Host` for the ID, containing the server name (minus the port) of the originating
+    /// homeserver.
+    fn hostname(&self) -> &Host;
+
+    /// Returns the port the originating homeserver can be accessed on.
+    fn port(&self) -> u16;
+
+    /// Returns the server name of the originating homeserver, i.e. its hostname followed by the
+    /// port if it isn't `DEFAULT_PORT`.
+    fn server_name(&self) -> String;
+}
+
+macro_rules! identifier_impl {
+    ($name:ident) => {
+        impl Identifier for $crate::$name {
+            fn localpart(&self) -> &str {
+                Self::localpart(self)
+            }
+
+            fn hostname(&self) -> &Host {
+                Self::hostname(self)
+            }
+
+            fn port(&self) -> u16 {
+                Self::port(self)
+            }
+
+            fn server_name(&self) -> String {
+                Self::server_name(self)
+            }
+        }
+    };
+}
+
+identifier_impl!(GroupId);
+identifier_impl!(RoomAliasId);
+identifier_impl!(RoomId);
+identifier_impl!(UserId);
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::Identifier;
+    use crate::{GroupId, Host, RoomAliasId, RoomId, UserId};
+
+    fn parts<T: Identifier>(id: &T) -> (&str, &Host, u16, String) {
+        (id.localpart(), id.hostname(), id.port(), id.server_name())
+    }
+
+    #[test]
+    fn identifier_accessors() {
+        let host = Host::Domain("example.com".to_string());
+
+        assert_eq!(
+            parts(&UserId::try_from("@carl:example.com").expect("Failed to create UserId.")),
+            ("carl", &host, 8448, "example.com".to_string())
+        );
+        assert_eq!(
+            parts(
+                &RoomId::try_from("!n8f893n9:example.com:5000").expect("Failed to create RoomId.")
+            ),
+            ("n8f893n9", &host, 5000, "example.com:5000".to_string())
+        );
+        assert_eq!(
+            parts(
+                &RoomAliasId::try_from("#ruma:example.com").expect("Failed to create RoomAliasId.")
+            ),
+            ("ruma", &host, 8448, "example.com".to_string())
+        );
+        assert_eq!(
+            parts(&GroupId::try_from("+ruma:example.com").expect("Failed to create GroupId.")),
+            ("ruma", &host, 8448, "example.com".to_string())
+        );
+    }
+}
@@ -0,0 +1,290 @@
+//! Identifiers of signing keys and device keys.
+
+use alloc::string::{String, ToString};
+use core::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
+#[cfg(feature = "diesel")]
+use diesel::sql_types::Text;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    deserialize_id,
+    error::{Error, ErrorKind},
+    MAX_BYTES,
+};
+
+/// The identifier of a server signing key or a device key.
+///
+/// A key ID consists of the algorithm of the key, a literal colon, and an identifier that
+/// distinguishes keys with the same algorithm, like `ed25519:abc123`. For device keys, the
+/// identifier is the device ID. A `KeyId` is converted from a string slice, and can be converted
+/// back into a string as needed.
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use ruma_identifiers::KeyId;
+/// let key_id = KeyId::try_from("ed25519:abc123").unwrap();
+/// assert_eq!(key_id.algorithm(), "ed25519");
+/// assert_eq!(key_id.key_id(), "abc123");
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "diesel", derive(FromSqlRow, QueryId, AsExpression, SqlType))]
+#[cfg_attr(feature = "diesel", sql_type = "Text")]
+pub struct KeyId {
+    /// The key ID in its string representation.
+    full_id: String,
+    /// The index of the colon separating the algorithm and the key identifier in `full_id`.
+    colon_idx: usize,
+}
+
+impl KeyId {
+    /// Returns the key ID as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.full_id
+    }
+
+    /// Converts the key ID into its string representation, without allocating.
+    ///
+    /// This returns the same string as `as_str` and `to_string`, but takes the stored string
+    /// instead of copying it.
+    pub fn into_string(self) -> String {
+        self.full_id
+    }
+
+    /// Returns the algorithm of the key, e.g. `ed25519`.
+    pub fn algorithm(&self) -> &str {
+        &self.full_id[..self.colon_idx]
+    }
+
+    /// Returns the identifier that distinguishes the key from others with the same algorithm,
+    /// e.g. a device ID.
+    pub fn key_id(&self) -> &str {
+        &self.full_id[self.colon_idx + 1..]
+    }
+}
+
+/// Checks a key ID and returns the index of the colon separating its parts.
+///
+/// The algorithm may only contain lowercase ASCII letters, digits and underscores. The key
+/// identifier may contain any printable ASCII character except for colons.
+fn validate_key_id(key_id: &str) -> Result<usize, ErrorKind> {
+    if key_id.len() > MAX_BYTES {
+        return Err(ErrorKind::MaximumLengthExceeded);
+    }
+
+    let colon_idx = match key_id.find(':') {
+        Some(index) => index,
+        None => return Err(ErrorKind::MissingDelimiter),
+    };
+
+    let algorithm = &key_id[..colon_idx];
+    let identifier = &key_id[colon_idx + 1..];
+
+    let is_valid_algorithm = !algorithm.is_empty()
+        && algorithm
+            .bytes()
+            .all(|b| matches!(b, b'a'..=b'z' | b'0'..=b'9' | b'_'));
+    let is_valid_identifier = !identifier.is_empty()
+        && identifier
+            .bytes()
+            .all(|b| b.is_ascii_graphic() && b != b':');
+
+    if !is_valid_algorithm || !is_valid_identifier {
+        return Err(ErrorKind::InvalidKeyId);
+    }
+
+    Ok(colon_idx)
+}
+
+impl Display for KeyId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(&self.full_id)
+    }
+}
+
+impl PartialOrd for KeyId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Key IDs are ordered lexicographically by their string representation.
+impl Ord for KeyId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Serialize for KeyId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_id(deserializer, "a Matrix key ID as a string")
+    }
+}
+
+impl TryFrom<&str> for KeyId {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix key ID from a string representation.
+    ///
+    /// The string must consist of the algorithm, a literal colon, and the key identifier.
+    fn try_from(key_id: &str) -> Result<Self, Error> {
+        Self::try_from(key_id.to_string())
+    }
+}
+
+impl TryFrom<String> for KeyId {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix key ID from an owned string representation.
+    ///
+    /// This performs the same checks as the `TryFrom<&str>` implementation, but reuses the string.
+    fn try_from(key_id: String) -> Result<Self, Error> {
+        let colon_idx = validate_key_id(&key_id).map_err(|kind| Error::new(kind, &key_id))?;
+
+        Ok(Self {
+            full_id: key_id,
+            colon_idx,
+        })
+    }
+}
+
+/// Checks whether a string is a valid key ID, without constructing a `KeyId`.
+///
+/// This performs the same checks as `KeyId::try_from`.
+pub fn is_valid_key_id(key_id: &str) -> bool {
+    validate_key_id(key_id).is_ok()
+}
+
+impl FromStr for KeyId {
+    type Err = Error;
+
+    /// Attempts to create a new Matrix key ID from a string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use serde_json::{from_str, to_string};
+
+    use super::{is_valid_key_id, KeyId};
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn valid_key_id() {
+        let key_id = KeyId::try_from("ed25519:abc123").expect("Failed to create KeyId.");
+
+        assert_eq!(key_id.to_string(), "ed25519:abc123");
+        assert_eq!(key_id.algorithm(), "ed25519");
+        assert_eq!(key_id.key_id(), "abc123");
+    }
+
+    #[test]
+    fn valid_device_key_id() {
+        let key_id =
+            KeyId::try_from("signed_curve25519:JLAFKJWSCS").expect("Failed to create KeyId.");
+
+        assert_eq!(key_id.algorithm(), "signed_curve25519");
+        assert_eq!(key_id.key_id(), "JLAFKJWSCS");
+    }
+
+    #[test]
+    fn parse_valid_key_id() {
+        assert_eq!(
+            "ed25519:abc123"
+                .parse::<KeyId>()
+                .expect("Failed to parse KeyId."),
+            KeyId::try_from("ed25519:abc123").expect("Failed to create KeyId.")
+        );
+    }
+
+    #[test]
+    fn serialize_valid_key_id() {
+        assert_eq!(
+            to_string(&KeyId::try_from("ed25519:abc123").expect("Failed to create KeyId."))
+                .expect("Failed to convert KeyId to JSON."),
+            r#""ed25519:abc123""#
+        );
+    }
+
+    #[test]
+    fn deserialize_valid_key_id() {
+        assert_eq!(
+            from_str::<KeyId>(r#""ed25519:abc123""#).expect("Failed to convert JSON to KeyId"),
+            KeyId::try_from("ed25519:abc123").expect("Failed to create KeyId.")
+        );
+    }
+
+    #[test]
+    fn missing_key_id_delimiter() {
+        assert_eq!(
+            KeyId::try_from("ed25519").unwrap_err().kind(),
+            ErrorKind::MissingDelimiter
+        );
+    }
+
+    #[test]
+    fn key_id_with_more_than_one_delimiter() {
+        assert_eq!(
+            KeyId::try_from("ed25519:abc:123").unwrap_err().kind(),
+            ErrorKind::InvalidKeyId
+        );
+    }
+
+    #[test]
+    fn key_id_with_empty_parts() {
+        for key_id in &[":abc123", "ed25519:", ":"] {
+            assert_eq!(
+                KeyId::try_from(*key_id).unwrap_err().kind(),
+                ErrorKind::InvalidKeyId
+            );
+        }
+    }
+
+    #[test]
+    fn key_id_with_invalid_characters() {
+        for key_id in &["Ed25519:abc123", "ed-25519:abc123", "ed25519:abc 123"] {
+            assert_eq!(
+                KeyId::try_from(*key_id).unwrap_err().kind(),
+                ErrorKind::InvalidKeyId
+            );
+        }
+    }
+
+    #[test]
+    fn is_valid_key_id_matches_try_from() {
+        assert!(is_valid_key_id("ed25519:abc123"));
+        assert!(!is_valid_key_id("ed25519"));
+        assert!(!is_valid_key_id("ed25519:abc:123"));
+    }
+
+    #[test]
+    fn key_id_into_string() {
+        let id = KeyId::try_from("ed25519:abc123").expect("Failed to create KeyId.");
+        let string = id.to_string();
+
+        assert_eq!(id.into_string(), string);
+    }
+}
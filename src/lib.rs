@@ -1,132 +1,500 @@
 //! Crate **ruma_identifiers** contains types for [Matrix](https://matrix.org/) identifiers
-//! for events, rooms, room aliases, room versions, and users.
+//! for events, groups, rooms, room aliases, room versions, users, user localparts, and server
+//! names, as well as content URIs and third-party identifiers.
+//!
+//! # Generated identifiers
+//!
+//! The `new` constructors generate random localparts using `rand`'s thread-local generator, which
+//! is a cryptographically secure generator seeded and periodically reseeded from the operating
+//! system, so generated identifiers can't be guessed. Each character is drawn uniformly from the
+//! ASCII letters and digits `[A-Za-z0-9]`, i.e. carries about 5.95 bits of entropy. User IDs and
+//! user localparts are downcased afterwards, which reduces this to about 5.12 bits.
+//!
+//! The `new_with_alphabet` constructors draw the characters from a different `Alphabet` instead,
+//! e.g. Crockford's Base32 alphabet with about 5 bits of entropy per character.
+//!
+//! These constructors, and the `new_sortable` constructors, which use the system clock, require the
+//! `std` feature, which is enabled by default. Without it, the crate is `no_std` and only requires
+//! `alloc`, and the `new_with_rng` constructors generate identifiers with a given random number
+//! generator.
+//!
+//! # Canonical form
+//!
+//! Identifiers with a server name, and server names themselves, are normalized when they are
+//! parsed or composed. Their `Display` implementations, `to_string` and `as_str` all return this
+//! canonical form, and equality, hashing and ordering are based on it, so equivalent spellings of
+//! an identifier are equal. The canonical form differs from the input as follows:
+//!
+//! * The port is omitted if it is `DEFAULT_PORT`, and leading zeros of other ports are removed.
+//! * Domain names are lowercased, and internationalized domain names are encoded as punycode, so
+//!   `Café.example` becomes `xn--caf-dma.example`. The trailing dot of a fully qualified domain
+//!   name is removed.
+//! * IP addresses are written in their standard notation, i.e. IPv4 addresses as four decimal
+//!   numbers, like `127.0.0.1` for `127.1`, and IPv6 addresses in their shortest form, like
+//!   `[::1]` for `[0:0::1]`.
+//! * The localparts of user IDs are downcased.
+//!
+//! Other localparts, room aliases and reference hashes of event IDs are kept exactly as given.
+//! `to_string_preserving_port` returns the string representation with an explicitly given port
+//! still included, but is otherwise canonical as well, so the original string can't be recovered
+//! from an identifier.
+//!
+//! # Serialization
+//!
+//! All identifier types serialize to their canonical string representation, in human-readable
+//! formats like JSON as well as in binary formats like bincode. The string is the most compact form
+//! that keeps every identifier intact, since binary formats already store it with a length prefix,
+//! while serializing the parts separately would add tags for the kind of host and the port. It is
+//! validated again when deserializing, regardless of the format. `ResolvedRoom`, which bundles
+//! several identifiers, serializes to a map of them instead.
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(rust_2018_idioms)]
+// The derives of diesel 1.x implement its traits inside of anonymous constants, which newer
+// compilers lint against. Lint attributes on the derived types don't apply to the generated code.
+#![cfg_attr(feature = "diesel", allow(unknown_lints, non_local_definitions))]
 #![deny(
     missing_copy_implementations,
     missing_debug_implementations,
     missing_docs
 )]
-// Since we support Rust 1.36.0, we can't apply this suggestion yet
-#![allow(clippy::use_self)]
+
+#[cfg(all(
+    not(feature = "std"),
+    any(
+        feature = "arbitrary",
+        feature = "diesel",
+        feature = "sqlx",
+        feature = "schemars"
+    )
+))]
+compile_error!(
+    "The `arbitrary`, `diesel`, `sqlx` and `schemars` features require the `std` feature."
+);
+
+extern crate alloc;
 
 #[cfg(feature = "diesel")]
 #[cfg_attr(feature = "diesel", macro_use)]
 extern crate diesel;
 
-use std::{
-    borrow::Cow,
+use alloc::{borrow::Cow, format, string::String};
+use core::{
     convert::TryFrom,
     fmt::{Formatter, Result as FmtResult},
+    marker::PhantomData,
 };
 
-use rand::{distributions::Alphanumeric, thread_rng, Rng};
-use serde::de::{self, Deserialize as _, Deserializer, Unexpected};
-use url::Url;
+use percent_encoding::{percent_decode_str, AsciiSet, CONTROLS};
+#[cfg(feature = "std")]
+use rand::thread_rng;
+use rand::Rng;
+// The rules for parsing identifiers live in a separate crate, so that the macros can apply them at
+// compile time as well. They are imported here to be shared by the modules of this crate.
+use ruma_identifiers_validation::{
+    has_surrounding_whitespace, host, is_sigil_only,
+    server_name::{is_forbidden_in_server_name, MAX_DOMAIN_BYTES},
+    validate_id, validate_opaque_id,
+};
+use serde::de::{self, Deserializer, Expected, Visitor};
 
-pub use url::Host;
+use crate::partial_eq::display_eq;
 
 #[doc(inline)]
-pub use crate::device_id::DeviceId;
+pub use crate::{alphabet::Alphabet, device_id::DeviceId};
 pub use crate::{
-    error::Error, event_id::EventId, room_alias_id::RoomAliasId, room_id::RoomId,
-    room_id_or_room_alias_id::RoomIdOrAliasId, room_version_id::RoomVersionId, user_id::UserId,
+    any_id::{parse_any, AnyId},
+    error::{Error, ErrorKind},
+    event_id::{is_valid_event_id, EventId, EventIdFormat},
+    group_id::{is_valid_group_id, GroupId},
+    host::Host,
+    id_ref::{EventIdRef, RoomIdRef},
+    identifier::Identifier,
+    key_id::{is_valid_key_id, KeyId},
+    matrix_to::MatrixToUri,
+    matrix_uri::{MatrixId, MatrixUri, UriAction},
+    mxc_uri::MxcUri,
+    resolved_room::ResolvedRoom,
+    room_alias::RoomAlias,
+    room_alias_id::{is_valid_room_alias_id, RoomAliasId},
+    room_id::{is_valid_room_id, RoomId},
+    room_id_or_room_alias_id::{is_valid_room_id_or_alias_id, RoomIdOrAliasId},
+    room_version_id::RoomVersionId,
+    server_name::{is_valid_server_name, ServerName},
+    third_party_id::{Medium, ThirdPartyId},
+    user_id::{is_valid_user_id, UserId},
+    user_localpart::UserLocalpart,
 };
+pub use ruma_identifiers_validation::{DEFAULT_PORT, MAX_BYTES, MIN_CHARS, SIGIL_BYTES};
 
+#[doc(hidden)]
+pub use ruma_identifiers_macros as _macros;
+
+mod alphabet;
+mod any_id;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_integration;
+mod borrow;
+mod byte_len;
 pub mod device_id;
 #[cfg(feature = "diesel")]
 mod diesel_integration;
+mod display;
 mod error;
 mod event_id;
+mod federation_url;
+mod from_bytes;
+#[cfg(feature = "url")]
+mod from_url;
+mod group_id;
+mod id_ref;
+mod identifier;
+mod key_id;
+mod macros;
+mod matrix_to;
+mod matrix_uri;
+mod mxc_uri;
+mod parse_many;
+mod partial_eq;
+mod resolved_room;
+mod room_alias;
 mod room_alias_id;
 mod room_id;
 mod room_id_or_room_alias_id;
 mod room_version_id;
+mod same_server;
+#[cfg(feature = "schemars")]
+mod schemars_integration;
+mod server_name;
+mod sigil;
+#[cfg(feature = "sqlx")]
+mod sqlx_integration;
+mod third_party_id;
 mod user_id;
+mod user_localpart;
+
+/// Builds the canonical string representation of an identifier, returning it along with the
+/// index of the colon delimiter.
+fn format_id(sigil: char, localpart: &str, server_name: &ServerName) -> (String, usize) {
+    let id = format!("{}{}:{}", sigil, localpart, server_name);
 
-/// All identifiers must be 255 bytes or less.
-const MAX_BYTES: usize = 255;
-/// The minimum number of characters an ID can be.
+    (id, SIGIL_BYTES + localpart.len())
+}
+
+/// Builds an identifier from its localpart and an unparsed server name, returning its canonical
+/// string representation, the index of the colon delimiter and the parsed server name.
 ///
-/// This is an optimization and not required by the spec. The shortest possible valid ID is a sigil
-/// + a single character local ID + a colon + a single character hostname.
-const MIN_CHARS: usize = 4;
-/// The number of bytes in a valid sigil.
-const SIGIL_BYTES: usize = 1;
-
-/// `Display` implementation shared by identifier types.
-fn display(
-    f: &mut Formatter<'_>,
+/// Only the server name is parsed. The localpart is checked by `id_from_server_name`, like for
+/// all other constructors that take the parts of an identifier.
+fn id_from_parts(
+    sigil: char,
+    localpart: &str,
+    server_name: &str,
+    validate_localpart: impl FnOnce(&str) -> Result<(), ErrorKind>,
+) -> Result<(String, usize, ServerName), Error> {
+    let server_name = ServerName::try_from(server_name).map_err(|err| {
+        let raw_id = format!("{}{}:{}", sigil, localpart, server_name);
+        err.with_input(&raw_id)
+    })?;
+
+    id_from_server_name(sigil, localpart, server_name, validate_localpart)
+}
+
+/// Like `id_from_parts`, but with an already parsed host and port, of which only a domain name and
+/// the port are checked.
+fn id_from_host(
     sigil: char,
     localpart: &str,
-    hostname: &Host,
+    host: Host,
     port: u16,
-) -> FmtResult {
-    if port == 443 {
-        write!(f, "{}{}:{}", sigil, localpart, hostname)
-    } else {
-        write!(f, "{}{}:{}:{}", sigil, localpart, hostname, port)
+    validate_localpart: impl FnOnce(&str) -> Result<(), ErrorKind>,
+) -> Result<(String, usize, ServerName), Error> {
+    let server_name = ServerName::from_host(host, port).map_err(|err| {
+        let raw_id = format!("{}{}:{}", sigil, localpart, err.input());
+        err.with_input(&raw_id)
+    })?;
+
+    id_from_server_name(sigil, localpart, server_name, validate_localpart)
+}
+
+/// Builds an identifier from its localpart and a parsed server name.
+///
+/// The localpart is checked to be non-empty and free of colons, so that the identifier can be
+/// parsed back into the same components, and then with the given function, which applies the
+/// rules of the identifier type that parsing its string representation would apply as well.
+fn id_from_server_name(
+    sigil: char,
+    localpart: &str,
+    server_name: ServerName,
+    validate_localpart: impl FnOnce(&str) -> Result<(), ErrorKind>,
+) -> Result<(String, usize, ServerName), Error> {
+    let (id, colon_idx) = format_id(sigil, localpart, &server_name);
+
+    if localpart.is_empty() {
+        return Err(Error::new(ErrorKind::MissingLocalpart, &id));
     }
+
+    if localpart.contains(':') {
+        return Err(Error::new(ErrorKind::InvalidCharacters, &id));
+    }
+
+    validate_localpart(localpart).map_err(|kind| Error::new(kind, &id))?;
+
+    validate_id(&id).map_err(|kind| Error::new(kind, &id))?;
+
+    Ok((id, colon_idx, server_name))
 }
 
-/// Generates a random identifier localpart.
+/// Generates a random identifier localpart of ASCII letters and digits.
+///
+/// `ThreadRng` is a cryptographically secure generator, which is what makes generated identifiers
+/// unpredictable. Any replacement must be as well.
+#[cfg(feature = "std")]
 fn generate_localpart(length: usize) -> String {
-    thread_rng()
-        .sample_iter(&Alphanumeric)
-        .take(length)
-        .collect()
+    generate_localpart_with_rng(&mut thread_rng(), length)
+}
+
+/// Generates a random identifier localpart of ASCII letters and digits with the given random
+/// number generator.
+#[cfg(feature = "std")]
+fn generate_localpart_with_rng(rng: &mut impl Rng, length: usize) -> String {
+    Alphabet::Alphanumeric.generate(rng, length)
 }
 
-/// Checks if an identifier is within the acceptable byte lengths.
-fn validate_id(id: &str) -> Result<(), Error> {
-    if id.len() > MAX_BYTES {
-        return Err(Error::MaximumLengthExceeded);
+/// Generates a random localpart for an identifier of the given homeserver, failing early if it
+/// can't possibly fit into an identifier.
+fn generate_id_localpart(
+    rng: &mut impl Rng,
+    length: usize,
+    alphabet: Alphabet,
+    homeserver_host: &str,
+) -> Result<String, Error> {
+    if length > MAX_BYTES {
+        return Err(Error::new(
+            ErrorKind::MaximumLengthExceeded,
+            homeserver_host,
+        ));
     }
 
-    if id.len() < MIN_CHARS {
-        return Err(Error::MinimumLengthNotSatisfied);
+    Ok(alphabet.generate(rng, length))
+}
+
+/// Returns the host of a server name in its canonical string representation, i.e. strips the port
+/// if there is one.
+fn canonical_host(server_name: &str) -> &str {
+    match server_name.rfind(':') {
+        // A colon followed by a closing bracket is part of an IPv6 literal, not a port delimiter.
+        Some(index) if !server_name[index..].contains(']') => &server_name[..index],
+        _ => server_name,
     }
+}
+
+/// Parses the localpart and server name from a string identifier.
+fn parse_id(required_sigil: char, id: &str) -> Result<(&str, ServerName), Error> {
+    let (localpart, host, port) = ruma_identifiers_validation::parse_id(required_sigil, id)
+        .map_err(|err| Error::from_validation(err, id))?;
+
+    Ok((localpart, ServerName::from_parsed(host, port)))
+}
+
+/// Parses a server name into its host and the port the homeserver can be accessed on.
+///
+/// This applies exactly the same rules as `ServerName::try_from`, for callers that need to
+/// validate a server name without keeping a `ServerName` around. If no port is given, the port is
+/// `DEFAULT_PORT`.
+///
+/// ```
+/// # use ruma_identifiers::{parse_server_name, Host};
+/// let (host, port) = parse_server_name("example.com:5000").unwrap();
+/// assert_eq!(host, Host::Domain("example.com".to_string()));
+/// assert_eq!(port, 5000);
+/// ```
+pub fn parse_server_name(server_name: &str) -> Result<(Host, u16), Error> {
+    let server_name = ServerName::try_from(server_name)?;
 
-    Ok(())
+    Ok((server_name.host().clone(), server_name.port()))
 }
 
-/// Parses the localpart, host, and port from a string identifier.
-fn parse_id(required_sigil: char, id: &str) -> Result<(&str, Host, u16), Error> {
-    validate_id(id)?;
+/// Splits an identifier with the given sigil into its localpart and server name, parsing the host
+/// and port of the server name.
+///
+/// This applies the same rules to the sigil, the delimiter, the length and the server name as the
+/// identifier types do. Sigils that aren't ASCII characters are rejected with
+/// `ErrorKind::MissingSigil`, since no identifier has such a sigil. The localpart is only checked
+/// to be non-empty; identifiers with stricter
+/// rules for their localparts, like user IDs, must check it separately, e.g. with
+/// `UserLocalpart::try_from`.
+///
+/// ```
+/// # use ruma_identifiers::{parse_id_parts, Host};
+/// let (localpart, host, port) = parse_id_parts('@', "@carl:example.com").unwrap();
+/// assert_eq!(localpart, "carl");
+/// assert_eq!(host, Host::Domain("example.com".to_string()));
+/// assert_eq!(port, 8448);
+/// ```
+pub fn parse_id_parts(sigil: char, id: &str) -> Result<(&str, Host, u16), Error> {
+    let (localpart, server_name) = parse_id(sigil, id)?;
+
+    Ok((localpart, server_name.host().clone(), server_name.port()))
+}
 
-    if !id.starts_with(required_sigil) {
-        return Err(Error::MissingSigil);
+/// Checks whether a string identifier is valid, validating its localpart with the given function,
+/// without allocating its localpart.
+fn is_valid_id<T>(
+    required_sigil: char,
+    id: &str,
+    validate_localpart: impl FnOnce(&str) -> Result<T, ErrorKind>,
+) -> bool {
+    match parse_id(required_sigil, id) {
+        Ok((localpart, _)) => validate_localpart(localpart).is_ok(),
+        Err(_) => false,
     }
+}
 
-    let delimiter_index = match id.find(':') {
-        Some(index) => index,
-        None => return Err(Error::MissingDelimiter),
-    };
+/// Parses a string identifier with an opaque localpart, like a room ID, without copying it,
+/// returning its canonical string representation.
+///
+/// The localpart is checked with `validate_opaque_id`. The string is borrowed if it already is
+/// canonical, which is the case unless its server name is spelled differently, e.g. with an
+/// explicit default port.
+fn canonical_id(required_sigil: char, id: &str) -> Result<Cow<'_, str>, Error> {
+    let (localpart, server_name) = parse_id(required_sigil, id)?;
+    validate_opaque_id(localpart).map_err(|kind| Error::new(kind, id))?;
+    let colon_idx = SIGIL_BYTES + localpart.len();
+
+    if display_eq(&server_name, &id[colon_idx + 1..]) {
+        Ok(Cow::Borrowed(id))
+    } else {
+        Ok(Cow::Owned(
+            format_id(required_sigil, localpart, &server_name).0,
+        ))
+    }
+}
 
-    let localpart = &id[1..delimiter_index];
-    let raw_host = &id[delimiter_index + SIGIL_BYTES..];
-    let url_string = format!("https://{}", raw_host);
-    let url = Url::parse(&url_string)?;
+/// Parses an owned string identifier, validating its localpart with the given function and
+/// returning its canonical string representation, the index of the colon delimiter, the parsed
+/// server name and the result of the localpart validation.
+///
+/// The string is reused as the canonical string representation if it already is canonical, which
+/// is the case unless its server name is spelled differently, e.g. with an explicit default port.
+fn parse_owned_id<T>(
+    required_sigil: char,
+    id: String,
+    validate_localpart: impl FnOnce(&str) -> Result<T, ErrorKind>,
+) -> Result<(String, usize, ServerName, T), Error> {
+    let (localpart, server_name) = parse_id(required_sigil, &id)?;
+    let validated = validate_localpart(localpart).map_err(|kind| Error::new(kind, &id))?;
+    let colon_idx = SIGIL_BYTES + localpart.len();
 
-    let host = match url.host() {
-        Some(host) => host.to_owned(),
-        None => return Err(Error::InvalidHost),
+    let full_id = if display_eq(&server_name, &id[colon_idx + 1..]) {
+        id
+    } else {
+        format_id(required_sigil, &id[SIGIL_BYTES..colon_idx], &server_name).0
     };
 
-    let port = url.port().unwrap_or(443);
+    Ok((full_id, colon_idx, server_name, validated))
+}
+
+/// The characters that are percent-encoded in the identifiers and server names of URIs like
+/// matrix.to permalinks.
+///
+/// Besides characters that aren't allowed in a URI fragment, this includes the ones that delimit
+/// the parts of such a URI, like the `/` between identifiers or the `&` between query parameters,
+/// as well as the `#` sigil of room alias IDs.
+const URI_COMPONENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'&')
+    .add(b'+')
+    .add(b'/')
+    .add(b'<')
+    .add(b'=')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
 
-    Ok((localpart, host, port))
+/// Decodes a percent-encoded part of the given URI.
+fn percent_decode(part: &str, uri: &str) -> Result<String, Error> {
+    percent_decode_str(part)
+        .decode_utf8()
+        .map(|decoded| decoded.into_owned())
+        .map_err(|_| Error::new(ErrorKind::InvalidCharacters, uri))
 }
 
 /// Deserializes any type of id using the provided TryFrom implementation.
 ///
 /// This is a helper function to reduce the boilerplate of the Deserialize implementations.
-fn deserialize_id<'de, D, T>(deserializer: D, expected_str: &str) -> Result<T, D::Error>
+fn deserialize_id<'de, D, T>(deserializer: D, expected_str: &'static str) -> Result<T, D::Error>
 where
     D: Deserializer<'de>,
-    T: for<'a> TryFrom<&'a str>,
+    T: for<'a> TryFrom<&'a str, Error = Error> + TryFrom<String, Error = Error>,
 {
-    Cow::<'_, str>::deserialize(deserializer).and_then(|v| {
-        T::try_from(&v).map_err(|_| de::Error::invalid_value(Unexpected::Str(&v), &expected_str))
+    deserializer.deserialize_str(IdVisitor {
+        expected_str,
+        id_type: PhantomData,
     })
 }
+
+/// Creates a serde error for an ID that failed to parse, including why it was rejected.
+fn invalid_id<E>(err: Error, expected: &dyn Expected) -> E
+where
+    E: de::Error,
+{
+    E::custom(format_args!(
+        "invalid Matrix ID: {}, expected {}",
+        err, expected
+    ))
+}
+
+/// A serde visitor for any type of id, validating strings with the provided TryFrom
+/// implementation.
+///
+/// Borrowed strings are validated in place rather than being copied first, and owned strings are
+/// reused by ids that store them, if possible.
+struct IdVisitor<T> {
+    /// The description of the expected input, used in error messages.
+    expected_str: &'static str,
+    /// The type of id being deserialized.
+    id_type: PhantomData<T>,
+}
+
+impl<'de, T> Visitor<'de> for IdVisitor<T>
+where
+    T: for<'a> TryFrom<&'a str, Error = Error> + TryFrom<String, Error = Error>,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.expected_str)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<T, E>
+    where
+        E: de::Error,
+    {
+        T::try_from(v).map_err(|err| invalid_id(err, &self))
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<T, E>
+    where
+        E: de::Error,
+    {
+        self.visit_str(v)
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<T, E>
+    where
+        E: de::Error,
+    {
+        T::try_from(v).map_err(|err| invalid_id(err, &self))
+    }
+}
@@ -0,0 +1,113 @@
+//! Macros for constructing identifiers from string literals.
+//!
+//! The literal is validated at compile time, with the same rules as the `TryFrom` implementations,
+//! by the procedural macros of the ruma-identifiers-macros crate, so a malformed literal fails to
+//! compile. The macros are meant for identifiers known in advance, like in tests or hard-coded
+//! configuration.
+//!
+//! The procedural macros are wrapped in `macro_rules!` macros, which pass them the `$crate` path,
+//! so that the generated code refers to this crate under whatever name it is imported.
+
+/// Creates a `UserId` from a string literal, failing to compile if it is not a valid user ID.
+///
+/// ```
+/// # use ruma_identifiers::user_id;
+/// let user_id = user_id!("@carl:example.com");
+/// assert_eq!(user_id.localpart(), "carl");
+/// ```
+///
+/// ```compile_fail
+/// # use ruma_identifiers::user_id;
+/// let user_id = user_id!("carl:example.com");
+/// ```
+#[macro_export]
+macro_rules! user_id {
+    ($id:literal) => {
+        $crate::_macros::user_id!($crate, $id)
+    };
+}
+
+/// Creates a `RoomId` from a string literal, failing to compile if it is not a valid room ID.
+///
+/// ```
+/// # use ruma_identifiers::room_id;
+/// let room_id = room_id!("!n8f893n9:example.com");
+/// assert_eq!(room_id.localpart(), "n8f893n9");
+/// ```
+///
+/// ```compile_fail
+/// # use ruma_identifiers::room_id;
+/// let room_id = room_id!("!n8f893n9");
+/// ```
+#[macro_export]
+macro_rules! room_id {
+    ($id:literal) => {
+        $crate::_macros::room_id!($crate, $id)
+    };
+}
+
+/// Creates a `RoomAliasId` from a string literal, failing to compile if it is not a valid room
+/// alias ID.
+///
+/// ```
+/// # use ruma_identifiers::room_alias_id;
+/// let room_alias_id = room_alias_id!("#ruma:example.com");
+/// assert_eq!(room_alias_id.localpart(), "ruma");
+/// ```
+///
+/// ```compile_fail
+/// # use ruma_identifiers::room_alias_id;
+/// let room_alias_id = room_alias_id!("#ruma chat:example.com");
+/// ```
+#[macro_export]
+macro_rules! room_alias_id {
+    ($id:literal) => {
+        $crate::_macros::room_alias_id!($crate, $id)
+    };
+}
+
+/// Creates an `EventId` from a string literal, failing to compile if it is not a valid event ID.
+///
+/// ```
+/// # use ruma_identifiers::event_id;
+/// let event_id = event_id!("$h29iv0s8:example.com");
+/// assert_eq!(event_id.localpart(), "h29iv0s8");
+/// ```
+///
+/// ```compile_fail
+/// # use ruma_identifiers::event_id;
+/// let event_id = event_id!("h29iv0s8:example.com");
+/// ```
+#[macro_export]
+macro_rules! event_id {
+    ($id:literal) => {
+        $crate::_macros::event_id!($crate, $id)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::{EventId, RoomAliasId, RoomId, UserId};
+
+    #[test]
+    fn valid_literals() {
+        assert_eq!(
+            user_id!("@carl:example.com"),
+            UserId::try_from("@carl:example.com").expect("Failed to create UserId.")
+        );
+        assert_eq!(
+            room_id!("!29fhd83h92h0:example.com"),
+            RoomId::try_from("!29fhd83h92h0:example.com").expect("Failed to create RoomId.")
+        );
+        assert_eq!(
+            room_alias_id!("#ruma:example.com"),
+            RoomAliasId::try_from("#ruma:example.com").expect("Failed to create RoomAliasId.")
+        );
+        assert_eq!(
+            event_id!("$39hvsi03hlne:example.com"),
+            EventId::try_from("$39hvsi03hlne:example.com").expect("Failed to create EventId.")
+        );
+    }
+}
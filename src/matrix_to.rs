@@ -0,0 +1,464 @@
+//! matrix.to permalinks.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    convert::TryFrom,
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
+use percent_encoding::utf8_percent_encode;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    deserialize_id,
+    error::{Error, ErrorKind},
+    event_id::EventId,
+    percent_decode,
+    room_id_or_room_alias_id::RoomIdOrAliasId,
+    server_name::ServerName,
+    user_id::UserId,
+    URI_COMPONENT,
+};
+
+/// The prefix of every matrix.to permalink, up to the first identifier.
+const MATRIX_TO_PREFIX: &str = "https://matrix.to/#/";
+
+/// A matrix.to permalink to a room, to an event in a room, or to a user.
+///
+/// A permalink to a room consists of a room ID or room alias ID, optionally an event ID, and the
+/// server names of homeservers that can be used to join the room, given as `via` query parameters.
+/// A permalink to a user consists of the user ID only. A `MatrixToUri` is converted from a string
+/// slice, and can be converted back into a string as needed, percent-encoding its parts.
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use ruma_identifiers::{MatrixToUri, RoomIdOrAliasId, ServerName};
+/// let uri = MatrixToUri::new(
+///     RoomIdOrAliasId::try_from("#ruma:example.com").unwrap(),
+///     None,
+///     vec![ServerName::try_from("example.org").unwrap()],
+/// );
+/// assert_eq!(uri.to_string(), "https://matrix.to/#/%23ruma:example.com?via=example.org");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MatrixToUri {
+    /// The room, event or user the permalink points to.
+    target: Target,
+    /// The server names of homeservers that can be used to join the room.
+    via: Vec<ServerName>,
+}
+
+/// What a matrix.to permalink points to.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum Target {
+    /// A room, or an event in it if the event ID is given.
+    Room(RoomIdOrAliasId, Option<EventId>),
+    /// A user.
+    User(UserId),
+}
+
+impl MatrixToUri {
+    /// Creates a permalink to the given room, or to the given event in it, with the given server
+    /// names of homeservers that can be used to join the room.
+    pub fn new(room: RoomIdOrAliasId, event_id: Option<EventId>, via: Vec<ServerName>) -> Self {
+        Self {
+            target: Target::Room(room, event_id),
+            via,
+        }
+    }
+
+    /// Creates a permalink to the given user.
+    ///
+    /// ```
+    /// # use std::convert::TryFrom;
+    /// # use ruma_identifiers::{MatrixToUri, UserId};
+    /// let uri = MatrixToUri::from_user_id(UserId::try_from("@carl:example.com").unwrap());
+    /// assert_eq!(uri.to_string(), "https://matrix.to/#/@carl:example.com");
+    /// ```
+    pub fn from_user_id(user_id: UserId) -> Self {
+        Self {
+            target: Target::User(user_id),
+            via: Vec::new(),
+        }
+    }
+
+    /// Returns the room the permalink points to, or `None` if it points to a user.
+    pub fn room(&self) -> Option<&RoomIdOrAliasId> {
+        match &self.target {
+            Target::Room(room, _) => Some(room),
+            Target::User(_) => None,
+        }
+    }
+
+    /// Returns the event the permalink points to, if any.
+    pub fn event_id(&self) -> Option<&EventId> {
+        match &self.target {
+            Target::Room(_, event_id) => event_id.as_ref(),
+            Target::User(_) => None,
+        }
+    }
+
+    /// Returns the user the permalink points to, if any.
+    pub fn user_id(&self) -> Option<&UserId> {
+        match &self.target {
+            Target::User(user_id) => Some(user_id),
+            Target::Room(..) => None,
+        }
+    }
+
+    /// Returns the server names of homeservers that can be used to join the room.
+    pub fn via(&self) -> &[ServerName] {
+        &self.via
+    }
+}
+
+impl Display for MatrixToUri {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(MATRIX_TO_PREFIX)?;
+
+        match &self.target {
+            Target::Room(room, event_id) => {
+                write!(f, "{}", utf8_percent_encode(room.as_str(), URI_COMPONENT))?;
+
+                if let Some(event_id) = event_id {
+                    write!(
+                        f,
+                        "/{}",
+                        utf8_percent_encode(event_id.as_str(), URI_COMPONENT)
+                    )?;
+                }
+            }
+            Target::User(user_id) => write!(
+                f,
+                "{}",
+                utf8_percent_encode(user_id.as_str(), URI_COMPONENT)
+            )?,
+        }
+
+        for (index, server_name) in self.via.iter().enumerate() {
+            let separator = if index == 0 { '?' } else { '&' };
+            write!(
+                f,
+                "{}via={}",
+                separator,
+                utf8_percent_encode(&server_name.to_string(), URI_COMPONENT)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Serialize for MatrixToUri {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MatrixToUri {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_id(deserializer, "a matrix.to permalink as a string")
+    }
+}
+
+impl TryFrom<&str> for MatrixToUri {
+    type Error = Error;
+
+    /// Attempts to create a new matrix.to permalink from a string representation.
+    ///
+    /// The string must start with `https://matrix.to/#/`, followed by either a percent-encoded
+    /// room ID or room alias ID and optionally a literal slash and a percent-encoded event ID, or
+    /// by a percent-encoded user ID. It may end with a query string. `via` query parameters must be
+    /// valid server names, other query parameters are ignored.
+    fn try_from(uri: &str) -> Result<Self, Error> {
+        if !uri.starts_with(MATRIX_TO_PREFIX) {
+            return Err(Error::new(ErrorKind::InvalidUriScheme, uri));
+        }
+
+        let rest = &uri[MATRIX_TO_PREFIX.len()..];
+        let (path, query) = match rest.find('?') {
+            Some(index) => (&rest[..index], Some(&rest[index + 1..])),
+            None => (rest, None),
+        };
+
+        let mut segments = path.split('/');
+        let first_id = match segments.next() {
+            Some(segment) if !segment.is_empty() => percent_decode(segment, uri)?,
+            _ => return Err(Error::new(ErrorKind::InvalidUriPath, uri)),
+        };
+        let target = if first_id.starts_with('@') {
+            Target::User(UserId::try_from(first_id).map_err(|err| err.with_input(uri))?)
+        } else {
+            let room = RoomIdOrAliasId::try_from(first_id).map_err(|err| err.with_input(uri))?;
+            let event_id = match segments.next() {
+                Some(segment) => Some(
+                    EventId::try_from(percent_decode(segment, uri)?.as_str())
+                        .map_err(|err| err.with_input(uri))?,
+                ),
+                None => None,
+            };
+
+            Target::Room(room, event_id)
+        };
+
+        if segments.next().is_some() {
+            return Err(Error::new(ErrorKind::InvalidUriPath, uri));
+        }
+
+        let mut via = Vec::new();
+        for parameter in query.into_iter().flat_map(|query| query.split('&')) {
+            if let Some(server_name) = parameter.strip_prefix("via=") {
+                let server_name = percent_decode(server_name, uri)?;
+                via.push(
+                    ServerName::try_from(server_name.as_str())
+                        .map_err(|err| err.with_input(uri))?,
+                );
+            }
+        }
+
+        Ok(Self { target, via })
+    }
+}
+
+impl TryFrom<String> for MatrixToUri {
+    type Error = Error;
+
+    /// Attempts to create a new matrix.to permalink from an owned string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn try_from(uri: String) -> Result<Self, Error> {
+        Self::try_from(uri.as_str())
+    }
+}
+
+impl FromStr for MatrixToUri {
+    type Err = Error;
+
+    /// Attempts to create a new matrix.to permalink from a string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use serde_json::{from_str, to_string};
+
+    use super::MatrixToUri;
+    use crate::{error::ErrorKind, EventId, RoomIdOrAliasId, ServerName, UserId};
+
+    fn room(id: &str) -> RoomIdOrAliasId {
+        RoomIdOrAliasId::try_from(id).expect("Failed to create RoomIdOrAliasId.")
+    }
+
+    fn server_name(server_name: &str) -> ServerName {
+        ServerName::try_from(server_name).expect("Failed to create ServerName.")
+    }
+
+    #[test]
+    fn room_permalink_round_trip() {
+        let uri = MatrixToUri::new(room("!n8f893n9:example.com"), None, Vec::new());
+
+        assert_eq!(uri.to_string(), "https://matrix.to/#/!n8f893n9:example.com");
+        assert_eq!(
+            MatrixToUri::try_from(uri.to_string().as_str()).expect("Failed to create MatrixToUri."),
+            uri
+        );
+    }
+
+    #[test]
+    fn room_and_event_permalink_round_trip() {
+        let uri = MatrixToUri::new(
+            room("!n8f893n9:example.com"),
+            Some(
+                EventId::try_from("$39hvsi03hlne:example.com").expect("Failed to create EventId."),
+            ),
+            vec![server_name("example.com"), server_name("example.org:5000")],
+        );
+
+        assert_eq!(
+            uri.to_string(),
+            "https://matrix.to/#/!n8f893n9:example.com/$39hvsi03hlne:example.com\
+             ?via=example.com&via=example.org:5000"
+        );
+        assert_eq!(
+            MatrixToUri::try_from(uri.to_string().as_str()).expect("Failed to create MatrixToUri."),
+            uri
+        );
+    }
+
+    #[test]
+    fn alias_permalink_round_trip() {
+        let uri = MatrixToUri::new(room("#ruma:example.com"), None, Vec::new());
+
+        assert_eq!(uri.to_string(), "https://matrix.to/#/%23ruma:example.com");
+        assert_eq!(
+            MatrixToUri::try_from(uri.to_string().as_str()).expect("Failed to create MatrixToUri."),
+            uri
+        );
+    }
+
+    #[test]
+    fn user_permalink_round_trip() {
+        let user_id = UserId::try_from("@carl:example.com").expect("Failed to create UserId.");
+        let uri = MatrixToUri::from_user_id(user_id.clone());
+
+        assert_eq!(uri.to_string(), "https://matrix.to/#/@carl:example.com");
+        assert_eq!(uri.user_id(), Some(&user_id));
+        assert_eq!(uri.room(), None);
+        assert_eq!(uri.event_id(), None);
+        assert_eq!(
+            MatrixToUri::try_from(uri.to_string().as_str()).expect("Failed to create MatrixToUri."),
+            uri
+        );
+        assert_eq!(
+            MatrixToUri::try_from("https://matrix.to/#/%40carl%3Aexample.com")
+                .expect("Failed to create MatrixToUri."),
+            uri
+        );
+    }
+
+    #[test]
+    fn invalid_user_permalink() {
+        assert_eq!(
+            MatrixToUri::try_from(
+                "https://matrix.to/#/@carl:example.com/$39hvsi03hlne:example.com"
+            )
+            .unwrap_err()
+            .kind(),
+            ErrorKind::InvalidUriPath
+        );
+        assert_eq!(
+            MatrixToUri::try_from("https://matrix.to/#/@ca rl:example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidCharacters
+        );
+    }
+
+    #[test]
+    fn permalink_with_ipv6_via() {
+        let uri = MatrixToUri::new(
+            room("!n8f893n9:example.com"),
+            None,
+            vec![server_name("[::1]:5000")],
+        );
+
+        assert_eq!(
+            uri.to_string(),
+            "https://matrix.to/#/!n8f893n9:example.com?via=%5B::1%5D:5000"
+        );
+        assert_eq!(
+            MatrixToUri::try_from(uri.to_string().as_str()).expect("Failed to create MatrixToUri."),
+            uri
+        );
+    }
+
+    #[test]
+    fn parse_fully_encoded_permalink() {
+        let uri = MatrixToUri::try_from(
+            "https://matrix.to/#/%21n8f893n9%3Aexample.com/%2439hvsi03hlne%3Aexample.com\
+             ?via=example.org&other=ignored",
+        )
+        .expect("Failed to create MatrixToUri.");
+
+        assert_eq!(uri.room(), Some(&room("!n8f893n9:example.com")));
+        assert_eq!(
+            uri.event_id().expect("Missing event ID."),
+            "$39hvsi03hlne:example.com"
+        );
+        assert_eq!(uri.via(), &[server_name("example.org")][..]);
+    }
+
+    #[test]
+    fn serialize_valid_permalink() {
+        assert_eq!(
+            to_string(&MatrixToUri::new(
+                room("#ruma:example.com"),
+                None,
+                Vec::new()
+            ))
+            .expect("Failed to convert MatrixToUri to JSON."),
+            r##""https://matrix.to/#/%23ruma:example.com""##
+        );
+    }
+
+    #[test]
+    fn deserialize_valid_permalink() {
+        assert_eq!(
+            from_str::<MatrixToUri>(r##""https://matrix.to/#/%23ruma:example.com""##)
+                .expect("Failed to convert JSON to MatrixToUri"),
+            MatrixToUri::new(room("#ruma:example.com"), None, Vec::new())
+        );
+    }
+
+    #[test]
+    fn invalid_permalink_prefix() {
+        assert_eq!(
+            MatrixToUri::try_from("https://example.com/#/!n8f893n9:example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidUriScheme
+        );
+    }
+
+    #[test]
+    fn invalid_permalink_path() {
+        for uri in &[
+            "https://matrix.to/#/",
+            "https://matrix.to/#/?via=example.com",
+            "https://matrix.to/#/!n8f893n9:example.com/$39hvsi03hlne:example.com/extra",
+        ] {
+            assert_eq!(
+                MatrixToUri::try_from(*uri).unwrap_err().kind(),
+                ErrorKind::InvalidUriPath
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_permalink_identifiers() {
+        let error = MatrixToUri::try_from("https://matrix.to/#/n8f893n9:example.com").unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::MissingSigil);
+        assert_eq!(error.input(), "https://matrix.to/#/n8f893n9:example.com");
+        assert_eq!(
+            MatrixToUri::try_from(
+                "https://matrix.to/#/!n8f893n9:example.com/39hvsi03hlne:example.com"
+            )
+            .unwrap_err()
+            .kind(),
+            ErrorKind::MissingSigil
+        );
+        assert_eq!(
+            MatrixToUri::try_from("https://matrix.to/#/!n8f893n9:example.com?via=exa%20mple.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+
+    #[test]
+    fn invalid_permalink_encoding() {
+        assert_eq!(
+            MatrixToUri::try_from("https://matrix.to/#/%21%FFn8f893n9:example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidCharacters
+        );
+    }
+}
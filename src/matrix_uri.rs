@@ -0,0 +1,501 @@
+//! `matrix:` URIs.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    convert::TryFrom,
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
+use percent_encoding::utf8_percent_encode;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    deserialize_id,
+    error::{Error, ErrorKind},
+    event_id::EventId,
+    percent_decode,
+    room_alias_id::RoomAliasId,
+    room_id::RoomId,
+    room_id_or_room_alias_id::RoomIdOrAliasId,
+    server_name::ServerName,
+    user_id::UserId,
+    SIGIL_BYTES, URI_COMPONENT,
+};
+
+/// The scheme prefix of every `matrix:` URI.
+const MATRIX_SCHEME: &str = "matrix:";
+
+/// The identifier a `matrix:` URI refers to.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum MatrixId {
+    /// A room, referred to by its room ID.
+    Room(RoomId),
+    /// A room, referred to by one of its aliases.
+    RoomAlias(RoomAliasId),
+    /// A user.
+    User(UserId),
+    /// An event in a room.
+    Event(RoomIdOrAliasId, EventId),
+}
+
+impl From<RoomId> for MatrixId {
+    fn from(room_id: RoomId) -> Self {
+        MatrixId::Room(room_id)
+    }
+}
+
+impl From<RoomAliasId> for MatrixId {
+    fn from(room_alias_id: RoomAliasId) -> Self {
+        MatrixId::RoomAlias(room_alias_id)
+    }
+}
+
+impl From<RoomIdOrAliasId> for MatrixId {
+    fn from(room: RoomIdOrAliasId) -> Self {
+        match room {
+            RoomIdOrAliasId::RoomAliasId(room_alias_id) => MatrixId::RoomAlias(room_alias_id),
+            RoomIdOrAliasId::RoomId(room_id) => MatrixId::Room(room_id),
+        }
+    }
+}
+
+impl From<UserId> for MatrixId {
+    fn from(user_id: UserId) -> Self {
+        MatrixId::User(user_id)
+    }
+}
+
+/// The action a `matrix:` URI suggests to perform on the identifier it refers to.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum UriAction {
+    /// Join the room.
+    Join,
+    /// Start a direct chat with the user.
+    Chat,
+    /// An action that isn't specified yet.
+    Custom(String),
+}
+
+impl UriAction {
+    /// Returns the action as a string slice, as given in the `action` query parameter.
+    pub fn as_str(&self) -> &str {
+        match self {
+            UriAction::Join => "join",
+            UriAction::Chat => "chat",
+            UriAction::Custom(action) => action,
+        }
+    }
+}
+
+impl From<&str> for UriAction {
+    fn from(action: &str) -> Self {
+        match action {
+            "join" => UriAction::Join,
+            "chat" => UriAction::Chat,
+            custom => UriAction::Custom(custom.to_string()),
+        }
+    }
+}
+
+/// A `matrix:` URI, as proposed in [MSC2312](https://github.com/matrix-org/matrix-doc/pull/2312).
+///
+/// A `matrix:` URI refers to a user, a room or an event in a room, optionally suggesting an action
+/// to perform and the server names of homeservers that can be used to join the room, given as
+/// `action` and `via` query parameters. A `MatrixUri` is converted from a string slice, and can be
+/// converted back into a string as needed, percent-encoding its parts.
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use ruma_identifiers::{MatrixUri, UriAction, UserId};
+/// let uri = MatrixUri::new(
+///     UserId::try_from("@carl:example.com").unwrap().into(),
+///     Some(UriAction::Chat),
+///     Vec::new(),
+/// );
+/// assert_eq!(uri.to_string(), "matrix:u/carl:example.com?action=chat");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MatrixUri {
+    /// The identifier the URI refers to.
+    id: MatrixId,
+    /// The action the URI suggests, if any.
+    action: Option<UriAction>,
+    /// The server names of homeservers that can be used to join the room.
+    via: Vec<ServerName>,
+}
+
+impl MatrixUri {
+    /// Creates a `matrix:` URI referring to the given identifier, with the given action and server
+    /// names of homeservers that can be used to join the room.
+    pub fn new(id: MatrixId, action: Option<UriAction>, via: Vec<ServerName>) -> Self {
+        Self { id, action, via }
+    }
+
+    /// Returns the identifier the URI refers to.
+    pub fn id(&self) -> &MatrixId {
+        &self.id
+    }
+
+    /// Returns the action the URI suggests, if any.
+    pub fn action(&self) -> Option<&UriAction> {
+        self.action.as_ref()
+    }
+
+    /// Returns the server names of homeservers that can be used to join the room.
+    pub fn via(&self) -> &[ServerName] {
+        &self.via
+    }
+}
+
+impl From<MatrixId> for MatrixUri {
+    fn from(id: MatrixId) -> Self {
+        Self::new(id, None, Vec::new())
+    }
+}
+
+/// Writes an identifier as a path segment of a `matrix:` URI, i.e. percent-encoded and without its
+/// sigil.
+fn write_segment(f: &mut Formatter<'_>, kind: &str, id: &str) -> FmtResult {
+    write!(
+        f,
+        "{}/{}",
+        kind,
+        utf8_percent_encode(&id[SIGIL_BYTES..], URI_COMPONENT)
+    )
+}
+
+/// Writes a room as a path segment of a `matrix:` URI.
+fn write_room_segment(f: &mut Formatter<'_>, room: &RoomIdOrAliasId) -> FmtResult {
+    match room {
+        RoomIdOrAliasId::RoomAliasId(room_alias_id) => {
+            write_segment(f, "r", room_alias_id.as_str())
+        }
+        RoomIdOrAliasId::RoomId(room_id) => write_segment(f, "roomid", room_id.as_str()),
+    }
+}
+
+impl Display for MatrixUri {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(MATRIX_SCHEME)?;
+
+        match &self.id {
+            MatrixId::Room(room_id) => write_segment(f, "roomid", room_id.as_str())?,
+            MatrixId::RoomAlias(room_alias_id) => write_segment(f, "r", room_alias_id.as_str())?,
+            MatrixId::User(user_id) => write_segment(f, "u", user_id.as_str())?,
+            MatrixId::Event(room, event_id) => {
+                write_room_segment(f, room)?;
+                f.write_str("/")?;
+                write_segment(f, "e", event_id.as_str())?;
+            }
+        }
+
+        let mut separator = '?';
+        if let Some(action) = &self.action {
+            write!(
+                f,
+                "{}action={}",
+                separator,
+                utf8_percent_encode(action.as_str(), URI_COMPONENT)
+            )?;
+            separator = '&';
+        }
+
+        for server_name in &self.via {
+            write!(
+                f,
+                "{}via={}",
+                separator,
+                utf8_percent_encode(&server_name.to_string(), URI_COMPONENT)
+            )?;
+            separator = '&';
+        }
+
+        Ok(())
+    }
+}
+
+impl Serialize for MatrixUri {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MatrixUri {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_id(deserializer, "a matrix: URI as a string")
+    }
+}
+
+/// Decodes a path segment of a `matrix:` URI and prepends the given sigil.
+fn decode_segment(sigil: char, segment: &str, uri: &str) -> Result<String, Error> {
+    Ok(format!("{}{}", sigil, percent_decode(segment, uri)?))
+}
+
+/// Parses the room a pair of path segments of a `matrix:` URI refers to.
+fn parse_room(kind: &str, segment: &str, uri: &str) -> Result<RoomIdOrAliasId, Error> {
+    let room = match kind {
+        "r" => RoomAliasId::try_from(decode_segment('#', segment, uri)?.as_str())
+            .map(RoomIdOrAliasId::RoomAliasId),
+        "roomid" => RoomId::try_from(decode_segment('!', segment, uri)?.as_str())
+            .map(RoomIdOrAliasId::RoomId),
+        _ => return Err(Error::new(ErrorKind::InvalidUriPath, uri)),
+    };
+
+    room.map_err(|err| err.with_input(uri))
+}
+
+impl TryFrom<&str> for MatrixUri {
+    type Error = Error;
+
+    /// Attempts to create a new `matrix:` URI from a string representation.
+    ///
+    /// The string must start with `matrix:`, followed by `u/` and a user ID, `r/` and a room alias
+    /// ID, or `roomid/` and a room ID, each percent-encoded and without its sigil. A room can be
+    /// followed by `/e/` and a percent-encoded event ID without its sigil. An optional query string
+    /// may contain an `action` and `via` parameters, which must be valid server names. Other query
+    /// parameters are ignored.
+    fn try_from(uri: &str) -> Result<Self, Error> {
+        if !uri.starts_with(MATRIX_SCHEME) {
+            return Err(Error::new(ErrorKind::InvalidUriScheme, uri));
+        }
+
+        let rest = &uri[MATRIX_SCHEME.len()..];
+        let (path, query) = match rest.find('?') {
+            Some(index) => (&rest[..index], Some(&rest[index + 1..])),
+            None => (rest, None),
+        };
+
+        let mut segments = path.split('/');
+        let id = match (segments.next(), segments.next()) {
+            (Some("u"), Some(user_id)) => MatrixId::User(
+                UserId::try_from(decode_segment('@', user_id, uri)?.as_str())
+                    .map_err(|err| err.with_input(uri))?,
+            ),
+            (Some(kind), Some(room)) => {
+                let room = parse_room(kind, room, uri)?;
+
+                match (segments.next(), segments.next()) {
+                    (None, _) => room.into(),
+                    (Some("e"), Some(event_id)) => MatrixId::Event(
+                        room,
+                        EventId::try_from(decode_segment('$', event_id, uri)?.as_str())
+                            .map_err(|err| err.with_input(uri))?,
+                    ),
+                    _ => return Err(Error::new(ErrorKind::InvalidUriPath, uri)),
+                }
+            }
+            _ => return Err(Error::new(ErrorKind::InvalidUriPath, uri)),
+        };
+
+        if segments.next().is_some() {
+            return Err(Error::new(ErrorKind::InvalidUriPath, uri));
+        }
+
+        let mut action = None;
+        let mut via = Vec::new();
+        for parameter in query.into_iter().flat_map(|query| query.split('&')) {
+            if let Some(value) = parameter.strip_prefix("action=") {
+                let value = percent_decode(value, uri)?;
+                action = Some(UriAction::from(value.as_str()));
+            } else if let Some(server_name) = parameter.strip_prefix("via=") {
+                let server_name = percent_decode(server_name, uri)?;
+                via.push(
+                    ServerName::try_from(server_name.as_str())
+                        .map_err(|err| err.with_input(uri))?,
+                );
+            }
+        }
+
+        Ok(Self { id, action, via })
+    }
+}
+
+impl TryFrom<String> for MatrixUri {
+    type Error = Error;
+
+    /// Attempts to create a new `matrix:` URI from an owned string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn try_from(uri: String) -> Result<Self, Error> {
+        Self::try_from(uri.as_str())
+    }
+}
+
+impl FromStr for MatrixUri {
+    type Err = Error;
+
+    /// Attempts to create a new `matrix:` URI from a string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use serde_json::{from_str, to_string};
+
+    use super::{MatrixId, MatrixUri, UriAction};
+    use crate::{
+        error::ErrorKind, EventId, RoomAliasId, RoomId, RoomIdOrAliasId, ServerName, UserId,
+    };
+
+    fn round_trip(uri: &MatrixUri) -> MatrixUri {
+        MatrixUri::try_from(uri.to_string().as_str()).expect("Failed to create MatrixUri.")
+    }
+
+    #[test]
+    fn user_uri_round_trip() {
+        let uri = MatrixUri::new(
+            UserId::try_from("@carl:example.com")
+                .expect("Failed to create UserId.")
+                .into(),
+            Some(UriAction::Chat),
+            Vec::new(),
+        );
+
+        assert_eq!(uri.to_string(), "matrix:u/carl:example.com?action=chat");
+        assert_eq!(round_trip(&uri), uri);
+    }
+
+    #[test]
+    fn room_uri_round_trip() {
+        let uri = MatrixUri::new(
+            RoomId::try_from("!n8f893n9:example.com")
+                .expect("Failed to create RoomId.")
+                .into(),
+            Some(UriAction::Join),
+            vec![ServerName::try_from("example.org").expect("Failed to create ServerName.")],
+        );
+
+        assert_eq!(
+            uri.to_string(),
+            "matrix:roomid/n8f893n9:example.com?action=join&via=example.org"
+        );
+        assert_eq!(round_trip(&uri), uri);
+    }
+
+    #[test]
+    fn alias_uri_round_trip() {
+        let uri = MatrixUri::from(MatrixId::from(
+            RoomAliasId::try_from("#ruma:example.com").expect("Failed to create RoomAliasId."),
+        ));
+
+        assert_eq!(uri.to_string(), "matrix:r/ruma:example.com");
+        assert_eq!(round_trip(&uri), uri);
+    }
+
+    #[test]
+    fn event_uri_round_trip() {
+        let uri = MatrixUri::new(
+            MatrixId::Event(
+                RoomIdOrAliasId::try_from("#ruma:example.com")
+                    .expect("Failed to create RoomIdOrAliasId."),
+                EventId::try_from("$39hvsi03hlne:example.com").expect("Failed to create EventId."),
+            ),
+            None,
+            vec![
+                ServerName::try_from("example.org").expect("Failed to create ServerName."),
+                ServerName::try_from("[::1]:5000").expect("Failed to create ServerName."),
+            ],
+        );
+
+        assert_eq!(
+            uri.to_string(),
+            "matrix:r/ruma:example.com/e/39hvsi03hlne:example.com\
+             ?via=example.org&via=%5B::1%5D:5000"
+        );
+        assert_eq!(round_trip(&uri), uri);
+    }
+
+    #[test]
+    fn parse_encoded_server_name() {
+        let uri = MatrixUri::try_from("matrix:u/carl:%5B::1%5D:5000?action=custom&other=ignored")
+            .expect("Failed to create MatrixUri.");
+
+        assert_eq!(
+            uri.id(),
+            &MatrixId::User(
+                UserId::try_from("@carl:[::1]:5000").expect("Failed to create UserId.")
+            )
+        );
+        assert_eq!(uri.action(), Some(&UriAction::Custom("custom".to_string())));
+        assert!(uri.via().is_empty());
+    }
+
+    #[test]
+    fn serialize_valid_matrix_uri() {
+        assert_eq!(
+            to_string(
+                &MatrixUri::try_from("matrix:r/ruma:example.com")
+                    .expect("Failed to create MatrixUri.")
+            )
+            .expect("Failed to convert MatrixUri to JSON."),
+            r#""matrix:r/ruma:example.com""#
+        );
+    }
+
+    #[test]
+    fn deserialize_valid_matrix_uri() {
+        assert_eq!(
+            from_str::<MatrixUri>(r#""matrix:r/ruma:example.com""#)
+                .expect("Failed to convert JSON to MatrixUri"),
+            MatrixUri::try_from("matrix:r/ruma:example.com").expect("Failed to create MatrixUri.")
+        );
+    }
+
+    #[test]
+    fn invalid_matrix_uri_scheme() {
+        assert_eq!(
+            MatrixUri::try_from("https://matrix.to/#/@carl:example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidUriScheme
+        );
+    }
+
+    #[test]
+    fn unknown_matrix_uri_path() {
+        for uri in &[
+            "matrix:",
+            "matrix:x/carl:example.com",
+            "matrix:user/carl:example.com",
+            "matrix:u/carl:example.com/e/39hvsi03hlne:example.com",
+            "matrix:r/ruma:example.com/x/39hvsi03hlne:example.com",
+            "matrix:r/ruma:example.com/e/39hvsi03hlne:example.com/extra",
+        ] {
+            assert_eq!(
+                MatrixUri::try_from(*uri).unwrap_err().kind(),
+                ErrorKind::InvalidUriPath
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_matrix_uri_identifiers() {
+        let error = MatrixUri::try_from("matrix:u/carl").unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::MissingDelimiter);
+        assert_eq!(error.input(), "matrix:u/carl");
+        assert_eq!(
+            MatrixUri::try_from("matrix:r/ruma:example.com?via=exa%20mple.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+}
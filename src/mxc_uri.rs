@@ -0,0 +1,244 @@
+//! Matrix content URIs.
+
+use alloc::string::{String, ToString};
+use core::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    deserialize_id,
+    error::{Error, ErrorKind},
+    server_name::ServerName,
+};
+
+/// The scheme prefix of every content URI.
+const MXC_SCHEME: &str = "mxc://";
+
+/// A Matrix content URI.
+///
+/// Content uploaded to a homeserver's media repository, like avatars and attachments, is referenced
+/// by an `mxc://` URI consisting of the server name of the homeserver and an opaque media ID. An
+/// `MxcUri` is converted from a string slice, and can be converted back into a string as needed.
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use ruma_identifiers::MxcUri;
+/// let uri = MxcUri::try_from("mxc://example.com/ascERGshawAWawugaAcauga").unwrap();
+/// assert_eq!(uri.server_name().to_string(), "example.com");
+/// assert_eq!(uri.media_id(), "ascERGshawAWawugaAcauga");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MxcUri {
+    /// The server name of the homeserver hosting the content.
+    server_name: ServerName,
+    /// The ID of the content on the homeserver.
+    media_id: String,
+}
+
+impl MxcUri {
+    /// Returns the server name of the homeserver hosting the content.
+    pub fn server_name(&self) -> &ServerName {
+        &self.server_name
+    }
+
+    /// Returns the opaque ID of the content on the homeserver.
+    pub fn media_id(&self) -> &str {
+        &self.media_id
+    }
+}
+
+impl Display for MxcUri {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}{}/{}", MXC_SCHEME, self.server_name, self.media_id)
+    }
+}
+
+impl PartialOrd for MxcUri {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Content URIs are ordered lexicographically by their string representation, not by their
+/// individual components.
+impl Ord for MxcUri {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_string().cmp(&other.to_string())
+    }
+}
+
+impl Serialize for MxcUri {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MxcUri {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_id(deserializer, "a Matrix content URI as a string")
+    }
+}
+
+impl TryFrom<&str> for MxcUri {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix content URI from a string representation.
+    ///
+    /// The string must include the leading `mxc://` scheme, a valid server name, a literal slash,
+    /// and a media ID consisting of ASCII letters, digits, hyphens and underscores.
+    fn try_from(uri: &str) -> Result<Self, Error> {
+        if !uri.starts_with(MXC_SCHEME) {
+            return Err(Error::new(ErrorKind::InvalidUriScheme, uri));
+        }
+
+        let rest = &uri[MXC_SCHEME.len()..];
+        let (raw_server_name, media_id) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index + 1..]),
+            None => return Err(Error::new(ErrorKind::MissingMediaId, uri)),
+        };
+
+        if media_id.is_empty() {
+            return Err(Error::new(ErrorKind::MissingMediaId, uri));
+        }
+
+        if !media_id
+            .bytes()
+            .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' | b'-' | b'_'))
+        {
+            return Err(Error::new(ErrorKind::InvalidCharacters, uri));
+        }
+
+        Ok(Self {
+            server_name: ServerName::try_from(raw_server_name)
+                .map_err(|err| err.with_input(uri))?,
+            media_id: media_id.to_string(),
+        })
+    }
+}
+
+impl TryFrom<String> for MxcUri {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix content URI from an owned string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn try_from(uri: String) -> Result<Self, Error> {
+        Self::try_from(uri.as_str())
+    }
+}
+
+impl FromStr for MxcUri {
+    type Err = Error;
+
+    /// Attempts to create a new Matrix content URI from a string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use serde_json::{from_str, to_string};
+
+    use super::MxcUri;
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn valid_mxc_uri() {
+        let uri = MxcUri::try_from("mxc://example.com/ascERGshawAWawugaAcauga")
+            .expect("Failed to create MxcUri.");
+
+        assert_eq!(uri.to_string(), "mxc://example.com/ascERGshawAWawugaAcauga");
+        assert_eq!(uri.server_name().to_string(), "example.com");
+        assert_eq!(uri.media_id(), "ascERGshawAWawugaAcauga");
+    }
+
+    #[test]
+    fn valid_mxc_uri_with_port() {
+        assert_eq!(
+            MxcUri::try_from("mxc://example.com:5000/ascERGshawAWawugaAcauga")
+                .expect("Failed to create MxcUri.")
+                .to_string(),
+            "mxc://example.com:5000/ascERGshawAWawugaAcauga"
+        );
+    }
+
+    #[test]
+    fn serialize_valid_mxc_uri() {
+        assert_eq!(
+            to_string(
+                &MxcUri::try_from("mxc://example.com/ascERGshawAWawugaAcauga")
+                    .expect("Failed to create MxcUri.")
+            )
+            .expect("Failed to convert MxcUri to JSON."),
+            r#""mxc://example.com/ascERGshawAWawugaAcauga""#
+        );
+    }
+
+    #[test]
+    fn deserialize_valid_mxc_uri() {
+        assert_eq!(
+            from_str::<MxcUri>(r#""mxc://example.com/ascERGshawAWawugaAcauga""#)
+                .expect("Failed to convert JSON to MxcUri"),
+            MxcUri::try_from("mxc://example.com/ascERGshawAWawugaAcauga")
+                .expect("Failed to create MxcUri.")
+        );
+    }
+
+    #[test]
+    fn invalid_mxc_uri_scheme() {
+        assert_eq!(
+            MxcUri::try_from("https://example.com/ascERGshawAWawugaAcauga")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidUriScheme
+        );
+    }
+
+    #[test]
+    fn missing_mxc_uri_media_id() {
+        assert_eq!(
+            MxcUri::try_from("mxc://example.com").unwrap_err().kind(),
+            ErrorKind::MissingMediaId
+        );
+        assert_eq!(
+            MxcUri::try_from("mxc://example.com/").unwrap_err().kind(),
+            ErrorKind::MissingMediaId
+        );
+    }
+
+    #[test]
+    fn invalid_mxc_uri_media_id() {
+        assert_eq!(
+            MxcUri::try_from("mxc://example.com/asc/ERG")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidCharacters
+        );
+    }
+
+    #[test]
+    fn invalid_mxc_uri_host() {
+        assert_eq!(
+            MxcUri::try_from("mxc:///ascERGshawAWawugaAcauga")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! Implements `parse_many` for identifiers, which parses a list of strings at once.
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use crate::error::Error;
+
+macro_rules! parse_many_impl {
+    ($name:ident) => {
+        impl $crate::$name {
+            /// Attempts to parse each of the given strings, e.g. the elements of an array in a
+            /// request, with the `TryFrom<&str>` implementation.
+            ///
+            /// The results are returned in the order of the input, so a failure can be traced back
+            /// to the string that caused it.
+            pub fn parse_many<'a>(
+                ids: impl IntoIterator<Item = &'a str>,
+            ) -> Vec<Result<Self, Error>> {
+                ids.into_iter().map(Self::try_from).collect()
+            }
+        }
+    };
+}
+
+parse_many_impl!(DeviceId);
+parse_many_impl!(EventId);
+parse_many_impl!(GroupId);
+parse_many_impl!(KeyId);
+parse_many_impl!(MxcUri);
+parse_many_impl!(RoomAlias);
+parse_many_impl!(RoomAliasId);
+parse_many_impl!(RoomId);
+parse_many_impl!(RoomIdOrAliasId);
+parse_many_impl!(RoomVersionId);
+parse_many_impl!(ServerName);
+parse_many_impl!(UserId);
+parse_many_impl!(UserLocalpart);
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::{error::ErrorKind, RoomVersionId, UserId};
+
+    #[test]
+    fn parse_many_user_ids_preserves_order() {
+        let results = UserId::parse_many(vec![
+            "@carl:example.com",
+            "carl:example.com",
+            "@alice:example.org",
+            "@bob",
+        ]);
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(
+            results[0].as_ref().expect("Failed to create UserId."),
+            &UserId::try_from("@carl:example.com").expect("Failed to create UserId.")
+        );
+        assert_eq!(
+            results[1].as_ref().unwrap_err().kind(),
+            ErrorKind::MissingSigil
+        );
+        assert_eq!(
+            results[2].as_ref().expect("Failed to create UserId."),
+            "@alice:example.org"
+        );
+        assert_eq!(
+            results[3].as_ref().unwrap_err().kind(),
+            ErrorKind::MissingDelimiter
+        );
+    }
+
+    #[test]
+    fn parse_many_without_ids() {
+        assert!(RoomVersionId::parse_many(Vec::new()).is_empty());
+    }
+}
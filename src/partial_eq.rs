@@ -0,0 +1,157 @@
+//! Implements `PartialEq` between identifiers and strings.
+//!
+//! Identifiers are compared against their string representation, so a string that spells an
+//! identifier differently, e.g. with an explicit default port, does not compare equal.
+
+use alloc::string::String;
+use core::fmt::{Display, Error as FmtError, Result as FmtResult, Write};
+
+/// Compares the `Display` output of a value with a string without allocating.
+pub(crate) fn display_eq(value: &impl Display, other: &str) -> bool {
+    /// A writer that checks whether everything written to it is a prefix of `remaining`.
+    struct Comparer<'a> {
+        remaining: &'a str,
+    }
+
+    impl Write for Comparer<'_> {
+        fn write_str(&mut self, s: &str) -> FmtResult {
+            if self.remaining.starts_with(s) {
+                self.remaining = &self.remaining[s.len()..];
+                Ok(())
+            } else {
+                Err(FmtError)
+            }
+        }
+    }
+
+    let mut comparer = Comparer { remaining: other };
+    write!(comparer, "{}", value).is_ok() && comparer.remaining.is_empty()
+}
+
+macro_rules! partial_eq_impl {
+    ($name:ident) => {
+        impl PartialEq<str> for $crate::$name {
+            fn eq(&self, other: &str) -> bool {
+                display_eq(self, other)
+            }
+        }
+
+        impl PartialEq<&str> for $crate::$name {
+            fn eq(&self, other: &&str) -> bool {
+                display_eq(self, other)
+            }
+        }
+
+        impl PartialEq<String> for $crate::$name {
+            fn eq(&self, other: &String) -> bool {
+                display_eq(self, other)
+            }
+        }
+
+        impl PartialEq<$crate::$name> for str {
+            fn eq(&self, other: &$crate::$name) -> bool {
+                other == self
+            }
+        }
+
+        impl PartialEq<$crate::$name> for &str {
+            fn eq(&self, other: &$crate::$name) -> bool {
+                other == self
+            }
+        }
+
+        impl PartialEq<$crate::$name> for String {
+            fn eq(&self, other: &$crate::$name) -> bool {
+                other == self
+            }
+        }
+    };
+}
+
+partial_eq_impl!(DeviceId);
+partial_eq_impl!(EventId);
+partial_eq_impl!(GroupId);
+partial_eq_impl!(KeyId);
+partial_eq_impl!(MatrixToUri);
+partial_eq_impl!(MatrixUri);
+partial_eq_impl!(MxcUri);
+partial_eq_impl!(RoomAlias);
+partial_eq_impl!(RoomAliasId);
+partial_eq_impl!(RoomId);
+partial_eq_impl!(RoomIdOrAliasId);
+partial_eq_impl!(RoomVersionId);
+partial_eq_impl!(ServerName);
+partial_eq_impl!(UserId);
+partial_eq_impl!(UserLocalpart);
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::{EventId, RoomAliasId, RoomId, RoomIdOrAliasId, RoomVersionId, UserId};
+
+    #[test]
+    fn compare_event_id_with_strings() {
+        let event_id =
+            EventId::try_from("$39hvsi03hlne:example.com").expect("Failed to create EventId.");
+
+        assert_eq!(event_id, "$39hvsi03hlne:example.com");
+        assert_eq!(event_id, *"$39hvsi03hlne:example.com");
+        assert_eq!(event_id, "$39hvsi03hlne:example.com".to_string());
+        assert_eq!("$39hvsi03hlne:example.com", event_id);
+        assert_ne!(event_id, "$39hvsi03hlne:example.org");
+    }
+
+    #[test]
+    fn compare_room_id_with_strings() {
+        let room_id =
+            RoomId::try_from("!29fhd83h92h0:example.com").expect("Failed to create RoomId.");
+
+        assert_eq!(room_id, "!29fhd83h92h0:example.com");
+        assert_eq!("!29fhd83h92h0:example.com".to_string(), room_id);
+        assert_ne!(room_id, "!29fhd83h92h0:example.com:5000");
+    }
+
+    #[test]
+    fn compare_room_alias_id_with_strings() {
+        let room_alias_id =
+            RoomAliasId::try_from("#ruma:example.com").expect("Failed to create RoomAliasId.");
+
+        assert_eq!(room_alias_id, "#ruma:example.com");
+        assert_ne!(room_alias_id, "!ruma:example.com");
+    }
+
+    #[test]
+    fn compare_room_id_or_alias_id_with_strings() {
+        let room_id_or_alias_id =
+            RoomIdOrAliasId::try_from("#ruma:example.com").expect("Failed to create RoomAliasId.");
+
+        assert_eq!(room_id_or_alias_id, "#ruma:example.com");
+        assert_ne!(room_id_or_alias_id, "#ruma2:example.com");
+    }
+
+    #[test]
+    fn compare_user_id_with_strings() {
+        let user_id = UserId::try_from("@carl:example.com").expect("Failed to create UserId.");
+
+        assert_eq!(user_id, "@carl:example.com");
+        assert_eq!(*"@carl:example.com", user_id);
+        assert_ne!(user_id, "@CARL:example.com");
+    }
+
+    #[test]
+    fn compare_room_version_id_with_strings() {
+        assert_eq!(RoomVersionId::version_1(), "1");
+        assert_eq!(RoomVersionId::custom("io.ruma.1"), "io.ruma.1");
+        assert_ne!(RoomVersionId::version_1(), "2");
+    }
+
+    #[test]
+    fn explicit_default_port_is_not_canonical() {
+        let room_id =
+            RoomId::try_from("!29fhd83h92h0:example.com:8448").expect("Failed to create RoomId.");
+
+        assert_eq!(room_id, "!29fhd83h92h0:example.com");
+        assert_ne!(room_id, "!29fhd83h92h0:example.com:8448");
+    }
+}
@@ -0,0 +1,241 @@
+//! Room aliases resolved to room IDs.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::{Formatter, Result as FmtResult};
+
+use serde::{
+    de::{Error as _, IgnoredAny, MapAccess, Visitor},
+    ser::SerializeStruct,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{room_alias_id::RoomAliasId, room_id::RoomId, server_name::ServerName};
+
+/// A room alias along with the room ID it resolved to.
+///
+/// Besides the room ID, resolving a room alias through the room directory yields the server names
+/// of homeservers that are aware of the room, which can be used to join it. A `ResolvedRoom` keeps
+/// these together with the alias they belong to.
+///
+/// A `ResolvedRoom` is serialized with the fields of a directory lookup response, `room_id` and
+/// `servers`, and additionally the alias as `room_alias`, since the response itself doesn't repeat
+/// the alias that was looked up. Other fields are ignored when deserializing.
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use ruma_identifiers::{ResolvedRoom, RoomAliasId, RoomId, ServerName};
+/// let resolved = ResolvedRoom::new(
+///     RoomAliasId::try_from("#ruma:example.com").unwrap(),
+///     RoomId::try_from("!n8f893n9:example.com").unwrap(),
+///     vec![ServerName::try_from("example.org").unwrap()],
+/// );
+/// assert_eq!(resolved.room_id().as_str(), "!n8f893n9:example.com");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ResolvedRoom {
+    /// The room alias that was resolved.
+    alias: RoomAliasId,
+    /// The room ID the alias resolved to.
+    room_id: RoomId,
+    /// The server names of homeservers that can be used to join the room.
+    via: Vec<ServerName>,
+}
+
+impl ResolvedRoom {
+    /// Creates a `ResolvedRoom` from a room alias, the room ID it resolved to and the server names
+    /// of homeservers that can be used to join the room.
+    pub fn new(alias: RoomAliasId, room_id: RoomId, via: Vec<ServerName>) -> Self {
+        Self {
+            alias,
+            room_id,
+            via,
+        }
+    }
+
+    /// Returns the room alias that was resolved.
+    pub fn alias(&self) -> &RoomAliasId {
+        &self.alias
+    }
+
+    /// Returns the room ID the alias resolved to.
+    pub fn room_id(&self) -> &RoomId {
+        &self.room_id
+    }
+
+    /// Returns the server names of homeservers that can be used to join the room.
+    pub fn via(&self) -> &[ServerName] {
+        &self.via
+    }
+
+    /// Converts the `ResolvedRoom` into the room alias, the room ID and the server names.
+    pub fn into_parts(self) -> (RoomAliasId, RoomId, Vec<ServerName>) {
+        (self.alias, self.room_id, self.via)
+    }
+}
+
+impl Serialize for ResolvedRoom {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("ResolvedRoom", 3)?;
+        state.serialize_field("room_alias", &self.alias)?;
+        state.serialize_field("room_id", &self.room_id)?;
+        state.serialize_field("servers", &self.via)?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for ResolvedRoom {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(
+            "ResolvedRoom",
+            &["room_alias", "room_id", "servers"],
+            ResolvedRoomVisitor,
+        )
+    }
+}
+
+/// A serde visitor for `ResolvedRoom`.
+struct ResolvedRoomVisitor;
+
+impl<'de> Visitor<'de> for ResolvedRoomVisitor {
+    type Value = ResolvedRoom;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> FmtResult {
+        formatter.write_str("a resolved room alias with a room ID and servers")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut alias = None;
+        let mut room_id = None;
+        let mut via = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "room_alias" if alias.is_some() => {
+                    return Err(A::Error::duplicate_field("room_alias"))
+                }
+                "room_alias" => alias = Some(map.next_value()?),
+                "room_id" if room_id.is_some() => return Err(A::Error::duplicate_field("room_id")),
+                "room_id" => room_id = Some(map.next_value()?),
+                "servers" if via.is_some() => return Err(A::Error::duplicate_field("servers")),
+                "servers" => via = Some(map.next_value()?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        Ok(ResolvedRoom {
+            alias: alias.ok_or_else(|| A::Error::missing_field("room_alias"))?,
+            room_id: room_id.ok_or_else(|| A::Error::missing_field("room_id"))?,
+            via: via.ok_or_else(|| A::Error::missing_field("servers"))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use serde_json::{from_str, from_value, json, to_value};
+
+    use super::ResolvedRoom;
+    use crate::{RoomAliasId, RoomId, ServerName};
+
+    fn resolved_room() -> ResolvedRoom {
+        ResolvedRoom::new(
+            RoomAliasId::try_from("#ruma:example.com").expect("Failed to create RoomAliasId."),
+            RoomId::try_from("!abnjk1jdasj98:capuchin.example.com")
+                .expect("Failed to create RoomId."),
+            vec![
+                ServerName::try_from("capuchin.example.com").expect("Failed to create ServerName."),
+                ServerName::try_from("matrix.org").expect("Failed to create ServerName."),
+            ],
+        )
+    }
+
+    #[test]
+    fn resolved_room_parts() {
+        let resolved = resolved_room();
+
+        assert_eq!(resolved.alias(), "#ruma:example.com");
+        assert_eq!(resolved.room_id(), "!abnjk1jdasj98:capuchin.example.com");
+        assert_eq!(resolved.via().len(), 2);
+        assert_eq!(resolved.via()[1].to_string(), "matrix.org");
+
+        let (alias, room_id, via) = resolved.clone().into_parts();
+
+        assert_eq!(&alias, resolved.alias());
+        assert_eq!(&room_id, resolved.room_id());
+        assert_eq!(via, resolved.via());
+    }
+
+    #[test]
+    fn resolved_room_round_trip() {
+        let json = json!({
+            "room_alias": "#ruma:example.com",
+            "room_id": "!abnjk1jdasj98:capuchin.example.com",
+            "servers": ["capuchin.example.com", "matrix.org"]
+        });
+
+        assert_eq!(
+            to_value(resolved_room()).expect("Failed to convert ResolvedRoom to JSON."),
+            json
+        );
+        assert_eq!(
+            from_value::<ResolvedRoom>(json).expect("Failed to convert JSON to ResolvedRoom."),
+            resolved_room()
+        );
+    }
+
+    #[test]
+    fn deserialize_resolved_room_ignores_unknown_fields() {
+        assert_eq!(
+            from_str::<ResolvedRoom>(
+                r##"{
+                    "room_id": "!abnjk1jdasj98:capuchin.example.com",
+                    "servers": ["capuchin.example.com", "matrix.org"],
+                    "room_alias": "#ruma:example.com",
+                    "extra": { "nested": true }
+                }"##
+            )
+            .expect("Failed to convert JSON to ResolvedRoom."),
+            resolved_room()
+        );
+    }
+
+    #[test]
+    fn deserialize_directory_response_without_alias() {
+        let error = from_str::<ResolvedRoom>(
+            r#"{
+                "room_id": "!abnjk1jdasj98:capuchin.example.com",
+                "servers": ["capuchin.example.com", "matrix.org"]
+            }"#,
+        )
+        .unwrap_err();
+
+        assert!(error.to_string().contains("missing field `room_alias`"));
+    }
+
+    #[test]
+    fn deserialize_resolved_room_with_invalid_server() {
+        let error = from_str::<ResolvedRoom>(
+            r##"{
+                "room_alias": "#ruma:example.com",
+                "room_id": "!abnjk1jdasj98:capuchin.example.com",
+                "servers": ["exa mple.com"]
+            }"##,
+        )
+        .unwrap_err();
+
+        assert!(error.to_string().contains("invalid Matrix ID"));
+    }
+}
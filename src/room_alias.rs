@@ -0,0 +1,256 @@
+//! Matrix room aliases.
+
+use alloc::string::{String, ToString};
+use core::{
+    convert::TryFrom,
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
+#[cfg(feature = "diesel")]
+use diesel::sql_types::Text;
+use ruma_identifiers_validation::room_alias_id::validate_alias;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    deserialize_id,
+    error::{Error, ErrorKind},
+    MAX_BYTES,
+};
+
+/// The alias of a Matrix room alias ID, i.e. the part between the leading # sigil and the colon.
+///
+/// A `RoomAlias` is converted from a string slice, and can be converted back into a string as
+/// needed. It is what `RoomAliasId::alias` returns, so that it can't be mixed up with other parts
+/// of an identifier.
+///
+/// An alias may contain any printable character, including non-ASCII ones, except for colons,
+/// whitespace and control characters.
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use ruma_identifiers::RoomAlias;
+/// assert_eq!(RoomAlias::try_from("ruma").unwrap().as_str(), "ruma");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "diesel", derive(FromSqlRow, QueryId, AsExpression, SqlType))]
+#[cfg_attr(feature = "diesel", sql_type = "Text")]
+pub struct RoomAlias(String);
+
+impl RoomAlias {
+    /// Creates a `RoomAlias` from a string slice that is already known to be a valid alias, e.g.
+    /// because it was sliced out of a `RoomAliasId`.
+    pub(crate) fn from_valid(alias: &str) -> Self {
+        Self(alias.to_string())
+    }
+
+    /// Returns the alias as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Converts the alias into its string representation, without allocating.
+    ///
+    /// This returns the same string as `as_str` and `to_string`, but takes the stored string
+    /// instead of copying it.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl Display for RoomAlias {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for RoomAlias {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for RoomAlias {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_id(deserializer, "a Matrix room alias as a string")
+    }
+}
+
+/// Validates a borrowed or owned room alias, only allocating if it is valid and borrowed.
+fn parse_alias<S>(alias: S) -> Result<RoomAlias, Error>
+where
+    S: AsRef<str> + Into<String>,
+{
+    let raw_alias = alias.as_ref();
+
+    if raw_alias.is_empty() {
+        return Err(Error::new(ErrorKind::MissingLocalpart, raw_alias));
+    }
+
+    if raw_alias.len() > MAX_BYTES {
+        return Err(Error::new(ErrorKind::MaximumLengthExceeded, raw_alias));
+    }
+
+    validate_alias(raw_alias).map_err(|kind| Error::new(kind, raw_alias))?;
+
+    Ok(RoomAlias(alias.into()))
+}
+
+impl TryFrom<&str> for RoomAlias {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix room alias from a string representation.
+    ///
+    /// The string must not include the leading # sigil or the server name.
+    fn try_from(alias: &str) -> Result<Self, Error> {
+        parse_alias(alias)
+    }
+}
+
+impl TryFrom<String> for RoomAlias {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix room alias from an owned string representation.
+    ///
+    /// This performs the same checks as the `TryFrom<&str>` implementation, but reuses the string.
+    fn try_from(alias: String) -> Result<Self, Error> {
+        parse_alias(alias)
+    }
+}
+
+impl FromStr for RoomAlias {
+    type Err = Error;
+
+    /// Attempts to create a new Matrix room alias from a string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use serde_json::{from_str, to_string};
+
+    use super::RoomAlias;
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn valid_room_alias() {
+        assert_eq!(
+            RoomAlias::try_from("ruma")
+                .expect("Failed to create RoomAlias.")
+                .to_string(),
+            "ruma"
+        );
+    }
+
+    #[test]
+    fn valid_room_alias_unicode() {
+        assert_eq!(
+            RoomAlias::try_from("老虎Â£я")
+                .expect("Failed to create RoomAlias.")
+                .as_str(),
+            "老虎Â£я"
+        );
+    }
+
+    #[test]
+    fn serialize_valid_room_alias() {
+        assert_eq!(
+            to_string(&RoomAlias::try_from("ruma").expect("Failed to create RoomAlias."))
+                .expect("Failed to convert RoomAlias to JSON."),
+            r#""ruma""#
+        );
+    }
+
+    #[test]
+    fn deserialize_valid_room_alias() {
+        assert_eq!(
+            from_str::<RoomAlias>(r#""ruma""#).expect("Failed to convert JSON to RoomAlias"),
+            RoomAlias::try_from("ruma").expect("Failed to create RoomAlias.")
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid_room_alias() {
+        let error = from_str::<RoomAlias>(r#""ruma:example.com""#).unwrap_err();
+
+        assert!(error
+            .to_string()
+            .contains("expected a Matrix room alias as a string"));
+    }
+
+    #[test]
+    fn invalid_characters_in_room_alias() {
+        assert_eq!(
+            RoomAlias::try_from("ruma:example.com").unwrap_err().kind(),
+            ErrorKind::InvalidCharacters
+        );
+    }
+
+    #[test]
+    fn room_alias_with_whitespace() {
+        for alias in &["ro om", "ro\tom", "ro\u{a0}om"] {
+            assert_eq!(
+                RoomAlias::try_from(*alias).unwrap_err().kind(),
+                ErrorKind::InvalidCharacters
+            );
+        }
+    }
+
+    #[test]
+    fn room_alias_with_control_characters() {
+        for alias in &["ro\nom", "ro\u{0}om", "ro\u{7f}om", "ro\u{85}om"] {
+            assert_eq!(
+                RoomAlias::try_from(*alias).unwrap_err().kind(),
+                ErrorKind::InvalidCharacters
+            );
+        }
+    }
+
+    #[test]
+    fn room_alias_with_punctuation() {
+        assert_eq!(
+            RoomAlias::try_from("ru-ma_#1!")
+                .expect("Failed to create RoomAlias.")
+                .as_str(),
+            "ru-ma_#1!"
+        );
+    }
+
+    #[test]
+    fn empty_room_alias() {
+        assert_eq!(
+            RoomAlias::try_from("").unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+    }
+
+    #[test]
+    fn room_alias_too_long() {
+        assert_eq!(
+            RoomAlias::try_from("a".repeat(256).as_str())
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+    }
+
+    #[test]
+    fn room_alias_into_string() {
+        let id = RoomAlias::try_from("ruma").expect("Failed to create RoomAlias.");
+        let string = id.to_string();
+
+        assert_eq!(id.into_string(), string);
+    }
+}
@@ -1,22 +1,36 @@
 //! Matrix room alias identifiers.
 
-use std::{
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::{
+    cmp::Ordering,
     convert::TryFrom,
     fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
 };
 
 #[cfg(feature = "diesel")]
 use diesel::sql_types::Text;
+use ruma_identifiers_validation::room_alias_id::{self as validation, validate_alias};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use url::Host;
 
-use crate::{deserialize_id, display, error::Error, parse_id};
+use crate::{
+    canonical_host, deserialize_id, error::Error, format_id, host::Host, id_from_host,
+    id_from_parts, parse_id, parse_owned_id, room_alias::RoomAlias, server_name::ServerName,
+    SIGIL_BYTES,
+};
 
 /// A Matrix room alias ID.
 ///
 /// A `RoomAliasId` is converted from a string slice, and can be converted back into a string as
 /// needed.
 ///
+/// Besides its components, a `RoomAliasId` stores its canonical string representation. This takes
+/// up roughly twice the memory, but allows it to be borrowed as a `&str`, e.g. to look it up in a
+/// `HashMap` or `HashSet` with a string slice.
+///
 /// ```
 /// # use std::convert::TryFrom;
 /// # use ruma_identifiers::RoomAliasId;
@@ -25,41 +39,166 @@ use crate::{deserialize_id, display, error::Error, parse_id};
 ///     "#ruma:example.com"
 /// );
 /// ```
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "diesel", derive(FromSqlRow, QueryId, AsExpression, SqlType))]
 #[cfg_attr(feature = "diesel", sql_type = "Text")]
 pub struct RoomAliasId {
-    /// The alias for the room.
-    alias: String,
-    /// The hostname of the homeserver.
-    hostname: Host,
-    /// The network port of the homeserver.
-    port: u16,
+    /// The room alias ID in its canonical string representation.
+    full_id: String,
+    /// The index of the colon separating the alias and the server name in `full_id`.
+    colon_idx: usize,
+    /// The server name of the homeserver.
+    server_name: ServerName,
 }
 
 impl RoomAliasId {
+    /// Creates a `RoomAliasId` from its alias and the server name of the homeserver.
+    ///
+    /// The alias is validated like a `RoomAlias`, and the server name like a `ServerName`.
+    pub fn from_parts(alias: &str, server_name: &str) -> Result<Self, Error> {
+        let (full_id, colon_idx, server_name) =
+            id_from_parts('#', alias, server_name, validate_alias)?;
+
+        Ok(Self {
+            full_id,
+            colon_idx,
+            server_name,
+        })
+    }
+
+    /// Creates a `RoomAliasId` from its alias and an already parsed host and port of the
+    /// homeserver.
+    ///
+    /// The alias is validated like a `RoomAlias`. Of the host and port, only the checks of
+    /// `ServerName::from_host` apply.
+    pub fn from_host(alias: &str, host: Host, port: u16) -> Result<Self, Error> {
+        let (full_id, colon_idx, server_name) =
+            id_from_host('#', alias, host, port, validate_alias)?;
+
+        Ok(Self {
+            full_id,
+            colon_idx,
+            server_name,
+        })
+    }
+
+    /// Creates a copy of the room alias ID with the same alias, but the given server name.
+    ///
+    /// Fails if the server name is invalid.
+    pub fn with_server_name(&self, server_name: &str) -> Result<Self, Error> {
+        Self::from_parts(&self.full_id[SIGIL_BYTES..self.colon_idx], server_name)
+    }
+
     /// Returns a `Host` for the room alias ID, containing the server name (minus the port) of
     /// the originating homeserver.
     ///
     /// The host can be either a domain name, an IPv4 address, or an IPv6 address.
     pub fn hostname(&self) -> &Host {
-        &self.hostname
+        self.server_name.host()
+    }
+
+    /// Whether the server name of the room alias ID is an IPv4 or IPv6 address rather than a domain
+    /// name.
+    pub fn is_ip_literal(&self) -> bool {
+        self.server_name.is_ip_literal()
+    }
+
+    /// Returns the host of the room alias ID's server name as a string slice, exactly as it
+    /// appears in the canonical string representation, i.e. without the port. IPv6 addresses are
+    /// enclosed in brackets.
+    pub fn host_str(&self) -> &str {
+        canonical_host(&self.full_id[self.colon_idx + 1..])
+    }
+
+    /// Returns the room alias ID as a string slice.
+    ///
+    /// The canonical string representation is stored when the room alias ID is created, so this
+    /// doesn't allocate, returns the same string as `to_string`, and can be called repeatedly for
+    /// free.
+    pub fn as_str(&self) -> &str {
+        &self.full_id
+    }
+
+    /// Converts the room alias ID into its string representation, without allocating.
+    ///
+    /// This returns the same string as `as_str` and `to_string`, but takes the stored string
+    /// instead of copying it.
+    pub fn into_string(self) -> String {
+        self.full_id
     }
 
     /// Returns the room's alias.
-    pub fn alias(&self) -> &str {
-        &self.alias
+    ///
+    /// The alias is copied into a new `RoomAlias`. Use `localpart` to borrow it as a string slice
+    /// instead.
+    pub fn alias(&self) -> RoomAlias {
+        RoomAlias::from_valid(self.localpart())
+    }
+
+    /// Returns the room's alias as a string slice, i.e. the part between the leading # sigil and
+    /// the colon.
+    ///
+    /// This is the same string as `alias`, but borrowed, and named like the `localpart` accessors
+    /// of the other IDs containing a server name.
+    pub fn localpart(&self) -> &str {
+        &self.full_id[SIGIL_BYTES..self.colon_idx]
     }
 
     /// Returns the port the originating homeserver can be accessed on.
     pub fn port(&self) -> u16 {
-        self.port
+        self.server_name.port()
+    }
+
+    /// Returns the port of the originating homeserver if it was given explicitly, even if it is
+    /// `DEFAULT_PORT`, or `None` if the room alias ID doesn't specify one.
+    pub fn explicit_port(&self) -> Option<u16> {
+        self.server_name.explicit_port()
+    }
+
+    /// Returns the server name of the originating homeserver, i.e. its hostname followed by the
+    /// port if it isn't `DEFAULT_PORT`.
+    pub fn server_name(&self) -> String {
+        self.server_name.to_string()
+    }
+
+    /// Returns the alias and the server name of the room alias ID in one call.
+    ///
+    /// Like `server_name`, the server name omits the port if it is `DEFAULT_PORT`.
+    pub fn parts(&self) -> (&str, String) {
+        (self.localpart(), self.server_name())
+    }
+
+    /// Returns the string representation of the ID, including the port if it was given explicitly,
+    /// even if it is the default port.
+    ///
+    /// Only the port is preserved, the rest of the ID is canonical. See
+    /// `ServerName::to_string_preserving_port`.
+    pub fn to_string_preserving_port(&self) -> String {
+        format!(
+            "#{}:{}",
+            self.localpart(),
+            self.server_name.to_string_preserving_port()
+        )
     }
 }
 
 impl Display for RoomAliasId {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        display(f, '#', &self.alias, &self.hostname, self.port)
+        f.write_str(&self.full_id)
+    }
+}
+
+impl PartialOrd for RoomAliasId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Room alias IDs are ordered lexicographically by their string representation, not by
+/// their individual components.
+impl Ord for RoomAliasId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
     }
 }
 
@@ -68,7 +207,7 @@ impl Serialize for RoomAliasId {
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        serializer.serialize_str(self.as_str())
     }
 }
 
@@ -87,26 +226,66 @@ impl TryFrom<&str> for RoomAliasId {
     /// Attempts to create a new Matrix room alias ID from a string representation.
     ///
     /// The string must include the leading # sigil, the alias, a literal colon, and a valid
-    /// server name.
+    /// server name. The alias must not contain whitespace or control characters.
     fn try_from(room_id: &str) -> Result<Self, Error> {
-        let (alias, host, port) = parse_id('#', room_id)?;
+        let (alias, server_name) = parse_id('#', room_id)?;
+        validate_alias(alias).map_err(|kind| Error::new(kind, room_id))?;
+
+        let (full_id, colon_idx) = format_id('#', alias, &server_name);
 
         Ok(Self {
-            alias: alias.to_owned(),
-            hostname: host,
-            port,
+            full_id,
+            colon_idx,
+            server_name,
         })
     }
 }
 
+impl TryFrom<String> for RoomAliasId {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix room alias ID from an owned string representation.
+    ///
+    /// This performs the same checks as the `TryFrom<&str>` implementation, but reuses the string
+    /// if it already is the canonical string representation of the room alias ID.
+    fn try_from(room_alias_id: String) -> Result<Self, Error> {
+        let (full_id, colon_idx, server_name, ()) =
+            parse_owned_id('#', room_alias_id, validate_alias)?;
+
+        Ok(Self {
+            full_id,
+            colon_idx,
+            server_name,
+        })
+    }
+}
+
+/// Checks whether a string is a valid room alias ID, without constructing a `RoomAliasId`.
+///
+/// This performs the same checks as `RoomAliasId::try_from`.
+pub fn is_valid_room_alias_id(room_alias_id: &str) -> bool {
+    validation::validate(room_alias_id).is_ok()
+}
+
+impl FromStr for RoomAliasId {
+    type Err = Error;
+
+    /// Attempts to create a new Matrix room alias ID from a string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::convert::TryFrom;
 
     use serde_json::{from_str, to_string};
 
-    use super::RoomAliasId;
-    use crate::error::Error;
+    use super::{is_valid_room_alias_id, RoomAliasId};
+    use crate::{error::ErrorKind, Host};
 
     #[test]
     fn valid_room_alias_id() {
@@ -118,6 +297,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn room_alias_id_from_owned_string() {
+        let raw_id = "#ruma:example.com".to_string();
+        let raw_ptr = raw_id.as_ptr();
+        let room_alias_id = RoomAliasId::try_from(raw_id).expect("Failed to create RoomAliasId.");
+
+        assert_eq!(room_alias_id.as_str().as_ptr(), raw_ptr);
+        assert_eq!(
+            RoomAliasId::try_from("#ro om:example.com".to_string())
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidCharacters
+        );
+    }
+
+    #[test]
+    fn parse_valid_room_alias_id() {
+        assert_eq!(
+            "#ruma:example.com"
+                .parse::<RoomAliasId>()
+                .expect("Failed to parse RoomAliasId."),
+            RoomAliasId::try_from("#ruma:example.com").expect("Failed to create RoomAliasId.")
+        );
+    }
+
+    #[test]
+    fn parse_invalid_room_alias_id() {
+        assert_eq!(
+            "#ruma:/".parse::<RoomAliasId>().unwrap_err().kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+
     #[test]
     fn serialize_valid_room_alias_id() {
         assert_eq!(
@@ -141,7 +353,7 @@ mod tests {
     #[test]
     fn valid_room_alias_id_with_explicit_standard_port() {
         assert_eq!(
-            RoomAliasId::try_from("#ruma:example.com:443")
+            RoomAliasId::try_from("#ruma:example.com:8448")
                 .expect("Failed to create RoomAliasId.")
                 .to_string(),
             "#ruma:example.com"
@@ -158,6 +370,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn server_name_of_room_alias_id() {
+        assert_eq!(
+            RoomAliasId::try_from("#ruma:example.com:8448")
+                .expect("Failed to create RoomAliasId.")
+                .server_name(),
+            "example.com"
+        );
+        assert_eq!(
+            RoomAliasId::try_from("#ruma:example.com:5000")
+                .expect("Failed to create RoomAliasId.")
+                .server_name(),
+            "example.com:5000"
+        );
+    }
+
     #[test]
     fn valid_room_alias_id_unicode() {
         assert_eq!(
@@ -168,35 +396,304 @@ mod tests {
         );
     }
 
+    #[test]
+    fn valid_room_alias_id_unicode_host() {
+        let room_alias_id =
+            RoomAliasId::try_from("#老虎:café.example").expect("Failed to create RoomAliasId.");
+
+        assert_eq!(room_alias_id.alias(), "老虎");
+        assert_eq!(room_alias_id.to_string(), "#老虎:xn--caf-dma.example");
+    }
+
+    #[test]
+    fn missing_room_alias_id_alias() {
+        assert_eq!(
+            RoomAliasId::try_from("#:example.com").unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+    }
+
+    #[test]
+    fn invalid_characters_in_room_alias_id_alias() {
+        for id in &[
+            "#ro om:example.com",
+            "#ro\tom:example.com",
+            "#ro\nom:example.com",
+            "#ro\u{7f}om:example.com",
+        ] {
+            assert_eq!(
+                RoomAliasId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::InvalidCharacters
+            );
+            assert!(!is_valid_room_alias_id(id));
+        }
+    }
+
+    #[test]
+    fn invalid_characters_in_room_alias_id_from_parts() {
+        assert_eq!(
+            RoomAliasId::from_parts("ro om", "example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidCharacters
+        );
+        assert_eq!(
+            RoomAliasId::from_parts("ro:om", "example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidCharacters
+        );
+    }
+
     #[test]
     fn missing_room_alias_id_sigil() {
         assert_eq!(
-            RoomAliasId::try_from("39hvsi03hlne:example.com").unwrap_err(),
-            Error::MissingSigil
+            RoomAliasId::try_from("39hvsi03hlne:example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingSigil
         );
     }
 
     #[test]
     fn missing_room_alias_id_delimiter() {
         assert_eq!(
-            RoomAliasId::try_from("#ruma").unwrap_err(),
-            Error::MissingDelimiter
+            RoomAliasId::try_from("#ruma").unwrap_err().kind(),
+            ErrorKind::MissingDelimiter
         );
     }
 
     #[test]
     fn invalid_room_alias_id_host() {
         assert_eq!(
-            RoomAliasId::try_from("#ruma:/").unwrap_err(),
-            Error::InvalidHost
+            RoomAliasId::try_from("#ruma:/").unwrap_err().kind(),
+            ErrorKind::InvalidHost
         );
     }
 
     #[test]
     fn invalid_room_alias_id_port() {
+        for id in &[
+            "#ruma:example.com:notaport",
+            "#ruma:example.com:65536",
+            "#ruma:example.com:0",
+        ] {
+            assert_eq!(
+                RoomAliasId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::InvalidPort
+            );
+        }
+    }
+
+    #[test]
+    fn room_alias_ids_are_ordered_by_string_representation() {
+        let first =
+            RoomAliasId::try_from("#a:z.example.com").expect("Failed to create RoomAliasId.");
+        let second =
+            RoomAliasId::try_from("#b:a.example.com").expect("Failed to create RoomAliasId.");
+
+        assert!(first < second);
+    }
+
+    #[test]
+    fn room_alias_id_preserving_port() {
+        for id in &[
+            "#ruma:example.com",
+            "#ruma:example.com:8448",
+            "#ruma:example.com:5000",
+        ] {
+            assert_eq!(
+                RoomAliasId::try_from(*id)
+                    .expect("Failed to create RoomAliasId.")
+                    .to_string_preserving_port(),
+                *id
+            );
+        }
+    }
+
+    #[test]
+    fn is_valid_room_alias_id_matches_try_from() {
+        assert!(is_valid_room_alias_id("#ruma:example.com"));
+        assert!(!is_valid_room_alias_id("!ruma:example.com"));
+        assert!(!is_valid_room_alias_id("#ruma"));
+        assert!(!is_valid_room_alias_id("#ruma:"));
+    }
+
+    #[test]
+    fn room_alias_id_from_parts() {
+        assert_eq!(
+            RoomAliasId::from_parts("ruma", "example.com").expect("Failed to create RoomAliasId."),
+            RoomAliasId::try_from("#ruma:example.com").expect("Failed to create RoomAliasId.")
+        );
+    }
+
+    #[test]
+    fn room_alias_id_is_ip_literal() {
+        assert!(!RoomAliasId::try_from("#ruma:example.com")
+            .expect("Failed to create RoomAliasId.")
+            .is_ip_literal());
+        assert!(RoomAliasId::try_from("#ruma:1.2.3.4:5000")
+            .expect("Failed to create RoomAliasId.")
+            .is_ip_literal());
+        assert!(RoomAliasId::try_from("#ruma:[::1]")
+            .expect("Failed to create RoomAliasId.")
+            .is_ip_literal());
+    }
+
+    #[test]
+    fn room_alias_id_parts() {
+        assert_eq!(
+            RoomAliasId::try_from("#ruma:example.com:8448")
+                .expect("Failed to create RoomAliasId.")
+                .parts(),
+            ("ruma", "example.com".to_string())
+        );
+        assert_eq!(
+            RoomAliasId::try_from("#ruma:example.com:5000")
+                .expect("Failed to create RoomAliasId.")
+                .parts(),
+            ("ruma", "example.com:5000".to_string())
+        );
+    }
+
+    #[test]
+    fn room_alias_id_with_surrounding_whitespace() {
+        for id in &[
+            " #ruma:example.com",
+            "#ruma:example.com ",
+            "\t#ruma:example.com",
+            "#ruma:example.com\t",
+        ] {
+            assert_eq!(
+                RoomAliasId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::SurroundingWhitespace
+            );
+            assert_eq!(
+                RoomAliasId::try_from(id.to_string()).unwrap_err().kind(),
+                ErrorKind::SurroundingWhitespace
+            );
+        }
+    }
+
+    #[test]
+    fn room_alias_id_with_server_name() {
+        let id = RoomAliasId::try_from("#ruma:example.com").expect("Failed to create RoomAliasId.");
+        let moved = id
+            .with_server_name("example.org:5000")
+            .expect("Failed to create RoomAliasId.");
+
+        assert_eq!(moved.to_string(), "#ruma:example.org:5000");
+        assert_eq!(moved.parts(), ("ruma", "example.org:5000".to_string()));
+        assert_eq!(id.to_string(), "#ruma:example.com");
+        assert_eq!(
+            id.with_server_name("exa mple.org").unwrap_err().kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+
+    #[test]
+    fn room_alias_id_host_str() {
+        assert_eq!(
+            RoomAliasId::try_from("#ruma:Example.com")
+                .expect("Failed to create RoomAliasId.")
+                .host_str(),
+            "example.com"
+        );
+        assert_eq!(
+            RoomAliasId::try_from("#ruma:example.com:5000")
+                .expect("Failed to create RoomAliasId.")
+                .host_str(),
+            "example.com"
+        );
+        assert_eq!(
+            RoomAliasId::try_from("#ruma:1.2.3.4:8448")
+                .expect("Failed to create RoomAliasId.")
+                .host_str(),
+            "1.2.3.4"
+        );
+        assert_eq!(
+            RoomAliasId::try_from("#ruma:[::1]")
+                .expect("Failed to create RoomAliasId.")
+                .host_str(),
+            "[::1]"
+        );
+        assert_eq!(
+            RoomAliasId::try_from("#ruma:[0:0::1]:5000")
+                .expect("Failed to create RoomAliasId.")
+                .host_str(),
+            "[::1]"
+        );
+    }
+
+    #[test]
+    fn room_alias_id_from_host() {
+        assert_eq!(
+            RoomAliasId::from_host("ruma", Host::Domain("example.com".to_string()), 5000)
+                .expect("Failed to create RoomAliasId."),
+            "#ruma:example.com:5000"
+        );
         assert_eq!(
-            RoomAliasId::try_from("#ruma:example.com:notaport").unwrap_err(),
-            Error::InvalidHost
+            RoomAliasId::from_host("ru ma", Host::Domain("example.com".to_string()), 5000)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidCharacters
         );
     }
+
+    #[test]
+    fn sigil_only_room_alias_id() {
+        assert_eq!(
+            RoomAliasId::try_from("#").unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+        assert_eq!(
+            RoomAliasId::try_from("#".to_string()).unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+        assert!(!is_valid_room_alias_id("#"));
+    }
+
+    #[test]
+    fn room_alias_id_with_leading_multibyte_character() {
+        for id in &["é:example.com", "老虎:example.com", "éa:example.com"] {
+            assert_eq!(
+                RoomAliasId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::MissingSigil
+            );
+            assert_eq!(
+                RoomAliasId::try_from(id.to_string()).unwrap_err().kind(),
+                ErrorKind::MissingSigil
+            );
+            assert!(!is_valid_room_alias_id(id));
+        }
+    }
+
+    #[test]
+    fn room_alias_id_into_string() {
+        let id =
+            RoomAliasId::try_from("#ruma:example.com:8448").expect("Failed to create RoomAliasId.");
+        let string = id.to_string();
+
+        assert_eq!(id.into_string(), string);
+    }
+
+    #[test]
+    fn deserialize_invalid_room_alias_id_error_message() {
+        assert_eq!(
+            from_str::<RoomAliasId>(r##""#ruma""##)
+                .unwrap_err()
+                .to_string(),
+            "invalid Matrix ID: colon is required between localpart and server name: \"#ruma\", \
+             expected a Matrix room alias ID as a string at line 1 column 7"
+        );
+    }
+
+    #[test]
+    fn room_alias_id_localpart() {
+        let room_alias_id =
+            RoomAliasId::try_from("#ruma:example.com:5000").expect("Failed to create RoomAliasId.");
+
+        assert_eq!(room_alias_id.localpart(), "ruma");
+        assert_eq!(room_alias_id.localpart(), room_alias_id.alias().as_str());
+    }
 }
@@ -1,22 +1,49 @@
 //! Matrix room identifiers.
 
-use std::{
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::{
+    cmp::Ordering,
     convert::TryFrom,
     fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
 };
+#[cfg(feature = "std")]
+use std::time::SystemTime;
 
 #[cfg(feature = "diesel")]
 use diesel::sql_types::Text;
+#[cfg(feature = "std")]
+use rand::thread_rng;
+use rand::Rng;
+use ruma_identifiers_validation::room_id as validation;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use url::Host;
 
-use crate::{deserialize_id, display, error::Error, generate_localpart, parse_id};
+#[cfg(feature = "std")]
+use crate::alphabet::generate_sortable_localpart;
+use crate::{
+    alphabet::Alphabet, canonical_host, deserialize_id, error::Error, format_id,
+    generate_id_localpart, host::Host, id_from_host, id_from_parts, parse_id, parse_owned_id,
+    server_name::ServerName, validate_opaque_id, SIGIL_BYTES,
+};
+
+/// The number of characters in the localpart of a generated room ID.
+const GENERATED_LENGTH: usize = 18;
 
 /// A Matrix room ID.
 ///
 /// A `RoomId` is generated randomly or converted from a string slice, and can be converted back
 /// into a string as needed.
 ///
+/// The localpart of a room ID is opaque. It may contain any printable character, including
+/// non-ASCII ones, except for colons, whitespace and control characters.
+///
+/// Besides its components, a `RoomId` stores its canonical string representation. This takes up
+/// roughly twice the memory, but allows it to be borrowed as a `&str`, e.g. to look it up in a
+/// `HashMap` or `HashSet` with a string slice.
+///
 /// ```
 /// # use std::convert::TryFrom;
 /// # use ruma_identifiers::RoomId;
@@ -25,56 +52,240 @@ use crate::{deserialize_id, display, error::Error, generate_localpart, parse_id}
 ///     "!n8f893n9:example.com"
 /// );
 /// ```
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "diesel", derive(FromSqlRow, QueryId, AsExpression, SqlType))]
 #[cfg_attr(feature = "diesel", sql_type = "Text")]
 pub struct RoomId {
-    /// The hostname of the homeserver.
-    hostname: Host,
-    /// The room's unique ID.
-    localpart: String,
-    /// The network port of the homeserver.
-    port: u16,
+    /// The room ID in its canonical string representation.
+    full_id: String,
+    /// The index of the colon separating the localpart and the server name in `full_id`.
+    colon_idx: usize,
+    /// The server name of the homeserver.
+    server_name: ServerName,
 }
 
 impl RoomId {
     /// Attempts to generate a `RoomId` for the given origin server with a localpart consisting of
-    /// 18 random ASCII characters.
+    /// 18 random ASCII letters and digits.
     ///
     /// Fails if the given homeserver cannot be parsed as a valid host.
+    #[cfg(feature = "std")]
     pub fn new(homeserver_host: &str) -> Result<Self, Error> {
-        let room_id = format!("!{}:{}", generate_localpart(18), homeserver_host);
-        let (localpart, host, port) = parse_id('!', &room_id)?;
+        Self::new_with_length(homeserver_host, GENERATED_LENGTH)
+    }
+
+    /// Attempts to generate a `RoomId` for the given origin server with a localpart consisting of
+    /// `length` random ASCII letters and digits.
+    ///
+    /// Fails if the given homeserver cannot be parsed as a valid host, if `length` is zero, or if
+    /// the room ID would exceed 255 bytes.
+    #[cfg(feature = "std")]
+    pub fn new_with_length(homeserver_host: &str, length: usize) -> Result<Self, Error> {
+        Self::generate(
+            &mut thread_rng(),
+            homeserver_host,
+            length,
+            Alphabet::Alphanumeric,
+        )
+    }
+
+    /// Attempts to generate a `RoomId` for the given origin server with a localpart consisting of
+    /// 18 random ASCII letters and digits drawn from the given random number generator.
+    ///
+    /// With a seeded generator, this produces reproducible room IDs, e.g. for tests. Unless the
+    /// generator is cryptographically secure, the room IDs are predictable.
+    ///
+    /// Fails if the given homeserver cannot be parsed as a valid host.
+    pub fn new_with_rng(homeserver_host: &str, rng: &mut impl Rng) -> Result<Self, Error> {
+        Self::generate(
+            rng,
+            homeserver_host,
+            GENERATED_LENGTH,
+            Alphabet::Alphanumeric,
+        )
+    }
+
+    /// Attempts to generate a `RoomId` for the given origin server with a localpart consisting of
+    /// 18 random characters from the given alphabet, e.g. to match the other identifiers of the
+    /// homeserver. `new` is equivalent to passing `Alphabet::Alphanumeric`.
+    ///
+    /// Fails if the homeserver cannot be parsed as a valid host.
+    #[cfg(feature = "std")]
+    pub fn new_with_alphabet(homeserver_host: &str, alphabet: Alphabet) -> Result<Self, Error> {
+        Self::generate(
+            &mut thread_rng(),
+            homeserver_host,
+            GENERATED_LENGTH,
+            alphabet,
+        )
+    }
+
+    /// Attempts to generate a `RoomId` for the given origin server whose localpart starts with
+    /// the current time, so that room IDs generated later sort after earlier ones.
+    ///
+    /// The localpart consists of 10 characters encoding the milliseconds since the Unix epoch,
+    /// followed by 16 random characters, both from `Alphabet::Base32Unambiguous`. Room IDs
+    /// generated within the same millisecond are ordered randomly. This is merely a convenience
+    /// for servers that want room IDs to sort roughly chronologically, e.g. in logs and
+    /// databases; the Matrix specification doesn't require any particular format of localparts,
+    /// and other servers' room IDs can't be assumed to be sortable.
+    ///
+    /// Fails if the homeserver cannot be parsed as a valid host.
+    #[cfg(feature = "std")]
+    pub fn new_sortable(homeserver_host: &str) -> Result<Self, Error> {
+        let localpart = generate_sortable_localpart(&mut thread_rng(), SystemTime::now());
+
+        Self::from_parts(&localpart, homeserver_host).map_err(|err| err.with_input(homeserver_host))
+    }
+
+    /// Generates a `RoomId` with a random localpart of the given length.
+    fn generate(
+        rng: &mut impl Rng,
+        homeserver_host: &str,
+        length: usize,
+        alphabet: Alphabet,
+    ) -> Result<Self, Error> {
+        let localpart = generate_id_localpart(rng, length, alphabet, homeserver_host)?;
+
+        Self::from_parts(&localpart, homeserver_host).map_err(|err| err.with_input(homeserver_host))
+    }
+
+    /// Creates a `RoomId` from its localpart and the server name of the originating homeserver.
+    ///
+    /// The localpart is validated like when parsing a string, so it must be non-empty and must not
+    /// contain colons, whitespace or control characters. The server name is validated like a
+    /// `ServerName`.
+    pub fn from_parts(localpart: &str, server_name: &str) -> Result<Self, Error> {
+        let (full_id, colon_idx, server_name) =
+            id_from_parts('!', localpart, server_name, validate_opaque_id)?;
 
         Ok(Self {
-            hostname: host,
-            localpart: localpart.to_string(),
-            port,
+            full_id,
+            colon_idx,
+            server_name,
         })
     }
 
+    /// Creates a `RoomId` from its localpart and an already parsed host and port of the
+    /// originating homeserver.
+    ///
+    /// The localpart is validated like in `from_parts`. Of the host and port, only the checks of
+    /// `ServerName::from_host` apply.
+    pub fn from_host(localpart: &str, host: Host, port: u16) -> Result<Self, Error> {
+        let (full_id, colon_idx, server_name) =
+            id_from_host('!', localpart, host, port, validate_opaque_id)?;
+
+        Ok(Self {
+            full_id,
+            colon_idx,
+            server_name,
+        })
+    }
+
+    /// Creates a copy of the room ID with the same localpart, but the given server name.
+    ///
+    /// Fails if the server name is invalid.
+    pub fn with_server_name(&self, server_name: &str) -> Result<Self, Error> {
+        Self::from_parts(self.localpart(), server_name)
+    }
+
     /// Returns a `Host` for the room ID, containing the server name (minus the port) of the
     /// originating homeserver.
     ///
     /// The host can be either a domain name, an IPv4 address, or an IPv6 address.
     pub fn hostname(&self) -> &Host {
-        &self.hostname
+        self.server_name.host()
+    }
+
+    /// Whether the server name of the room ID is an IPv4 or IPv6 address rather than a domain
+    /// name.
+    pub fn is_ip_literal(&self) -> bool {
+        self.server_name.is_ip_literal()
+    }
+
+    /// Returns the host of the room ID's server name as a string slice, exactly as it appears in
+    /// the canonical string representation, i.e. without the port. IPv6 addresses are enclosed in
+    /// brackets.
+    pub fn host_str(&self) -> &str {
+        canonical_host(&self.full_id[self.colon_idx + 1..])
+    }
+
+    /// Returns the room ID as a string slice.
+    ///
+    /// The canonical string representation is stored when the room ID is created, so this doesn't
+    /// allocate, returns the same string as `to_string`, and can be called repeatedly for free.
+    pub fn as_str(&self) -> &str {
+        &self.full_id
+    }
+
+    /// Converts the room ID into its string representation, without allocating.
+    ///
+    /// This returns the same string as `as_str` and `to_string`, but takes the stored string
+    /// instead of copying it.
+    pub fn into_string(self) -> String {
+        self.full_id
     }
 
     /// Returns the rooms's unique ID.
     pub fn localpart(&self) -> &str {
-        &self.localpart
+        &self.full_id[SIGIL_BYTES..self.colon_idx]
     }
 
     /// Returns the port the originating homeserver can be accessed on.
     pub fn port(&self) -> u16 {
-        self.port
+        self.server_name.port()
+    }
+
+    /// Returns the port of the originating homeserver if it was given explicitly, even if it is
+    /// `DEFAULT_PORT`, or `None` if the room ID doesn't specify one.
+    pub fn explicit_port(&self) -> Option<u16> {
+        self.server_name.explicit_port()
+    }
+
+    /// Returns the server name of the originating homeserver, i.e. its hostname followed by the
+    /// port if it isn't `DEFAULT_PORT`.
+    pub fn server_name(&self) -> String {
+        self.server_name.to_string()
+    }
+
+    /// Returns the unique ID and the server name of the room ID in one call.
+    ///
+    /// Like `server_name`, the server name omits the port if it is `DEFAULT_PORT`.
+    pub fn parts(&self) -> (&str, String) {
+        (self.localpart(), self.server_name())
+    }
+
+    /// Returns the string representation of the ID, including the port if it was given explicitly,
+    /// even if it is the default port.
+    ///
+    /// Only the port is preserved, the rest of the ID is canonical. See
+    /// `ServerName::to_string_preserving_port`.
+    pub fn to_string_preserving_port(&self) -> String {
+        format!(
+            "!{}:{}",
+            self.localpart(),
+            self.server_name.to_string_preserving_port()
+        )
     }
 }
 
 impl Display for RoomId {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        display(f, '!', &self.localpart, &self.hostname, self.port)
+        f.write_str(&self.full_id)
+    }
+}
+
+impl PartialOrd for RoomId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Room IDs are ordered lexicographically by their string representation, not by
+/// their individual components.
+impl Ord for RoomId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
     }
 }
 
@@ -83,7 +294,7 @@ impl Serialize for RoomId {
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        serializer.serialize_str(self.as_str())
     }
 }
 
@@ -104,24 +315,65 @@ impl TryFrom<&str> for RoomId {
     /// The string must include the leading ! sigil, the localpart, a literal colon, and a valid
     /// server name.
     fn try_from(room_id: &str) -> Result<Self, Error> {
-        let (localpart, host, port) = parse_id('!', room_id)?;
+        let (localpart, server_name) = parse_id('!', room_id)?;
+        validate_opaque_id(localpart).map_err(|kind| Error::new(kind, room_id))?;
+
+        let (full_id, colon_idx) = format_id('!', localpart, &server_name);
+
+        Ok(Self {
+            full_id,
+            colon_idx,
+            server_name,
+        })
+    }
+}
+
+impl TryFrom<String> for RoomId {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix room ID from an owned string representation.
+    ///
+    /// This performs the same checks as the `TryFrom<&str>` implementation, but reuses the string
+    /// if it already is the canonical string representation of the room ID.
+    fn try_from(room_id: String) -> Result<Self, Error> {
+        let (full_id, colon_idx, server_name, ()) =
+            parse_owned_id('!', room_id, validate_opaque_id)?;
 
         Ok(Self {
-            hostname: host,
-            localpart: localpart.to_owned(),
-            port,
+            full_id,
+            colon_idx,
+            server_name,
         })
     }
 }
 
+/// Checks whether a string is a valid room ID, without constructing a `RoomId`.
+///
+/// This performs the same checks as `RoomId::try_from`.
+pub fn is_valid_room_id(room_id: &str) -> bool {
+    validation::validate(room_id).is_ok()
+}
+
+impl FromStr for RoomId {
+    type Err = Error;
+
+    /// Attempts to create a new Matrix room ID from a string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::convert::TryFrom;
+    use std::{convert::TryFrom, net::Ipv6Addr, thread::sleep, time::Duration};
 
+    use rand::{rngs::StdRng, SeedableRng};
     use serde_json::{from_str, to_string};
 
-    use super::RoomId;
-    use crate::error::Error;
+    use super::{is_valid_room_id, RoomId};
+    use crate::{error::ErrorKind, Alphabet, Host};
 
     #[test]
     fn valid_room_id() {
@@ -133,6 +385,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_valid_room_id() {
+        assert_eq!(
+            "!29fhd83h92h0:example.com"
+                .parse::<RoomId>()
+                .expect("Failed to parse RoomId."),
+            RoomId::try_from("!29fhd83h92h0:example.com").expect("Failed to create RoomId.")
+        );
+    }
+
+    #[test]
+    fn parse_invalid_room_id() {
+        assert_eq!(
+            "!29fhd83h92h0".parse::<RoomId>().unwrap_err().kind(),
+            ErrorKind::MissingDelimiter
+        );
+    }
+
+    #[test]
+    fn room_id_from_owned_string() {
+        let raw_id = "!29fhd83h92h0:example.com".to_string();
+        let raw_ptr = raw_id.as_ptr();
+        let room_id = RoomId::try_from(raw_id).expect("Failed to create RoomId.");
+
+        assert_eq!(room_id.as_str(), "!29fhd83h92h0:example.com");
+        assert_eq!(room_id.as_str().as_ptr(), raw_ptr);
+        assert_eq!(
+            RoomId::try_from("!29fhd83h92h0:example.com:8448".to_string())
+                .expect("Failed to create RoomId.")
+                .as_str(),
+            "!29fhd83h92h0:example.com"
+        );
+        assert_eq!(
+            RoomId::try_from("29fhd83h92h0:example.com".to_string())
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingSigil
+        );
+    }
+
     #[test]
     fn generate_random_valid_room_id() {
         let room_id = RoomId::new("example.com")
@@ -143,11 +435,59 @@ mod tests {
         assert_eq!(room_id.len(), 31);
     }
 
+    #[test]
+    fn generate_seeded_room_id() {
+        let first = RoomId::new_with_rng("example.com", &mut StdRng::seed_from_u64(42))
+            .expect("Failed to generate RoomId.");
+        let second = RoomId::new_with_rng("example.com", &mut StdRng::seed_from_u64(42))
+            .expect("Failed to generate RoomId.");
+        let other = RoomId::new_with_rng("example.com", &mut StdRng::seed_from_u64(43))
+            .expect("Failed to generate RoomId.");
+
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+        assert!(RoomId::try_from(first.as_str()).is_ok());
+    }
+
     #[test]
     fn generate_random_invalid_room_id() {
         assert!(RoomId::new("").is_err());
     }
 
+    #[test]
+    fn generate_random_room_id_with_length() {
+        let room_id =
+            RoomId::new_with_length("example.com", 32).expect("Failed to generate RoomId.");
+
+        assert_eq!(room_id.localpart().len(), 32);
+        assert!(room_id
+            .localpart()
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric()));
+    }
+
+    #[test]
+    fn generate_random_room_id_with_invalid_length() {
+        assert_eq!(
+            RoomId::new_with_length("example.com", 0)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingLocalpart
+        );
+        assert_eq!(
+            RoomId::new_with_length("example.com", 250)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+        assert_eq!(
+            RoomId::new_with_length("example.com", usize::MAX)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+    }
+
     #[test]
     fn serialize_valid_room_id() {
         assert_eq!(
@@ -168,10 +508,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn valid_room_id_with_ipv6_host() {
+        let room_id =
+            RoomId::try_from("!room:[2001:db8::1]:8448").expect("Failed to create RoomId.");
+
+        assert_eq!(room_id.localpart(), "room");
+        assert_eq!(
+            room_id.hostname(),
+            &Host::<String>::Ipv6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1))
+        );
+        assert_eq!(room_id.port(), 8448);
+        assert_eq!(room_id.to_string(), "!room:[2001:db8::1]");
+        assert_eq!(
+            room_id.to_string_preserving_port(),
+            "!room:[2001:db8::1]:8448"
+        );
+    }
+
+    #[test]
+    fn valid_room_id_with_ipv6_host_and_non_standard_port() {
+        let room_id =
+            RoomId::try_from("!room:[2001:db8::1]:5000").expect("Failed to create RoomId.");
+
+        assert_eq!(room_id.port(), 5000);
+        assert_eq!(room_id.to_string(), "!room:[2001:db8::1]:5000");
+    }
+
     #[test]
     fn valid_room_id_with_explicit_standard_port() {
         assert_eq!(
-            RoomId::try_from("!29fhd83h92h0:example.com:443")
+            RoomId::try_from("!29fhd83h92h0:example.com:8448")
                 .expect("Failed to create RoomId.")
                 .to_string(),
             "!29fhd83h92h0:example.com"
@@ -188,35 +555,456 @@ mod tests {
         );
     }
 
+    #[test]
+    fn server_name_of_room_id() {
+        assert_eq!(
+            RoomId::try_from("!29fhd83h92h0:example.com:8448")
+                .expect("Failed to create RoomId.")
+                .server_name(),
+            "example.com"
+        );
+        assert_eq!(
+            RoomId::try_from("!29fhd83h92h0:example.com:5000")
+                .expect("Failed to create RoomId.")
+                .server_name(),
+            "example.com:5000"
+        );
+    }
+
+    #[test]
+    fn valid_room_id_with_multibyte_localpart() {
+        assert_eq!(
+            RoomId::try_from("!老虎Â£я:example.com")
+                .expect("Failed to create RoomId.")
+                .localpart(),
+            "老虎Â£я"
+        );
+    }
+
+    #[test]
+    fn missing_room_id_localpart() {
+        assert_eq!(
+            RoomId::try_from("!:example.com").unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+    }
+
     #[test]
     fn missing_room_id_sigil() {
         assert_eq!(
-            RoomId::try_from("carl:example.com").unwrap_err(),
-            Error::MissingSigil
+            RoomId::try_from("carl:example.com").unwrap_err().kind(),
+            ErrorKind::MissingSigil
         );
     }
 
     #[test]
     fn missing_room_id_delimiter() {
         assert_eq!(
-            RoomId::try_from("!29fhd83h92h0").unwrap_err(),
-            Error::MissingDelimiter
+            RoomId::try_from("!29fhd83h92h0").unwrap_err().kind(),
+            ErrorKind::MissingDelimiter
         );
     }
 
     #[test]
     fn invalid_room_id_host() {
         assert_eq!(
-            RoomId::try_from("!29fhd83h92h0:/").unwrap_err(),
-            Error::InvalidHost
+            RoomId::try_from("!29fhd83h92h0:/").unwrap_err().kind(),
+            ErrorKind::InvalidHost
         );
     }
 
     #[test]
     fn invalid_room_id_port() {
+        for id in &[
+            "!29fhd83h92h0:example.com:notaport",
+            "!29fhd83h92h0:example.com:65536",
+            "!29fhd83h92h0:example.com:0",
+        ] {
+            assert_eq!(
+                RoomId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::InvalidPort
+            );
+        }
+    }
+
+    #[test]
+    fn room_ids_are_ordered_by_string_representation() {
+        let first = RoomId::try_from("!a:z.example.com").expect("Failed to create RoomId.");
+        let second = RoomId::try_from("!b:a.example.com").expect("Failed to create RoomId.");
+
+        assert!(first < second);
+    }
+
+    #[test]
+    fn room_id_preserving_port() {
+        for id in &[
+            "!29fhd83h92h0:example.com",
+            "!29fhd83h92h0:example.com:8448",
+            "!29fhd83h92h0:example.com:5000",
+        ] {
+            assert_eq!(
+                RoomId::try_from(*id)
+                    .expect("Failed to create RoomId.")
+                    .to_string_preserving_port(),
+                *id
+            );
+        }
+    }
+
+    #[test]
+    fn is_valid_room_id_matches_try_from() {
+        assert!(is_valid_room_id("!29fhd83h92h0:example.com"));
+        assert!(is_valid_room_id("!29fhd83h92h0:example.com:5000"));
+        assert!(!is_valid_room_id("!29fhd83h92h0:example.com:notaport"));
+        assert!(!is_valid_room_id("29fhd83h92h0:example.com"));
+        assert!(!is_valid_room_id("!:example.com"));
+    }
+
+    #[test]
+    fn room_id_from_parts() {
+        assert_eq!(
+            RoomId::from_parts("29fhd83h92h0", "example.com").expect("Failed to create RoomId."),
+            RoomId::try_from("!29fhd83h92h0:example.com").expect("Failed to create RoomId.")
+        );
+    }
+
+    #[test]
+    fn invalid_room_id_from_parts() {
         assert_eq!(
-            RoomId::try_from("!29fhd83h92h0:example.com:notaport").unwrap_err(),
-            Error::InvalidHost
+            RoomId::from_parts("", "example.com").unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
         );
+        assert_eq!(
+            RoomId::from_parts("29fhd83h92h0:", "example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidCharacters
+        );
+        assert_eq!(
+            RoomId::from_parts("29fhd83h92h0", "").unwrap_err().kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+
+    #[test]
+    fn room_id_is_ip_literal() {
+        assert!(!RoomId::try_from("!29fhd83h92h0:example.com")
+            .expect("Failed to create RoomId.")
+            .is_ip_literal());
+        assert!(RoomId::try_from("!29fhd83h92h0:1.2.3.4:5000")
+            .expect("Failed to create RoomId.")
+            .is_ip_literal());
+        assert!(RoomId::try_from("!29fhd83h92h0:[::1]")
+            .expect("Failed to create RoomId.")
+            .is_ip_literal());
+    }
+
+    #[test]
+    fn room_id_parts() {
+        assert_eq!(
+            RoomId::try_from("!29fhd83h92h0:example.com:8448")
+                .expect("Failed to create RoomId.")
+                .parts(),
+            ("29fhd83h92h0", "example.com".to_string())
+        );
+        assert_eq!(
+            RoomId::try_from("!29fhd83h92h0:example.com:5000")
+                .expect("Failed to create RoomId.")
+                .parts(),
+            ("29fhd83h92h0", "example.com:5000".to_string())
+        );
+    }
+
+    #[test]
+    fn room_id_with_surrounding_whitespace() {
+        for id in &[
+            " !29fhd83h92h0:example.com",
+            "!29fhd83h92h0:example.com ",
+            "\t!29fhd83h92h0:example.com",
+            "!29fhd83h92h0:example.com\t",
+        ] {
+            assert_eq!(
+                RoomId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::SurroundingWhitespace
+            );
+            assert_eq!(
+                RoomId::try_from(id.to_string()).unwrap_err().kind(),
+                ErrorKind::SurroundingWhitespace
+            );
+        }
+    }
+
+    #[test]
+    fn room_id_with_server_name() {
+        let id = RoomId::try_from("!29fhd83h92h0:example.com").expect("Failed to create RoomId.");
+        let moved = id
+            .with_server_name("example.org:5000")
+            .expect("Failed to create RoomId.");
+
+        assert_eq!(moved.to_string(), "!29fhd83h92h0:example.org:5000");
+        assert_eq!(
+            moved.parts(),
+            ("29fhd83h92h0", "example.org:5000".to_string())
+        );
+        assert_eq!(id.to_string(), "!29fhd83h92h0:example.com");
+        assert_eq!(
+            id.with_server_name("exa mple.org").unwrap_err().kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+
+    #[test]
+    fn room_id_host_str() {
+        assert_eq!(
+            RoomId::try_from("!29fhd83h92h0:Example.com")
+                .expect("Failed to create RoomId.")
+                .host_str(),
+            "example.com"
+        );
+        assert_eq!(
+            RoomId::try_from("!29fhd83h92h0:example.com:5000")
+                .expect("Failed to create RoomId.")
+                .host_str(),
+            "example.com"
+        );
+        assert_eq!(
+            RoomId::try_from("!29fhd83h92h0:1.2.3.4:8448")
+                .expect("Failed to create RoomId.")
+                .host_str(),
+            "1.2.3.4"
+        );
+        assert_eq!(
+            RoomId::try_from("!29fhd83h92h0:[::1]")
+                .expect("Failed to create RoomId.")
+                .host_str(),
+            "[::1]"
+        );
+        assert_eq!(
+            RoomId::try_from("!29fhd83h92h0:[0:0::1]:5000")
+                .expect("Failed to create RoomId.")
+                .host_str(),
+            "[::1]"
+        );
+    }
+
+    #[test]
+    fn room_id_from_host() {
+        assert_eq!(
+            RoomId::from_host("29fhd83h92h0", Host::Ipv6(Ipv6Addr::LOCALHOST), 5000)
+                .expect("Failed to create RoomId."),
+            "!29fhd83h92h0:[::1]:5000"
+        );
+        assert_eq!(
+            RoomId::from_host(
+                "29fhd83h92h0",
+                Host::Domain("example.com".to_string()),
+                8448
+            )
+            .expect("Failed to create RoomId."),
+            RoomId::try_from("!29fhd83h92h0:example.com").expect("Failed to create RoomId.")
+        );
+        assert_eq!(
+            RoomId::from_host("", Host::Domain("example.com".to_string()), 8448)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingLocalpart
+        );
+        assert_eq!(
+            RoomId::from_host("29fhd83h92h0", Host::Domain("example.com".to_string()), 0)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidPort
+        );
+    }
+
+    #[test]
+    fn generate_random_room_id_with_alphabet() {
+        for &(alphabet, chars) in &[
+            (
+                Alphabet::LowercaseAlphanumeric,
+                "abcdefghijklmnopqrstuvwxyz0123456789",
+            ),
+            (
+                Alphabet::Base32Unambiguous,
+                "0123456789abcdefghjkmnpqrstvwxyz",
+            ),
+        ] {
+            let generated_id = RoomId::new_with_alphabet("example.com", alphabet)
+                .expect("Failed to generate RoomId.");
+            let id_str = generated_id.to_string();
+
+            assert!(id_str.ends_with(":example.com"));
+            assert_eq!(id_str.len(), 31);
+            assert!(generated_id.localpart().chars().all(|c| chars.contains(c)));
+            assert_eq!(
+                RoomId::try_from(id_str.as_str()).expect("Failed to create RoomId."),
+                generated_id
+            );
+        }
+    }
+
+    #[test]
+    fn generate_sortable_room_id() {
+        let first = RoomId::new_sortable("example.com").expect("Failed to generate RoomId.");
+        sleep(Duration::from_millis(2));
+        let second = RoomId::new_sortable("example.com").expect("Failed to generate RoomId.");
+
+        assert_eq!(first.localpart().len(), 26);
+        assert!(second > first);
+        assert_eq!(
+            RoomId::try_from(second.as_str()).expect("Failed to create RoomId."),
+            second
+        );
+    }
+
+    #[test]
+    fn generate_sortable_room_id_with_invalid_homeserver() {
+        assert!(RoomId::new_sortable("").is_err());
+    }
+
+    #[test]
+    fn room_id_with_internationalized_server_name() {
+        assert_eq!(
+            RoomId::try_from("!29fhd83h92h0:café.example").expect("Failed to create RoomId."),
+            RoomId::try_from("!29fhd83h92h0:xn--caf-dma.example")
+                .expect("Failed to create RoomId.")
+        );
+    }
+
+    #[test]
+    fn room_id_exceeding_maximum_length_after_punycode_encoding() {
+        // `é.example` is encoded as `xn--9ca.example`, which is five bytes longer.
+        let room_id = format!("!{}:é.example", "a".repeat(240));
+        assert_eq!(room_id.len(), 252);
+
+        assert_eq!(
+            RoomId::try_from(room_id.as_str()).unwrap_err().kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+        assert_eq!(
+            RoomId::try_from(room_id.clone()).unwrap_err().kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+        assert!(!is_valid_room_id(&room_id));
+
+        let room_id = RoomId::try_from(format!("!{}:é.example", "a".repeat(238)))
+            .expect("Failed to create RoomId.");
+
+        assert_eq!(
+            room_id.as_str(),
+            format!("!{}:xn--9ca.example", "a".repeat(238))
+        );
+        assert_eq!(room_id.as_str().len(), 255);
+        assert_eq!(
+            RoomId::try_from(room_id.as_str()).expect("Failed to create RoomId."),
+            room_id
+        );
+    }
+
+    #[test]
+    fn sigil_only_room_id() {
+        assert_eq!(
+            RoomId::try_from("!").unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+        assert_eq!(
+            RoomId::try_from("!".to_string()).unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+        assert!(!is_valid_room_id("!"));
+    }
+
+    #[test]
+    fn room_id_with_leading_multibyte_character() {
+        for id in &["é:example.com", "老虎:example.com", "éa:example.com"] {
+            assert_eq!(
+                RoomId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::MissingSigil
+            );
+            assert_eq!(
+                RoomId::try_from(id.to_string()).unwrap_err().kind(),
+                ErrorKind::MissingSigil
+            );
+            assert!(!is_valid_room_id(id));
+        }
+    }
+
+    #[test]
+    fn room_id_into_string() {
+        let id =
+            RoomId::try_from("!29fhd83h92h0:example.com:8448").expect("Failed to create RoomId.");
+        let string = id.to_string();
+
+        assert_eq!(id.into_string(), string);
+    }
+
+    #[test]
+    fn deserialize_invalid_room_id_error_message() {
+        assert_eq!(
+            from_str::<RoomId>(r#""!29fhd83h92h0:example.com:notaport""#)
+                .unwrap_err()
+                .to_string(),
+            "invalid Matrix ID: port of the server name is not a valid number: \
+             \"!29fhd83h92h0:example.com:notaport\", expected a Matrix room ID as a string at \
+             line 1 column 36"
+        );
+    }
+
+    #[test]
+    fn invalid_characters_in_room_id_localpart() {
+        for room_id in &[
+            "!bad room:example.com",
+            "!bad\troom:example.com",
+            "!bad\u{0}room:example.com",
+            "!bad\nroom:example.com",
+        ] {
+            assert_eq!(
+                RoomId::try_from(*room_id).unwrap_err().kind(),
+                ErrorKind::InvalidCharacters
+            );
+            assert_eq!(
+                RoomId::try_from(room_id.to_string()).unwrap_err().kind(),
+                ErrorKind::InvalidCharacters
+            );
+            assert!(!is_valid_room_id(room_id));
+        }
+    }
+
+    #[test]
+    fn room_id_from_parts_with_invalid_characters() {
+        for localpart in &["bad room", "bad\troom", "bad room\n", "bad\u{7f}"] {
+            assert_eq!(
+                RoomId::from_parts(localpart, "example.com")
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::InvalidCharacters
+            );
+            assert_eq!(
+                RoomId::from_host(localpart, Host::Domain("example.com".to_string()), 5000)
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::InvalidCharacters
+            );
+        }
+    }
+
+    #[test]
+    fn room_id_from_parts_round_trip() {
+        for localpart in &["29fhd83h92h0", "老虎", "a-b_c.d!"] {
+            let room_id =
+                RoomId::from_parts(localpart, "example.com").expect("Failed to create RoomId.");
+
+            assert_eq!(
+                RoomId::try_from(room_id.as_str()).expect("Failed to create RoomId."),
+                room_id
+            );
+            assert_eq!(
+                room_id
+                    .with_server_name("example.org:5000")
+                    .expect("Failed to create RoomId.")
+                    .localpart(),
+                *localpart
+            );
+        }
     }
 }
@@ -1,8 +1,11 @@
 //! Matrix identifiers for places where a room ID or room alias ID are used interchangeably.
 
-use std::{
+use alloc::string::{String, ToString};
+use core::{
+    cmp::Ordering,
     convert::TryFrom,
     fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
 };
 
 #[cfg(feature = "diesel")]
@@ -10,7 +13,12 @@ use diesel::sql_types::Text;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::{
-    deserialize_id, display, error::Error, room_alias_id::RoomAliasId, room_id::RoomId, validate_id,
+    deserialize_id,
+    error::{Error, ErrorKind},
+    is_sigil_only,
+    room_alias_id::{is_valid_room_alias_id, RoomAliasId},
+    room_id::{is_valid_room_id, RoomId},
+    validate_id,
 };
 
 /// A Matrix room ID or a Matrix room alias ID.
@@ -42,38 +50,74 @@ pub enum RoomIdOrAliasId {
     RoomId(RoomId),
 }
 
+impl RoomIdOrAliasId {
+    /// Whether or not this is a room ID.
+    pub fn is_room_id(&self) -> bool {
+        match self {
+            RoomIdOrAliasId::RoomId(_) => true,
+            RoomIdOrAliasId::RoomAliasId(_) => false,
+        }
+    }
+
+    /// Whether or not this is a room alias ID.
+    pub fn is_room_alias_id(&self) -> bool {
+        !self.is_room_id()
+    }
+
+    /// Returns the room ID or room alias ID as a string slice.
+    pub fn as_str(&self) -> &str {
+        match self {
+            RoomIdOrAliasId::RoomAliasId(room_alias_id) => room_alias_id.as_str(),
+            RoomIdOrAliasId::RoomId(room_id) => room_id.as_str(),
+        }
+    }
+
+    /// Returns the sigil of the identifier, i.e. `RoomId::SIGIL` or `RoomAliasId::SIGIL`.
+    pub fn sigil(&self) -> char {
+        match self {
+            RoomIdOrAliasId::RoomAliasId(room_alias_id) => room_alias_id.sigil(),
+            RoomIdOrAliasId::RoomId(room_id) => room_id.sigil(),
+        }
+    }
+
+    /// Converts the room ID or room alias ID into its string representation, without allocating.
+    pub fn into_string(self) -> String {
+        match self {
+            RoomIdOrAliasId::RoomAliasId(room_alias_id) => room_alias_id.into_string(),
+            RoomIdOrAliasId::RoomId(room_id) => room_id.into_string(),
+        }
+    }
+}
+
 impl Display for RoomIdOrAliasId {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         match *self {
-            RoomIdOrAliasId::RoomAliasId(ref room_alias_id) => display(
-                f,
-                '#',
-                room_alias_id.alias(),
-                room_alias_id.hostname(),
-                room_alias_id.port(),
-            ),
-            RoomIdOrAliasId::RoomId(ref room_id) => display(
-                f,
-                '!',
-                room_id.localpart(),
-                room_id.hostname(),
-                room_id.port(),
-            ),
+            RoomIdOrAliasId::RoomAliasId(ref room_alias_id) => room_alias_id.fmt(f),
+            RoomIdOrAliasId::RoomId(ref room_id) => room_id.fmt(f),
         }
     }
 }
 
+impl PartialOrd for RoomIdOrAliasId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Room IDs and room alias IDs are ordered lexicographically by their string representation, not
+/// by their individual components. This means all room IDs sort before all room alias IDs.
+impl Ord for RoomIdOrAliasId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_string().cmp(&other.to_string())
+    }
+}
+
 impl Serialize for RoomIdOrAliasId {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        match *self {
-            RoomIdOrAliasId::RoomAliasId(ref room_alias_id) => {
-                serializer.serialize_str(&room_alias_id.to_string())
-            }
-            RoomIdOrAliasId::RoomId(ref room_id) => serializer.serialize_str(&room_id.to_string()),
-        }
+        serializer.serialize_str(self.as_str())
     }
 }
 
@@ -89,6 +133,36 @@ impl<'de> Deserialize<'de> for RoomIdOrAliasId {
     }
 }
 
+/// Parses a borrowed or owned room ID or room alias ID, depending on its leading sigil.
+fn parse_room_id_or_alias_id<S>(room_id_or_alias_id: S) -> Result<RoomIdOrAliasId, Error>
+where
+    S: AsRef<str>,
+    RoomAliasId: TryFrom<S, Error = Error>,
+    RoomId: TryFrom<S, Error = Error>,
+{
+    let raw_id = room_id_or_alias_id.as_ref();
+
+    if is_sigil_only('#', raw_id) || is_sigil_only('!', raw_id) {
+        return Err(Error::new(ErrorKind::MissingLocalpart, raw_id));
+    }
+
+    validate_id(raw_id).map_err(|kind| Error::new(kind, raw_id))?;
+
+    let sigil = raw_id.chars().next().expect("ID missing first character.");
+
+    match sigil {
+        '#' => {
+            let room_alias_id = RoomAliasId::try_from(room_id_or_alias_id)?;
+            Ok(RoomIdOrAliasId::RoomAliasId(room_alias_id))
+        }
+        '!' => {
+            let room_id = RoomId::try_from(room_id_or_alias_id)?;
+            Ok(RoomIdOrAliasId::RoomId(room_id))
+        }
+        _ => Err(Error::new(ErrorKind::MissingSigil, raw_id)),
+    }
+}
+
 impl TryFrom<&str> for RoomIdOrAliasId {
     type Error = Error;
 
@@ -98,23 +172,39 @@ impl TryFrom<&str> for RoomIdOrAliasId {
     /// valid homeserver host or include the leading # sigil, the alias, a literal colon, and a
     /// valid homeserver host.
     fn try_from(room_id_or_alias_id: &str) -> Result<Self, Error> {
-        validate_id(room_id_or_alias_id)?;
-
-        let mut chars = room_id_or_alias_id.chars();
-
-        let sigil = chars.nth(0).expect("ID missing first character.");
-
-        match sigil {
-            '#' => {
-                let room_alias_id = RoomAliasId::try_from(room_id_or_alias_id)?;
-                Ok(RoomIdOrAliasId::RoomAliasId(room_alias_id))
-            }
-            '!' => {
-                let room_id = RoomId::try_from(room_id_or_alias_id)?;
-                Ok(RoomIdOrAliasId::RoomId(room_id))
-            }
-            _ => Err(Error::MissingSigil),
-        }
+        parse_room_id_or_alias_id(room_id_or_alias_id)
+    }
+}
+
+impl TryFrom<String> for RoomIdOrAliasId {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix room ID or a room alias ID from an owned string
+    /// representation.
+    ///
+    /// This performs the same checks as the `TryFrom<&str>` implementation, but reuses the string
+    /// if it already is the canonical string representation of the ID.
+    fn try_from(room_id_or_alias_id: String) -> Result<Self, Error> {
+        parse_room_id_or_alias_id(room_id_or_alias_id)
+    }
+}
+
+/// Checks whether a string is a valid room ID or room alias ID, without constructing a
+/// `RoomIdOrAliasId`.
+///
+/// This performs the same checks as `RoomIdOrAliasId::try_from`.
+pub fn is_valid_room_id_or_alias_id(room_id_or_alias_id: &str) -> bool {
+    is_valid_room_id(room_id_or_alias_id) || is_valid_room_alias_id(room_id_or_alias_id)
+}
+
+impl FromStr for RoomIdOrAliasId {
+    type Err = Error;
+
+    /// Attempts to create a new Matrix room ID or a room alias ID from a string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
     }
 }
 
@@ -124,8 +214,8 @@ mod tests {
 
     use serde_json::{from_str, to_string};
 
-    use super::RoomIdOrAliasId;
-    use crate::error::Error;
+    use super::{is_valid_room_id_or_alias_id, RoomIdOrAliasId};
+    use crate::error::ErrorKind;
 
     #[test]
     fn valid_room_id_or_alias_id_with_a_room_alias_id() {
@@ -147,11 +237,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_valid_room_id_or_alias_id() {
+        assert_eq!(
+            "#ruma:example.com"
+                .parse::<RoomIdOrAliasId>()
+                .expect("Failed to parse RoomIdOrAliasId."),
+            RoomIdOrAliasId::try_from("#ruma:example.com").expect("Failed to create RoomAliasId.")
+        );
+        assert_eq!(
+            "!29fhd83h92h0:example.com"
+                .parse::<RoomIdOrAliasId>()
+                .expect("Failed to parse RoomIdOrAliasId."),
+            RoomIdOrAliasId::try_from("!29fhd83h92h0:example.com")
+                .expect("Failed to create RoomId.")
+        );
+    }
+
+    #[test]
+    fn missing_localpart_for_room_id_or_alias_id() {
+        assert_eq!(
+            RoomIdOrAliasId::try_from("#:example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingLocalpart
+        );
+        assert_eq!(
+            RoomIdOrAliasId::try_from("!:example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingLocalpart
+        );
+    }
+
+    #[test]
+    fn predicate_methods() {
+        let room_alias_id =
+            RoomIdOrAliasId::try_from("#ruma:example.com").expect("Failed to create RoomAliasId.");
+        let room_id = RoomIdOrAliasId::try_from("!29fhd83h92h0:example.com")
+            .expect("Failed to create RoomId.");
+
+        assert!(room_alias_id.is_room_alias_id());
+        assert!(!room_alias_id.is_room_id());
+        assert!(room_id.is_room_id());
+        assert!(!room_id.is_room_alias_id());
+    }
+
     #[test]
     fn missing_sigil_for_room_id_or_alias_id() {
         assert_eq!(
-            RoomIdOrAliasId::try_from("ruma:example.com").unwrap_err(),
-            Error::MissingSigil
+            RoomIdOrAliasId::try_from("ruma:example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingSigil
         );
     }
 
@@ -197,4 +335,63 @@ mod tests {
                 .expect("Failed to create RoomAliasId.")
         );
     }
+
+    #[test]
+    fn room_ids_are_ordered_before_room_alias_ids() {
+        let room_alias_id =
+            RoomIdOrAliasId::try_from("#ruma:example.com").expect("Failed to create RoomAliasId.");
+        let room_id = RoomIdOrAliasId::try_from("!29fhd83h92h0:example.com")
+            .expect("Failed to create RoomId.");
+
+        assert!(room_id < room_alias_id);
+    }
+
+    #[test]
+    fn is_valid_room_id_or_alias_id_matches_try_from() {
+        assert!(is_valid_room_id_or_alias_id("#ruma:example.com"));
+        assert!(is_valid_room_id_or_alias_id("!29fhd83h92h0:example.com"));
+        assert!(!is_valid_room_id_or_alias_id("@carl:example.com"));
+        assert!(!is_valid_room_id_or_alias_id("#ruma"));
+    }
+
+    #[test]
+    fn room_id_or_alias_id_with_surrounding_whitespace() {
+        for id in &[
+            " #ruma:example.com",
+            "#ruma:example.com ",
+            "\t#ruma:example.com",
+            "#ruma:example.com\t",
+        ] {
+            assert_eq!(
+                RoomIdOrAliasId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::SurroundingWhitespace
+            );
+            assert_eq!(
+                RoomIdOrAliasId::try_from(id.to_string())
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::SurroundingWhitespace
+            );
+        }
+    }
+
+    #[test]
+    fn sigil_only_room_id_or_alias_id() {
+        for id in &["#", "!"] {
+            assert_eq!(
+                RoomIdOrAliasId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::MissingLocalpart
+            );
+        }
+    }
+
+    #[test]
+    fn room_id_or_alias_id_into_string() {
+        for id in &["#ruma:example.com:8448", "!29fhd83h92h0:example.com:8448"] {
+            let id = RoomIdOrAliasId::try_from(*id).expect("Failed to create RoomIdOrAliasId.");
+            let string = id.to_string();
+
+            assert_eq!(id.into_string(), string);
+        }
+    }
 }
@@ -1,24 +1,56 @@
 //! Matrix room version identifiers.
 
-use std::{
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+};
+use core::{
+    cmp::Ordering,
     convert::TryFrom,
     fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
 };
 
 #[cfg(feature = "diesel")]
 use diesel::sql_types::Text;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::{
+    de::{self, Unexpected, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 
-use crate::{deserialize_id, error::Error};
+use crate::{
+    error::{Error, ErrorKind},
+    event_id::EventIdFormat,
+    invalid_id,
+};
 
 /// Room version identifiers cannot be more than 32 code points.
-const MAX_CODE_POINTS: usize = 32;
+pub(crate) const MAX_CODE_POINTS: usize = 32;
+
+/// The official room versions specified by the Matrix protocol, in ascending order.
+static OFFICIAL_VERSIONS: [InnerRoomVersionId; 11] = [
+    InnerRoomVersionId::Version1,
+    InnerRoomVersionId::Version2,
+    InnerRoomVersionId::Version3,
+    InnerRoomVersionId::Version4,
+    InnerRoomVersionId::Version5,
+    InnerRoomVersionId::Version6,
+    InnerRoomVersionId::Version7,
+    InnerRoomVersionId::Version8,
+    InnerRoomVersionId::Version9,
+    InnerRoomVersionId::Version10,
+    InnerRoomVersionId::Version11,
+];
 
 /// A Matrix room version ID.
 ///
 /// A `RoomVersionId` can be or converted or deserialized from a string slice, and can be converted
 /// or serialized back into a string as needed.
 ///
+/// Room versions are strings, but some servers send them as numbers, like `"room_version": 5`. For
+/// interoperability, deserialization also accepts integers and converts them to their string form,
+/// so `5` is deserialized as room version `"5"`. Serialization always produces a string.
+///
 /// ```
 /// # use std::convert::TryFrom;
 /// # use ruma_identifiers::RoomVersionId;
@@ -48,6 +80,24 @@ enum InnerRoomVersionId {
     /// A version 5 room.
     Version5,
 
+    /// A version 6 room.
+    Version6,
+
+    /// A version 7 room.
+    Version7,
+
+    /// A version 8 room.
+    Version8,
+
+    /// A version 9 room.
+    Version9,
+
+    /// A version 10 room.
+    Version10,
+
+    /// A version 11 room.
+    Version11,
+
     /// A custom room version.
     Custom(String),
 }
@@ -78,11 +128,90 @@ impl RoomVersionId {
         Self(InnerRoomVersionId::Version5)
     }
 
+    /// Creates a version 6 room ID.
+    pub fn version_6() -> Self {
+        Self(InnerRoomVersionId::Version6)
+    }
+
+    /// Creates a version 7 room ID.
+    pub fn version_7() -> Self {
+        Self(InnerRoomVersionId::Version7)
+    }
+
+    /// Creates a version 8 room ID.
+    pub fn version_8() -> Self {
+        Self(InnerRoomVersionId::Version8)
+    }
+
+    /// Creates a version 9 room ID.
+    pub fn version_9() -> Self {
+        Self(InnerRoomVersionId::Version9)
+    }
+
+    /// Creates a version 10 room ID.
+    pub fn version_10() -> Self {
+        Self(InnerRoomVersionId::Version10)
+    }
+
+    /// Creates a version 11 room ID.
+    pub fn version_11() -> Self {
+        Self(InnerRoomVersionId::Version11)
+    }
+
     /// Creates a custom room version ID from the given string slice.
+    ///
+    /// Unlike `TryFrom`, this doesn't check the length or characters of the string.
     pub fn custom(id: &str) -> Self {
         Self(InnerRoomVersionId::Custom(id.to_string()))
     }
 
+    /// Returns an iterator over all official room versions specified by the Matrix protocol, in
+    /// ascending order.
+    pub fn official_versions() -> impl Iterator<Item = Self> {
+        OFFICIAL_VERSIONS.iter().cloned().map(Self)
+    }
+
+    /// Returns the room version ID as a string slice.
+    ///
+    /// For official room versions, this is a static string, so it doesn't allocate.
+    pub fn as_str(&self) -> &str {
+        match self.0 {
+            InnerRoomVersionId::Custom(ref version) => version,
+            ref official => official_str(official),
+        }
+    }
+
+    /// Returns the room version ID as a string that can outlive it.
+    ///
+    /// For official room versions, this is a borrowed static string, so it doesn't allocate. Custom
+    /// room versions are copied into an owned string; use `into_cow` to avoid that if the
+    /// `RoomVersionId` isn't needed anymore, or `as_str` if a borrowed string is enough.
+    pub fn as_cow(&self) -> Cow<'static, str> {
+        match self.0 {
+            InnerRoomVersionId::Custom(ref version) => Cow::Owned(version.clone()),
+            ref official => Cow::Borrowed(official_str(official)),
+        }
+    }
+
+    /// Converts the room version ID into its string representation.
+    ///
+    /// Custom room versions are moved out without allocating, official ones are copied from a
+    /// static string.
+    pub fn into_string(self) -> String {
+        self.into_cow().into_owned()
+    }
+
+    /// Converts the room version ID into a string, without allocating.
+    ///
+    /// For official room versions, this is a borrowed static string. For custom room versions,
+    /// the string the `RoomVersionId` holds is returned.
+    pub fn into_cow(self) -> Cow<'static, str> {
+        match self.0 {
+            InnerRoomVersionId::Custom(version) => Cow::Owned(version),
+            ref official => Cow::Borrowed(official_str(official)),
+        }
+    }
+
     /// Whether or not this room version is an official one specified by the Matrix protocol.
     pub fn is_official(&self) -> bool {
         !self.is_custom()
@@ -90,9 +219,32 @@ impl RoomVersionId {
 
     /// Whether or not this is a custom room version.
     pub fn is_custom(&self) -> bool {
+        matches!(self.0, InnerRoomVersionId::Custom(_))
+    }
+
+    /// Whether or not this room version is stable.
+    ///
+    /// All official room versions are currently specified as stable. Custom room versions are
+    /// considered unstable, as the specification recommends for versions it doesn't define.
+    pub fn is_stable(&self) -> bool {
+        self.is_official()
+    }
+
+    /// Whether or not this room version is experimental, i.e. not stable.
+    pub fn is_experimental(&self) -> bool {
+        !self.is_stable()
+    }
+
+    /// Returns the format of the event IDs in rooms of this version, or `None` for custom room
+    /// versions, whose event ID format isn't known.
+    pub fn event_id_format(&self) -> Option<EventIdFormat> {
         match self.0 {
-            InnerRoomVersionId::Custom(_) => true,
-            _ => false,
+            InnerRoomVersionId::Version1 | InnerRoomVersionId::Version2 => {
+                Some(EventIdFormat::Original)
+            }
+            InnerRoomVersionId::Version3 => Some(EventIdFormat::Base64),
+            InnerRoomVersionId::Custom(_) => None,
+            _ => Some(EventIdFormat::UrlSafeBase64),
         }
     }
 
@@ -120,29 +272,99 @@ impl RoomVersionId {
     pub fn is_version_5(&self) -> bool {
         self.0 == InnerRoomVersionId::Version5
     }
+
+    /// Whether or not this is a version 6 room.
+    pub fn is_version_6(&self) -> bool {
+        self.0 == InnerRoomVersionId::Version6
+    }
+
+    /// Whether or not this is a version 7 room.
+    pub fn is_version_7(&self) -> bool {
+        self.0 == InnerRoomVersionId::Version7
+    }
+
+    /// Whether or not this is a version 8 room.
+    pub fn is_version_8(&self) -> bool {
+        self.0 == InnerRoomVersionId::Version8
+    }
+
+    /// Whether or not this is a version 9 room.
+    pub fn is_version_9(&self) -> bool {
+        self.0 == InnerRoomVersionId::Version9
+    }
+
+    /// Whether or not this is a version 10 room.
+    pub fn is_version_10(&self) -> bool {
+        self.0 == InnerRoomVersionId::Version10
+    }
+
+    /// Whether or not this is a version 11 room.
+    pub fn is_version_11(&self) -> bool {
+        self.0 == InnerRoomVersionId::Version11
+    }
+}
+
+/// Returns the string representation of an official room version.
+///
+/// Panics if given a custom room version, which has no static string representation.
+fn official_str(version: &InnerRoomVersionId) -> &'static str {
+    match version {
+        InnerRoomVersionId::Version1 => "1",
+        InnerRoomVersionId::Version2 => "2",
+        InnerRoomVersionId::Version3 => "3",
+        InnerRoomVersionId::Version4 => "4",
+        InnerRoomVersionId::Version5 => "5",
+        InnerRoomVersionId::Version6 => "6",
+        InnerRoomVersionId::Version7 => "7",
+        InnerRoomVersionId::Version8 => "8",
+        InnerRoomVersionId::Version9 => "9",
+        InnerRoomVersionId::Version10 => "10",
+        InnerRoomVersionId::Version11 => "11",
+        InnerRoomVersionId::Custom(_) => panic!("Custom room version has no static string."),
+    }
 }
 
 impl Display for RoomVersionId {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        let message = match self.0 {
-            InnerRoomVersionId::Version1 => "1",
-            InnerRoomVersionId::Version2 => "2",
-            InnerRoomVersionId::Version3 => "3",
-            InnerRoomVersionId::Version4 => "4",
-            InnerRoomVersionId::Version5 => "5",
-            InnerRoomVersionId::Custom(ref version) => version,
-        };
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialOrd for RoomVersionId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
 
-        write!(f, "{}", message)
+/// Official room versions are ordered numerically, so e.g. version 2 comes before version 10.
+/// Custom room versions come after all official ones, and are ordered lexicographically by their
+/// string representation among themselves.
+impl Ord for RoomVersionId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (&self.0, &other.0) {
+            (InnerRoomVersionId::Custom(this), InnerRoomVersionId::Custom(other)) => {
+                this.cmp(other)
+            }
+            (InnerRoomVersionId::Custom(_), _) => Ordering::Greater,
+            (_, InnerRoomVersionId::Custom(_)) => Ordering::Less,
+            (this, other) => official_index(this).cmp(&official_index(other)),
+        }
     }
 }
 
+/// Returns the position of an official room version in `OFFICIAL_VERSIONS`.
+fn official_index(version: &InnerRoomVersionId) -> Option<usize> {
+    OFFICIAL_VERSIONS
+        .iter()
+        .position(|official| official == version)
+}
+
 impl Serialize for RoomVersionId {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        serializer.serialize_str(self.as_str())
     }
 }
 
@@ -151,44 +373,147 @@ impl<'de> Deserialize<'de> for RoomVersionId {
     where
         D: Deserializer<'de>,
     {
-        deserialize_id(deserializer, "a Matrix room version ID as a string")
+        // Integers are only accepted from self-describing formats like JSON. Binary formats like
+        // bincode don't support `deserialize_any`, and only ever contain the serialized string.
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(RoomVersionIdVisitor)
+        } else {
+            deserializer.deserialize_str(RoomVersionIdVisitor)
+        }
+    }
+}
+
+/// A serde visitor for room version IDs, which accepts integers in addition to strings.
+struct RoomVersionIdVisitor;
+
+impl<'de> Visitor<'de> for RoomVersionIdVisitor {
+    type Value = RoomVersionId;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str("a Matrix room version ID as a string or an integer")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        RoomVersionId::try_from(v).map_err(|err| invalid_id(err, &self))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        RoomVersionId::try_from(v).map_err(|err| invalid_id(err, &self))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_string(v.to_string())
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_string(v.to_string())
+    }
+
+    /// Floats are only accepted if they are integral, like `5.0` from formats that don't
+    /// distinguish integers from floats.
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        // Within this range, every integral float can be represented exactly as an `i64`. The
+        // float methods for checking this directly, like `fract`, aren't available without `std`.
+        const LIMIT: f64 = 9_007_199_254_740_992.0;
+        if -LIMIT < v && v < LIMIT && v as i64 as f64 == v {
+            self.visit_i64(v as i64)
+        } else {
+            Err(E::invalid_value(Unexpected::Float(v), &self))
+        }
     }
 }
 
+/// Parses a borrowed or owned room version ID, only allocating if it is a valid custom room
+/// version ID and borrowed.
+fn parse_room_version_id<S>(room_version_id: S) -> Result<RoomVersionId, Error>
+where
+    S: AsRef<str> + Into<String>,
+{
+    let version = match room_version_id.as_ref() {
+        "1" => RoomVersionId(InnerRoomVersionId::Version1),
+        "2" => RoomVersionId(InnerRoomVersionId::Version2),
+        "3" => RoomVersionId(InnerRoomVersionId::Version3),
+        "4" => RoomVersionId(InnerRoomVersionId::Version4),
+        "5" => RoomVersionId(InnerRoomVersionId::Version5),
+        "6" => RoomVersionId(InnerRoomVersionId::Version6),
+        "7" => RoomVersionId(InnerRoomVersionId::Version7),
+        "8" => RoomVersionId(InnerRoomVersionId::Version8),
+        "9" => RoomVersionId(InnerRoomVersionId::Version9),
+        "10" => RoomVersionId(InnerRoomVersionId::Version10),
+        "11" => RoomVersionId(InnerRoomVersionId::Version11),
+        custom => {
+            if custom.is_empty() {
+                return Err(Error::new(ErrorKind::MinimumLengthNotSatisfied, custom));
+            } else if custom.chars().count() > MAX_CODE_POINTS {
+                return Err(Error::new(ErrorKind::MaximumLengthExceeded, custom));
+            } else if custom.chars().any(|c| c.is_whitespace() || c.is_control()) {
+                // Room versions end up in canonical JSON that gets signed, so only printable
+                // characters are allowed.
+                return Err(Error::new(ErrorKind::InvalidCharacters, custom));
+            } else {
+                RoomVersionId(InnerRoomVersionId::Custom(room_version_id.into()))
+            }
+        }
+    };
+
+    Ok(version)
+}
+
 impl TryFrom<&str> for RoomVersionId {
     type Error = Error;
 
     /// Attempts to create a new Matrix room version ID from a string representation.
     fn try_from(room_version_id: &str) -> Result<Self, Error> {
-        let version = match room_version_id {
-            "1" => Self(InnerRoomVersionId::Version1),
-            "2" => Self(InnerRoomVersionId::Version2),
-            "3" => Self(InnerRoomVersionId::Version3),
-            "4" => Self(InnerRoomVersionId::Version4),
-            "5" => Self(InnerRoomVersionId::Version5),
-            custom => {
-                if custom.is_empty() {
-                    return Err(Error::MinimumLengthNotSatisfied);
-                } else if custom.chars().count() > MAX_CODE_POINTS {
-                    return Err(Error::MaximumLengthExceeded);
-                } else {
-                    Self(InnerRoomVersionId::Custom(custom.to_string()))
-                }
-            }
-        };
+        parse_room_version_id(room_version_id)
+    }
+}
+
+impl TryFrom<String> for RoomVersionId {
+    type Error = Error;
 
-        Ok(version)
+    /// Attempts to create a new Matrix room version ID from an owned string representation.
+    ///
+    /// This performs the same checks as the `TryFrom<&str>` implementation, but reuses the string
+    /// for custom room version IDs.
+    fn try_from(room_version_id: String) -> Result<Self, Error> {
+        parse_room_version_id(room_version_id)
+    }
+}
+
+impl FromStr for RoomVersionId {
+    type Err = Error;
+
+    /// Attempts to create a new Matrix room version ID from a string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::convert::TryFrom;
+    use std::{borrow::Cow, convert::TryFrom};
 
     use serde_json::{from_str, to_string};
 
     use super::RoomVersionId;
-    use crate::error::Error;
+    use crate::{error::ErrorKind, EventIdFormat};
 
     #[test]
     fn valid_version_1_room_version_id() {
@@ -240,6 +565,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn valid_version_6_to_11_room_version_ids() {
+        let versions = [
+            ("6", RoomVersionId::version_6()),
+            ("7", RoomVersionId::version_7()),
+            ("8", RoomVersionId::version_8()),
+            ("9", RoomVersionId::version_9()),
+            ("10", RoomVersionId::version_10()),
+            ("11", RoomVersionId::version_11()),
+        ];
+
+        for (string, version) in &versions {
+            let parsed = RoomVersionId::try_from(*string).expect("Failed to create RoomVersionId.");
+
+            assert_eq!(&parsed, version);
+            assert_eq!(parsed.to_string(), *string);
+            assert!(parsed.is_official());
+        }
+
+        assert!(RoomVersionId::try_from("9")
+            .expect("Failed to create RoomVersionId.")
+            .is_version_9());
+        assert!(!RoomVersionId::version_10().is_version_11());
+    }
+
     #[test]
     fn valid_custom_room_version_id() {
         assert_eq!(
@@ -250,22 +600,108 @@ mod tests {
         );
     }
 
+    #[test]
+    fn room_version_id_from_owned_string() {
+        assert_eq!(
+            RoomVersionId::try_from("5".to_string()).expect("Failed to create RoomVersionId."),
+            RoomVersionId::version_5()
+        );
+        assert_eq!(
+            RoomVersionId::try_from("io.ruma.1".to_string())
+                .expect("Failed to create RoomVersionId.")
+                .to_string(),
+            "io.ruma.1"
+        );
+        assert_eq!(
+            RoomVersionId::try_from(String::new()).unwrap_err().kind(),
+            ErrorKind::MinimumLengthNotSatisfied
+        );
+    }
+
+    #[test]
+    fn parse_room_version_id() {
+        assert!("1"
+            .parse::<RoomVersionId>()
+            .expect("Failed to parse RoomVersionId.")
+            .is_version_1());
+        assert!("io.ruma.1"
+            .parse::<RoomVersionId>()
+            .expect("Failed to parse RoomVersionId.")
+            .is_custom());
+        assert_eq!(
+            "".parse::<RoomVersionId>().unwrap_err().kind(),
+            ErrorKind::MinimumLengthNotSatisfied
+        );
+    }
+
     #[test]
     fn empty_room_version_id() {
         assert_eq!(
-            RoomVersionId::try_from(""),
-            Err(Error::MinimumLengthNotSatisfied)
+            RoomVersionId::try_from("").unwrap_err().kind(),
+            ErrorKind::MinimumLengthNotSatisfied
         );
     }
 
     #[test]
     fn over_max_code_point_room_version_id() {
         assert_eq!(
-            RoomVersionId::try_from("0123456789012345678901234567890123456789"),
-            Err(Error::MaximumLengthExceeded)
+            RoomVersionId::try_from("0123456789012345678901234567890123456789")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MaximumLengthExceeded
         );
     }
 
+    #[test]
+    fn room_version_id_with_invalid_characters() {
+        for version in &[
+            "io.ruma 1",
+            "io.ruma.1\n",
+            "\tio.ruma.1",
+            "io.\u{0}ruma",
+            "io.ruma\u{7f}",
+        ] {
+            assert_eq!(
+                RoomVersionId::try_from(*version).unwrap_err().kind(),
+                ErrorKind::InvalidCharacters
+            );
+        }
+    }
+
+    #[test]
+    fn as_str_matches_to_string() {
+        for version in RoomVersionId::official_versions()
+            .chain(vec![
+                RoomVersionId::try_from("io.ruma.1").expect("Failed to create RoomVersionId.")
+            ])
+        {
+            assert_eq!(version.as_str(), version.to_string());
+        }
+    }
+
+    #[test]
+    fn official_room_versions() {
+        let versions = RoomVersionId::official_versions().collect::<Vec<_>>();
+
+        assert_eq!(versions.len(), 11);
+        assert_eq!(versions[0], RoomVersionId::version_1());
+        assert_eq!(versions[10], RoomVersionId::version_11());
+        for (index, version) in versions.iter().enumerate() {
+            assert_eq!(version.to_string(), (index + 1).to_string());
+            assert!(version.is_official());
+            assert!(version.is_stable());
+            assert!(!version.is_experimental());
+        }
+    }
+
+    #[test]
+    fn custom_room_version_is_experimental() {
+        let version = RoomVersionId::custom("io.ruma.1");
+
+        assert!(!version.is_stable());
+        assert!(version.is_experimental());
+    }
+
     #[test]
     fn serialize_official_room_id() {
         assert_eq!(
@@ -364,4 +800,135 @@ mod tests {
         assert!(!custom.is_version_4());
         assert!(!custom.is_version_5());
     }
+
+    #[test]
+    fn official_room_version_ids_are_ordered_numerically() {
+        assert!(RoomVersionId::version_1() < RoomVersionId::version_2());
+        assert!(RoomVersionId::version_2() < RoomVersionId::version_10());
+        assert!(RoomVersionId::version_9() < RoomVersionId::version_11());
+        assert!(RoomVersionId::version_5() >= RoomVersionId::version_3());
+
+        let mut versions = RoomVersionId::official_versions().collect::<Vec<_>>();
+        versions.reverse();
+        versions.sort();
+        assert_eq!(
+            versions,
+            RoomVersionId::official_versions().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn custom_room_version_ids_are_ordered_after_official_ones() {
+        assert!(RoomVersionId::version_11() < RoomVersionId::custom("1.1"));
+        assert!(RoomVersionId::version_5() < RoomVersionId::custom("io.ruma.1"));
+        assert!(RoomVersionId::custom("io.ruma.1") < RoomVersionId::custom("io.ruma.2"));
+        assert!(RoomVersionId::custom("io.ruma.10") < RoomVersionId::custom("io.ruma.2"));
+    }
+
+    #[test]
+    fn deserialize_integer_room_version_id() {
+        assert_eq!(
+            from_str::<RoomVersionId>("5").expect("Failed to convert JSON to RoomVersionId."),
+            RoomVersionId::version_5()
+        );
+        assert_eq!(
+            from_str::<RoomVersionId>("5.0").expect("Failed to convert JSON to RoomVersionId."),
+            RoomVersionId::version_5()
+        );
+
+        let custom =
+            from_str::<RoomVersionId>("-1").expect("Failed to convert JSON to RoomVersionId.");
+        assert!(custom.is_custom());
+        assert_eq!(custom.as_str(), "-1");
+        assert_eq!(
+            to_string(&RoomVersionId::version_5())
+                .expect("Failed to convert RoomVersionId to JSON."),
+            r#""5""#
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid_room_version_id() {
+        assert!(from_str::<RoomVersionId>("5.5").is_err());
+        assert!(from_str::<RoomVersionId>("true").is_err());
+        assert!(from_str::<RoomVersionId>(r#""""#).is_err());
+
+        let error = from_str::<RoomVersionId>("1e100").unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("expected a Matrix room version ID as a string or an integer"));
+    }
+
+    #[test]
+    fn official_room_versions_as_borrowed_cow() {
+        for version in RoomVersionId::official_versions() {
+            match version.as_cow() {
+                Cow::Borrowed(borrowed) => assert_eq!(borrowed, version.as_str()),
+                Cow::Owned(_) => panic!("Official room version was allocated."),
+            }
+
+            let string = version.to_string();
+            match version.into_cow() {
+                Cow::Borrowed(borrowed) => assert_eq!(borrowed, string),
+                Cow::Owned(_) => panic!("Official room version was allocated."),
+            }
+        }
+    }
+
+    #[test]
+    fn custom_room_version_as_owned_cow() {
+        let version =
+            RoomVersionId::try_from("io.ruma.1").expect("Failed to create RoomVersionId.");
+
+        match version.as_cow() {
+            Cow::Owned(owned) => assert_eq!(owned, "io.ruma.1"),
+            Cow::Borrowed(_) => panic!("Custom room version was borrowed."),
+        }
+        assert_eq!(version.into_cow(), "io.ruma.1");
+    }
+
+    #[test]
+    fn room_version_id_into_string() {
+        for version in &["1", "11", "io.ruma.1"] {
+            let version =
+                RoomVersionId::try_from(*version).expect("Failed to create RoomVersionId.");
+            let string = version.to_string();
+
+            assert_eq!(version.into_string(), string);
+        }
+    }
+
+    #[test]
+    fn deserialize_invalid_room_version_id_error_message() {
+        assert_eq!(
+            from_str::<RoomVersionId>(r#""""#).unwrap_err().to_string(),
+            "invalid Matrix ID: ID must be at least 4 characters: \"\", expected a Matrix room \
+             version ID as a string or an integer at line 1 column 2"
+        );
+    }
+
+    #[test]
+    fn event_id_format_of_room_versions() {
+        assert_eq!(
+            RoomVersionId::version_1().event_id_format(),
+            Some(EventIdFormat::Original)
+        );
+        assert_eq!(
+            RoomVersionId::version_2().event_id_format(),
+            Some(EventIdFormat::Original)
+        );
+        assert_eq!(
+            RoomVersionId::version_3().event_id_format(),
+            Some(EventIdFormat::Base64)
+        );
+
+        for version in RoomVersionId::official_versions().skip(3) {
+            assert_eq!(
+                version.event_id_format(),
+                Some(EventIdFormat::UrlSafeBase64)
+            );
+        }
+
+        assert_eq!(RoomVersionId::custom("io.ruma.1").event_id_format(), None);
+    }
 }
@@ -0,0 +1,57 @@
+//! Implements `same_server` for identifiers that always contain a server name.
+
+macro_rules! same_server_impl {
+    ($name:ident) => {
+        impl $crate::$name {
+            /// Whether both IDs originate from the same homeserver, i.e. have the same host and
+            /// port, regardless of their localparts.
+            ///
+            /// Like the equality of server names, this treats an explicitly given `DEFAULT_PORT`
+            /// the same as an omitted port.
+            pub fn same_server(&self, other: &Self) -> bool {
+                self.hostname() == other.hostname() && self.port() == other.port()
+            }
+        }
+    };
+}
+
+same_server_impl!(GroupId);
+same_server_impl!(RoomAliasId);
+same_server_impl!(RoomId);
+same_server_impl!(UserId);
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::{RoomAliasId, RoomId, UserId};
+
+    fn user_id(id: &str) -> UserId {
+        UserId::try_from(id).expect("Failed to create UserId.")
+    }
+
+    #[test]
+    fn same_server_with_different_localparts() {
+        assert!(user_id("@carl:example.com").same_server(&user_id("@alice:example.com")));
+        assert!(user_id("@carl:example.com").same_server(&user_id("@alice:example.com:8448")));
+        assert!(user_id("@carl:127.0.0.1").same_server(&user_id("@alice:127.0.0.1")));
+        assert!(RoomId::try_from("!n8f893n9:example.com")
+            .expect("Failed to create RoomId.")
+            .same_server(
+                &RoomId::try_from("!29fhd83h:example.com").expect("Failed to create RoomId.")
+            ));
+    }
+
+    #[test]
+    fn same_server_with_different_servers() {
+        assert!(!user_id("@carl:example.com").same_server(&user_id("@carl:example.com:5000")));
+        assert!(!user_id("@carl:example.com:5000").same_server(&user_id("@carl:example.com:5001")));
+        assert!(!user_id("@carl:example.com").same_server(&user_id("@carl:example.org")));
+        assert!(!RoomAliasId::try_from("#ruma:example.com")
+            .expect("Failed to create RoomAliasId.")
+            .same_server(
+                &RoomAliasId::try_from("#ruma:sub.example.com")
+                    .expect("Failed to create RoomAliasId.")
+            ));
+    }
+}
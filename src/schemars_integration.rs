@@ -0,0 +1,81 @@
+//! Implements `JsonSchema` from schemars, describing identifiers as strings in generated schemas.
+//!
+//! The schemas of IDs with a server name don't have a `maxLength`: JSON Schema counts code points,
+//! but `MAX_BYTES` limits the bytes of the canonical form, in which non-ASCII domain names are
+//! punycode-encoded, so no code point limit matches it.
+
+use schemars::{
+    schema::{InstanceType, Metadata, Schema, SchemaObject, StringValidation},
+    JsonSchema, SchemaGenerator,
+};
+
+use crate::room_version_id::MAX_CODE_POINTS;
+
+/// Builds the schema of a string with the given pattern, maximum length in code points and
+/// description.
+fn string_schema(pattern: &str, max_length: Option<usize>, description: &str) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        string: Some(Box::new(StringValidation {
+            max_length: max_length.map(|max_length| max_length as u32),
+            min_length: Some(1),
+            pattern: Some(pattern.to_string()),
+        })),
+        metadata: Some(Box::new(Metadata {
+            description: Some(description.to_string()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+macro_rules! json_schema_impl {
+    ($name:ident, $pattern:expr, $max_length:expr, $description:expr) => {
+        impl JsonSchema for $crate::$name {
+            fn schema_name() -> String {
+                stringify!($name).to_string()
+            }
+
+            fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                string_schema($pattern, $max_length, $description)
+            }
+        }
+    };
+}
+
+json_schema_impl!(
+    EventId,
+    r"^\$([^:\s]+:\S+|[A-Za-z0-9+/_-]+)$",
+    None,
+    "A Matrix event ID: a `$` sigil followed either by an opaque ID, a colon and a server name \
+     (room versions 1 and 2), or by the reference hash of the event (later room versions)."
+);
+json_schema_impl!(
+    RoomAliasId,
+    r"^#[^:\s]+:\S+$",
+    None,
+    "A Matrix room alias ID: a `#` sigil followed by the alias, a colon and a server name, \
+     optionally with a port."
+);
+json_schema_impl!(
+    RoomId,
+    r"^![^:\s]+:\S+$",
+    None,
+    "A Matrix room ID: a `!` sigil followed by an opaque ID, a colon and a server name, \
+     optionally with a port."
+);
+json_schema_impl!(
+    UserId,
+    r"^@[!-9;-~]+:\S+$",
+    None,
+    "A Matrix user ID: an `@` sigil followed by the localpart, a colon and a server name, \
+     optionally with a port."
+);
+json_schema_impl!(
+    RoomVersionId,
+    r"^\S+$",
+    Some(MAX_CODE_POINTS),
+    "A Matrix room version ID, like `5`: either an official version or a custom one without \
+     whitespace."
+);
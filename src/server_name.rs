@@ -0,0 +1,979 @@
+//! Matrix server names.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+#[cfg(not(feature = "std"))]
+use core::net::{Ipv4Addr, Ipv6Addr};
+use core::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt::{Display, Error as FmtError, Formatter, Result as FmtResult, Write},
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+#[cfg(feature = "std")]
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use ruma_identifiers_validation::server_name as validation;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    deserialize_id,
+    error::{Error, ErrorKind},
+    federation_url::{borrow_host, federation_base_url},
+    host::Host,
+    is_forbidden_in_server_name, DEFAULT_PORT, MAX_DOMAIN_BYTES,
+};
+
+/// The maximum length of the string representation of a server name in bytes: a domain name of
+/// the maximum length, followed by a colon and a port of five digits.
+const MAX_SERVER_NAME_BYTES: usize = MAX_DOMAIN_BYTES + 6;
+
+/// A Matrix server name.
+///
+/// A server name is the hostname of a homeserver, optionally followed by a port. It is the part of
+/// an identifier that follows the first colon, but it is also used on its own, e.g. in the
+/// federation API. A `ServerName` is converted from a string slice, and can be converted back into
+/// a string as needed.
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use ruma_identifiers::ServerName;
+/// assert_eq!(
+///     ServerName::try_from("example.com:5000").unwrap().to_string(),
+///     "example.com:5000"
+/// );
+/// ```
+///
+/// A server name without a port refers to `DEFAULT_PORT`, the Matrix federation port 8448. Server
+/// names that only differ in whether that port is given explicitly are equal, and the port is
+/// omitted from the string representation unless it is a different one. Use
+/// `to_string_preserving_port` to get the server name with the port it was given with.
+///
+/// A domain name may be given fully qualified, i.e. with a trailing dot for the DNS root. The dot
+/// is removed, so `example.com.` and `example.com` are the same server name, and it is never part
+/// of the string representation. Only a single trailing dot is accepted; `.` on its own and
+/// `example.com..` are rejected.
+///
+/// Domain names are limited to what DNS can represent: at most 253 bytes in total and 63 bytes per
+/// label, measured in their ASCII form and without the trailing dot. Longer ones are rejected with
+/// `ErrorKind::InvalidHost`.
+///
+/// IPv6 addresses with a zone identifier, like `[fe80::1%25eth0]`, are rejected with
+/// `ErrorKind::InvalidHost`. A zone identifier names a network interface of the host it is
+/// assigned on, so it is meaningless to other servers.
+///
+/// Domain names are stored and displayed in their canonical ASCII form: they are lowercased, and
+/// internationalized domain names are encoded as punycode as described by IDNA. So the Unicode and
+/// punycode spellings of the same domain, e.g. `café.example` and `xn--caf-dma.example`, are the
+/// same server name, and either is displayed as `xn--caf-dma.example`. The punycode form can be
+/// longer than the Unicode one, and it is what the `MAX_BYTES` limit of identifiers applies to.
+///
+/// Since DNS names are case-insensitive, this lowercasing is guaranteed: server names and the IDs
+/// containing them that only differ in the case of their domain name, like `Example.COM` and
+/// `example.com`, are equal and hash identically. IP addresses are only written in their standard
+/// notation, which for IPv6 addresses uses lowercase hexadecimal digits.
+#[derive(Clone, Debug)]
+pub struct ServerName {
+    /// The hostname of the homeserver.
+    host: Host,
+    /// The network port of the homeserver, if it was given explicitly.
+    port: Option<u16>,
+}
+
+impl ServerName {
+    /// Creates a `ServerName` from an already parsed host and a port.
+    ///
+    /// IP addresses are accepted as they are. Domain names must be non-empty and free of colons and
+    /// other characters that can't be part of a server name, and are converted to the same
+    /// canonical form as when parsing a string, so e.g. `Host::Domain("Café.example.")` results in
+    /// the same server name as `xn--caf-dma.example`. Port 0 is rejected. Like an explicitly given
+    /// port, the port is kept by `to_string_preserving_port` even if it is `DEFAULT_PORT`.
+    pub fn from_host(host: Host, port: u16) -> Result<Self, Error> {
+        let host = match host {
+            Host::Domain(domain) => {
+                if domain.is_empty()
+                    || domain.contains(|c| c == ':' || is_forbidden_in_server_name(c))
+                {
+                    return Err(Error::new(ErrorKind::InvalidHost, &domain));
+                }
+
+                validation::parse_host(&domain)
+                    .map_err(|err| Error::from_validation(err, &domain))?
+            }
+            ip => ip,
+        };
+
+        if port == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidPort,
+                &format!("{}:{}", host, port),
+            ));
+        }
+
+        Ok(Self {
+            host,
+            port: Some(port),
+        })
+    }
+
+    /// Creates a `ServerName` from the host and the explicitly specified port, if any, of a server
+    /// name that was already parsed by the validation crate.
+    pub(crate) fn from_parsed(host: Host, port: Option<u16>) -> Self {
+        Self { host, port }
+    }
+
+    /// Returns the `Host` of the server name, i.e. the server name minus the port.
+    ///
+    /// The host can be either a domain name, an IPv4 address, or an IPv6 address.
+    pub fn host(&self) -> &Host {
+        &self.host
+    }
+
+    /// Whether the host is an IPv4 or IPv6 address rather than a domain name.
+    ///
+    /// Servers with an IP literal as their server name are contacted directly, without looking
+    /// up `.well-known` delegation or SRV records.
+    pub fn is_ip_literal(&self) -> bool {
+        match self.host {
+            Host::Domain(_) => false,
+            Host::Ipv4(_) | Host::Ipv6(_) => true,
+        }
+    }
+
+    /// Whether the host refers to the local machine, i.e. is the `localhost` domain or one of its
+    /// subdomains, an IPv4 address in 127.0.0.0/8, or the IPv6 address `::1`.
+    ///
+    /// IPv4-mapped IPv6 addresses in ::ffff:0:0/96, like `[::ffff:127.0.0.1]`, reach the IPv4
+    /// address they contain, so they are checked like that address.
+    pub fn is_loopback(&self) -> bool {
+        match &self.host {
+            Host::Domain(domain) => domain == "localhost" || domain.ends_with(".localhost"),
+            Host::Ipv4(address) => address.is_loopback(),
+            Host::Ipv6(address) => match ipv4_mapped(address) {
+                Some(address) => address.is_loopback(),
+                None => address.is_loopback(),
+            },
+        }
+    }
+
+    /// Whether the host can't be reached over the public internet.
+    ///
+    /// Besides loopback hosts as determined by `is_loopback`, this includes the unspecified
+    /// addresses `0.0.0.0` and `::`, the private IPv4 ranges 10.0.0.0/8, 172.16.0.0/12 and
+    /// 192.168.0.0/16, link-local addresses in 169.254.0.0/16 and fe80::/10, and unique local IPv6
+    /// addresses in fc00::/7. Other domains are assumed to be public, as resolving them is out of
+    /// scope for this crate. Like in `is_loopback`, IPv4-mapped IPv6 addresses are checked like
+    /// the IPv4 address they contain.
+    pub fn is_private_network(&self) -> bool {
+        if self.is_loopback() {
+            return true;
+        }
+
+        match &self.host {
+            Host::Domain(_) => false,
+            Host::Ipv4(address) => is_private_ipv4(address),
+            Host::Ipv6(address) => {
+                if let Some(address) = ipv4_mapped(address) {
+                    return is_private_ipv4(&address);
+                }
+
+                let first_segment = address.segments()[0];
+
+                address.is_unspecified()
+                    || first_segment & 0xfe00 == 0xfc00
+                    || first_segment & 0xffc0 == 0xfe80
+            }
+        }
+    }
+
+    /// Returns the host as a string, exactly as it appears in the string representation of the
+    /// server name, i.e. without the port. IPv6 addresses are enclosed in brackets.
+    pub fn host_str(&self) -> String {
+        self.host.to_string()
+    }
+
+    /// Returns the port the homeserver can be accessed on.
+    pub fn port(&self) -> u16 {
+        self.port.unwrap_or(DEFAULT_PORT)
+    }
+
+    /// Returns the port if it was given explicitly, even if it is `DEFAULT_PORT`, or `None` if the
+    /// server name doesn't specify one.
+    ///
+    /// Unlike `port`, this tells a server name without a port apart from one with the default
+    /// port given explicitly. Server names created with `from_host` always have an explicit port.
+    pub fn explicit_port(&self) -> Option<u16> {
+        self.port
+    }
+
+    /// Returns the HTTPS base URL of the homeserver's federation API, like
+    /// `https://example.com:8448/`.
+    ///
+    /// Unlike the string representation, the URL always contains the port, because the default
+    /// port of HTTPS URLs is 443 while the default port of server names is `DEFAULT_PORT`, 8448.
+    /// Leaving the port out would make the URL point to port 443 instead.
+    ///
+    /// This is the URL to use if the server name doesn't delegate to another host, e.g. with a
+    /// `.well-known` file or an SRV record; resolving such delegations is left to the caller.
+    pub fn federation_base_url(&self) -> String {
+        federation_base_url(&self.host, self.port())
+    }
+
+    /// Returns the host and the port of the homeserver in one call, with the domain borrowed
+    /// instead of being copied, e.g. as the starting point for resolving the server name as
+    /// described by the Matrix server discovery algorithm.
+    ///
+    /// The host can be matched as a domain name, IPv4 address or IPv6 address without converting
+    /// it to a string first. The port is `DEFAULT_PORT` if none is given; use `explicit_port` to
+    /// find out whether one was given, which affects the discovery algorithm.
+    pub fn resolution_target(&self) -> (Host<&str>, u16) {
+        (borrow_host(&self.host), self.port())
+    }
+
+    /// Returns the string representation of the server name, including the port if it was given
+    /// explicitly, even if it is the default port.
+    ///
+    /// Only whether the port was given is preserved. The host is still in its canonical form, i.e.
+    /// domain names are lowercased, encoded as punycode and stripped of a trailing dot, and IP
+    /// addresses are written in their standard notation, so `EXAMPLE.com.:8448` is returned as
+    /// `example.com:8448`. This doesn't reproduce the original string byte for byte unless it was
+    /// already canonical apart from the port.
+    pub fn to_string_preserving_port(&self) -> String {
+        match self.port {
+            Some(port) => format!("{}:{}", self.host, port),
+            None => self.host.to_string(),
+        }
+    }
+}
+
+impl Display for ServerName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        if self.port() == DEFAULT_PORT {
+            write!(f, "{}", self.host)
+        } else {
+            write!(f, "{}:{}", self.host, self.port())
+        }
+    }
+}
+
+impl PartialEq for ServerName {
+    fn eq(&self, other: &Self) -> bool {
+        self.host == other.host && self.port() == other.port()
+    }
+}
+
+impl Eq for ServerName {}
+
+impl Hash for ServerName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.host.hash(state);
+        self.port().hash(state);
+    }
+}
+
+impl PartialOrd for ServerName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Server names are ordered lexicographically by their string representation, i.e. by their host
+/// and then by their port as they are written, not by their individual components. So
+/// `example.com` comes before `example.com:443`, which comes before `example.com:5000`.
+///
+/// The string representations are compared without allocating.
+impl Ord for ServerName {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (DisplayBuffer::new(self), DisplayBuffer::new(other)) {
+            (Some(buffer), Some(other_buffer)) => buffer.as_bytes().cmp(other_buffer.as_bytes()),
+            // Every server name fits into the buffer, so this is never reached.
+            _ => self.to_string().cmp(&other.to_string()),
+        }
+    }
+}
+
+/// A buffer on the stack holding the string representation of a server name.
+struct DisplayBuffer {
+    /// The bytes of the string representation, followed by unused ones.
+    bytes: [u8; MAX_SERVER_NAME_BYTES],
+    /// The number of bytes written to the buffer.
+    len: usize,
+}
+
+impl DisplayBuffer {
+    /// Writes the string representation of a server name to a new buffer, or returns `None` if it
+    /// doesn't fit.
+    fn new(server_name: &ServerName) -> Option<Self> {
+        let mut buffer = Self {
+            bytes: [0; MAX_SERVER_NAME_BYTES],
+            len: 0,
+        };
+
+        write!(buffer, "{}", server_name).ok().map(|_| buffer)
+    }
+
+    /// Returns the bytes written to the buffer.
+    fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl Write for DisplayBuffer {
+    fn write_str(&mut self, s: &str) -> FmtResult {
+        let end = self.len + s.len();
+        if end > MAX_SERVER_NAME_BYTES {
+            return Err(FmtError);
+        }
+
+        self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+impl Serialize for ServerName {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ServerName {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_id(deserializer, "a Matrix server name as a string")
+    }
+}
+
+impl TryFrom<&str> for ServerName {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix server name from a string representation.
+    ///
+    /// The string must be a valid IP address or DNS name, optionally followed by a literal colon
+    /// and a port.
+    fn try_from(server_name: &str) -> Result<Self, Error> {
+        let (host, port) = validation::parse(server_name)
+            .map_err(|err| Error::from_validation(err, server_name))?;
+
+        Ok(Self { host, port })
+    }
+}
+
+/// Returns the IPv4 address contained in an IPv4-mapped IPv6 address, i.e. one in ::ffff:0:0/96.
+fn ipv4_mapped(address: &Ipv6Addr) -> Option<Ipv4Addr> {
+    let segments = address.segments();
+
+    if segments[..5].iter().all(|&segment| segment == 0) && segments[5] == 0xffff {
+        let octets = address.octets();
+        Some(Ipv4Addr::new(
+            octets[12], octets[13], octets[14], octets[15],
+        ))
+    } else {
+        None
+    }
+}
+
+/// Whether an IPv4 address is unspecified, private or link-local.
+fn is_private_ipv4(address: &Ipv4Addr) -> bool {
+    address.is_unspecified() || address.is_private() || address.is_link_local()
+}
+
+/// Checks whether a string is a valid server name, without constructing a `ServerName`.
+///
+/// This performs the same checks as `ServerName::try_from`.
+pub fn is_valid_server_name(server_name: &str) -> bool {
+    validation::parse(server_name).is_ok()
+}
+
+impl TryFrom<String> for ServerName {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix server name from an owned string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn try_from(server_name: String) -> Result<Self, Error> {
+        Self::try_from(server_name.as_str())
+    }
+}
+
+impl FromStr for ServerName {
+    type Err = Error;
+
+    /// Attempts to create a new Matrix server name from a string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cmp::Ordering,
+        convert::TryFrom,
+        net::{Ipv4Addr, Ipv6Addr},
+    };
+
+    use serde_json::{from_str, to_string};
+
+    use super::{is_valid_server_name, ServerName};
+    use crate::{error::ErrorKind, Host};
+
+    #[test]
+    fn valid_server_name() {
+        let server_name =
+            ServerName::try_from("example.com").expect("Failed to create ServerName.");
+
+        assert_eq!(server_name.to_string(), "example.com");
+        assert_eq!(server_name.host(), &Host::Domain("example.com".to_string()));
+        assert_eq!(server_name.port(), 8448);
+    }
+
+    #[test]
+    fn valid_server_name_with_explicit_standard_port() {
+        assert_eq!(
+            ServerName::try_from("example.com:8448")
+                .expect("Failed to create ServerName.")
+                .to_string(),
+            "example.com"
+        );
+    }
+
+    #[test]
+    fn valid_server_name_with_non_standard_port() {
+        let server_name =
+            ServerName::try_from("example.com:443").expect("Failed to create ServerName.");
+
+        assert_eq!(server_name.to_string(), "example.com:443");
+        assert_eq!(server_name.port(), 443);
+    }
+
+    #[test]
+    fn server_name_preserving_port() {
+        let implicit = ServerName::try_from("example.com").expect("Failed to create ServerName.");
+        let explicit =
+            ServerName::try_from("example.com:8448").expect("Failed to create ServerName.");
+        let non_standard =
+            ServerName::try_from("example.com:5000").expect("Failed to create ServerName.");
+
+        assert_eq!(implicit.to_string_preserving_port(), "example.com");
+        assert_eq!(explicit.to_string_preserving_port(), "example.com:8448");
+        assert_eq!(non_standard.to_string_preserving_port(), "example.com:5000");
+        assert_eq!(implicit, explicit);
+    }
+
+    #[test]
+    fn ipv6_server_name_preserving_port() {
+        assert_eq!(
+            ServerName::try_from("[::1]")
+                .expect("Failed to create ServerName.")
+                .to_string_preserving_port(),
+            "[::1]"
+        );
+        assert_eq!(
+            ServerName::try_from("[::1]:8448")
+                .expect("Failed to create ServerName.")
+                .to_string_preserving_port(),
+            "[::1]:8448"
+        );
+    }
+
+    #[test]
+    fn valid_ipv4_server_name() {
+        assert_eq!(
+            ServerName::try_from("1.1.1.1")
+                .expect("Failed to create ServerName.")
+                .host(),
+            &Host::<String>::Ipv4(Ipv4Addr::new(1, 1, 1, 1))
+        );
+    }
+
+    #[test]
+    fn valid_ipv6_server_name() {
+        let server_name = ServerName::try_from("[::1]:5000").expect("Failed to create ServerName.");
+
+        assert_eq!(
+            server_name.host(),
+            &Host::<String>::Ipv6(Ipv6Addr::LOCALHOST)
+        );
+        assert_eq!(server_name.to_string(), "[::1]:5000");
+    }
+
+    #[test]
+    fn parse_valid_server_name() {
+        assert_eq!(
+            "example.com"
+                .parse::<ServerName>()
+                .expect("Failed to parse ServerName."),
+            ServerName::try_from("example.com").expect("Failed to create ServerName.")
+        );
+    }
+
+    #[test]
+    fn serialize_valid_server_name() {
+        assert_eq!(
+            to_string(&ServerName::try_from("example.com").expect("Failed to create ServerName."))
+                .expect("Failed to convert ServerName to JSON."),
+            r#""example.com""#
+        );
+    }
+
+    #[test]
+    fn deserialize_valid_server_name() {
+        assert_eq!(
+            from_str::<ServerName>(r#""example.com:5000""#)
+                .expect("Failed to convert JSON to ServerName"),
+            ServerName::try_from("example.com:5000").expect("Failed to create ServerName.")
+        );
+    }
+
+    #[test]
+    fn empty_server_name() {
+        assert_eq!(
+            ServerName::try_from("").unwrap_err().kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+
+    #[test]
+    fn invalid_server_name_host() {
+        assert_eq!(
+            ServerName::try_from("/").unwrap_err().kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+
+    #[test]
+    fn invalid_server_name_port() {
+        for server_name in &[
+            "example.com:notaport",
+            "example.com:",
+            "example.com:+80",
+            "example.com:65536",
+            "example.com:0",
+            "example.com:00",
+            "[::1]:0",
+            "[::1]:",
+            "[::1]5000",
+        ] {
+            assert_eq!(
+                ServerName::try_from(*server_name).unwrap_err().kind(),
+                ErrorKind::InvalidPort
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_ipv6_server_name() {
+        for server_name in &["[::1", "::1", "[::1::2]", "[example.com]"] {
+            assert_eq!(
+                ServerName::try_from(*server_name).unwrap_err().kind(),
+                ErrorKind::InvalidHost
+            );
+        }
+    }
+
+    #[test]
+    fn server_name_with_url_components() {
+        assert_eq!(
+            ServerName::try_from("example.com/path").unwrap_err().kind(),
+            ErrorKind::InvalidHost
+        );
+        assert_eq!(
+            ServerName::try_from("example.com?query")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidHost
+        );
+        assert_eq!(
+            ServerName::try_from("example.com#fragment")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidHost
+        );
+        assert_eq!(
+            ServerName::try_from("user@example.com").unwrap_err().kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+
+    #[test]
+    fn server_name_with_whitespace() {
+        assert_eq!(
+            ServerName::try_from("exam\tple.com").unwrap_err().kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+
+    #[test]
+    fn is_valid_server_name_matches_try_from() {
+        assert!(is_valid_server_name("example.com"));
+        assert!(is_valid_server_name("[::1]:5000"));
+        assert!(!is_valid_server_name("example.com/path"));
+        assert!(!is_valid_server_name(""));
+    }
+
+    #[test]
+    fn server_name_is_ip_literal() {
+        assert!(!ServerName::try_from("example.com")
+            .expect("Failed to create ServerName.")
+            .is_ip_literal());
+        assert!(ServerName::try_from("1.2.3.4:5000")
+            .expect("Failed to create ServerName.")
+            .is_ip_literal());
+        assert!(ServerName::try_from("[::1]")
+            .expect("Failed to create ServerName.")
+            .is_ip_literal());
+    }
+
+    #[test]
+    fn loopback_server_names() {
+        for server_name in &[
+            "localhost",
+            "LocalHost:5000",
+            "matrix.localhost",
+            "127.0.0.1",
+            "127.1.2.3:8448",
+            "[::1]",
+            "[::ffff:127.0.0.1]",
+            "[::ffff:127.1.2.3]:8448",
+        ] {
+            let server_name =
+                ServerName::try_from(*server_name).expect("Failed to create ServerName.");
+
+            assert!(server_name.is_loopback());
+            assert!(server_name.is_private_network());
+        }
+    }
+
+    #[test]
+    fn private_network_server_names() {
+        for server_name in &[
+            "0.0.0.0",
+            "10.1.2.3",
+            "172.16.0.1",
+            "172.31.255.255",
+            "192.168.1.1:5000",
+            "169.254.1.1",
+            "[::]",
+            "[fc00::1]",
+            "[fd12:3456::1]:8448",
+            "[fe80::1]",
+            "[::ffff:10.0.0.1]",
+            "[::ffff:172.16.0.1]",
+            "[::ffff:192.168.1.1]:5000",
+            "[::ffff:169.254.1.1]",
+            "[::ffff:0.0.0.0]",
+        ] {
+            let server_name =
+                ServerName::try_from(*server_name).expect("Failed to create ServerName.");
+
+            assert!(!server_name.is_loopback());
+            assert!(server_name.is_private_network());
+        }
+    }
+
+    #[test]
+    fn public_server_names() {
+        for server_name in &[
+            "example.com",
+            "localhost.example.com",
+            "1.1.1.1",
+            "172.32.0.1",
+            "192.169.1.1",
+            "[2001:db8::1]",
+            "[fec0::1]",
+            "[::ffff:1.1.1.1]",
+            "[::ffff:172.32.0.1]",
+        ] {
+            let server_name =
+                ServerName::try_from(*server_name).expect("Failed to create ServerName.");
+
+            assert!(!server_name.is_loopback());
+            assert!(!server_name.is_private_network());
+        }
+    }
+
+    #[test]
+    fn server_name_with_surrounding_whitespace() {
+        for id in &[
+            " example.com",
+            "example.com ",
+            "\texample.com",
+            "example.com\t",
+        ] {
+            assert_eq!(
+                ServerName::try_from(*id).unwrap_err().kind(),
+                ErrorKind::SurroundingWhitespace
+            );
+            assert_eq!(
+                ServerName::try_from(id.to_string()).unwrap_err().kind(),
+                ErrorKind::SurroundingWhitespace
+            );
+        }
+    }
+
+    #[test]
+    fn server_name_host_str() {
+        assert_eq!(
+            ServerName::try_from("Example.com")
+                .expect("Failed to create ServerName.")
+                .host_str(),
+            "example.com"
+        );
+        assert_eq!(
+            ServerName::try_from("example.com:5000")
+                .expect("Failed to create ServerName.")
+                .host_str(),
+            "example.com"
+        );
+        assert_eq!(
+            ServerName::try_from("1.2.3.4:8448")
+                .expect("Failed to create ServerName.")
+                .host_str(),
+            "1.2.3.4"
+        );
+        assert_eq!(
+            ServerName::try_from("[::1]")
+                .expect("Failed to create ServerName.")
+                .host_str(),
+            "[::1]"
+        );
+        assert_eq!(
+            ServerName::try_from("[0:0::1]:5000")
+                .expect("Failed to create ServerName.")
+                .host_str(),
+            "[::1]"
+        );
+    }
+
+    #[test]
+    fn server_name_from_host() {
+        let server_name = ServerName::from_host(Host::Ipv4(Ipv4Addr::new(1, 1, 1, 1)), 8448)
+            .expect("Failed to create ServerName.");
+
+        assert_eq!(
+            server_name,
+            ServerName::try_from("1.1.1.1").expect("Failed to create ServerName.")
+        );
+        assert_eq!(server_name.to_string_preserving_port(), "1.1.1.1:8448");
+    }
+
+    #[test]
+    fn invalid_server_name_from_host() {
+        for domain in &["", "example.com:5000", "exa mple.com", "example.com/path"] {
+            assert_eq!(
+                ServerName::from_host(Host::Domain(domain.to_string()), 5000)
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::InvalidHost
+            );
+        }
+        assert_eq!(
+            ServerName::from_host(Host::Domain("example.com".to_string()), 0)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidPort
+        );
+    }
+
+    #[test]
+    fn fully_qualified_server_name() {
+        let server_name =
+            ServerName::try_from("example.com.").expect("Failed to create ServerName.");
+
+        assert_eq!(server_name.to_string(), "example.com");
+        assert_eq!(server_name.host(), &Host::Domain("example.com".to_string()));
+        assert_eq!(
+            server_name,
+            ServerName::try_from("example.com").expect("Failed to create ServerName.")
+        );
+        assert_eq!(
+            ServerName::try_from("example.com.:5000")
+                .expect("Failed to create ServerName.")
+                .to_string(),
+            "example.com:5000"
+        );
+        assert_eq!(
+            ServerName::from_host(Host::Domain("example.com.".to_string()), 5000)
+                .expect("Failed to create ServerName.")
+                .host(),
+            &Host::Domain("example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn server_name_with_invalid_trailing_dots() {
+        for server_name in &[".", ".:5000", "example.com..", "example.com..:5000"] {
+            assert_eq!(
+                ServerName::try_from(*server_name).unwrap_err().kind(),
+                ErrorKind::InvalidHost
+            );
+            assert!(!is_valid_server_name(server_name));
+        }
+
+        assert_eq!(
+            ServerName::from_host(Host::Domain("example.com..".to_string()), 5000)
+                .unwrap_err()
+                .input(),
+            "example.com.."
+        );
+    }
+
+    #[test]
+    fn internationalized_server_name() {
+        let unicode = ServerName::try_from("café.example").expect("Failed to create ServerName.");
+        let punycode =
+            ServerName::try_from("xn--caf-dma.example").expect("Failed to create ServerName.");
+
+        assert_eq!(unicode, punycode);
+        assert_eq!(unicode.to_string(), "xn--caf-dma.example");
+        assert_eq!(
+            unicode.host(),
+            &Host::Domain("xn--caf-dma.example".to_string())
+        );
+        assert_eq!(
+            ServerName::try_from("CAFÉ.example:5000")
+                .expect("Failed to create ServerName.")
+                .to_string(),
+            "xn--caf-dma.example:5000"
+        );
+        assert_eq!(
+            ServerName::from_host(Host::Domain("Café.example".to_string()), 8448)
+                .expect("Failed to create ServerName."),
+            punycode
+        );
+    }
+
+    #[test]
+    fn ipv6_server_name_with_zone_identifier() {
+        for server_name in &["[fe80::1%25eth0]", "[fe80::1%eth0]:8448", "[fe80::1%]"] {
+            let error = ServerName::try_from(*server_name).unwrap_err();
+
+            assert_eq!(error.kind(), ErrorKind::InvalidHost);
+            assert_eq!(error.input(), *server_name);
+            assert!(!is_valid_server_name(server_name));
+        }
+    }
+
+    #[test]
+    fn server_name_explicit_port() {
+        let server_name = |s| ServerName::try_from(s).expect("Failed to create ServerName.");
+
+        assert_eq!(server_name("example.com").explicit_port(), None);
+        assert_eq!(server_name("example.com").port(), 8448);
+        assert_eq!(server_name("example.com:8448").explicit_port(), Some(8448));
+        assert_eq!(server_name("example.com:443").explicit_port(), Some(443));
+        assert_eq!(
+            ServerName::from_host(Host::Domain("example.com".to_string()), 8448)
+                .expect("Failed to create ServerName.")
+                .explicit_port(),
+            Some(8448)
+        );
+    }
+
+    #[test]
+    fn server_name_with_dns_length_limits() {
+        let label = "a".repeat(63);
+        let domain = format!("{}.{}.{}.{}", label, label, label, "a".repeat(61));
+
+        assert_eq!(domain.len(), 253);
+        assert!(ServerName::try_from(format!("{}.com", label).as_str()).is_ok());
+        assert!(ServerName::try_from(domain.as_str()).is_ok());
+        assert!(ServerName::try_from(format!("{}.", domain).as_str()).is_ok());
+    }
+
+    #[test]
+    fn server_name_with_too_long_label() {
+        let server_name = format!("{}.com", "a".repeat(64));
+
+        assert_eq!(
+            ServerName::try_from(server_name.as_str())
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidHost
+        );
+        assert!(!is_valid_server_name(&server_name));
+        assert_eq!(
+            ServerName::from_host(Host::Domain(server_name), 5000)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+
+    #[test]
+    fn server_name_ordering_matches_string_ordering() {
+        let label = "a".repeat(63);
+        let longest = format!("{}.{}.{}.{}:65535", label, label, label, "a".repeat(61));
+        let mut server_names = [
+            "example.com",
+            "example.com:443",
+            "example.com:5000",
+            "example.com:8448",
+            "example.co",
+            "example.co:5000",
+            "example.co-op",
+            "example.community",
+            "1.2.3.4",
+            "1.2.3.4:5000",
+            "1.2.3.45",
+            "[::1]",
+            "[::1]:5000",
+            "[::ffff:1.2.3.4]",
+            longest.as_str(),
+        ]
+        .iter()
+        .map(|s| ServerName::try_from(*s).expect("Failed to create ServerName."))
+        .collect::<Vec<_>>();
+        let mut strings = server_names
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        server_names.sort();
+        strings.sort();
+
+        assert_eq!(
+            server_names
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            strings
+        );
+        assert_eq!(
+            server_names[0]
+                .cmp(&ServerName::try_from("1.2.3.4:8448").expect("Failed to create ServerName.")),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn server_name_too_long_for_dns() {
+        let label = "a".repeat(63);
+        let server_name = format!("{}.{}.{}.{}", label, label, label, "a".repeat(62));
+
+        assert_eq!(server_name.len(), 254);
+        assert_eq!(
+            ServerName::try_from(server_name.as_str())
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidHost
+        );
+        assert_eq!(
+            ServerName::try_from(format!("{}:5000", server_name).as_str())
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! Implements `SIGIL` and `sigil` for identifiers that start with a sigil.
+
+macro_rules! sigil_impl {
+    ($name:ident, $sigil:literal) => {
+        impl $crate::$name {
+            /// The sigil every identifier of this kind starts with.
+            pub const SIGIL: char = $sigil;
+
+            /// Returns the sigil of the identifier, i.e. `SIGIL`.
+            pub fn sigil(&self) -> char {
+                Self::SIGIL
+            }
+        }
+    };
+}
+
+sigil_impl!(EventId, '$');
+sigil_impl!(GroupId, '+');
+sigil_impl!(RoomAliasId, '#');
+sigil_impl!(RoomId, '!');
+sigil_impl!(UserId, '@');
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::{EventId, GroupId, RoomAliasId, RoomId, RoomIdOrAliasId, UserId};
+
+    #[test]
+    fn sigils_of_identifiers() {
+        assert_eq!(
+            EventId::try_from("$39hvsi03hlne:example.com")
+                .expect("Failed to create EventId.")
+                .sigil(),
+            '$'
+        );
+        assert_eq!(
+            GroupId::try_from("+ruma:example.com")
+                .expect("Failed to create GroupId.")
+                .sigil(),
+            '+'
+        );
+        assert_eq!(
+            RoomAliasId::try_from("#ruma:example.com")
+                .expect("Failed to create RoomAliasId.")
+                .sigil(),
+            '#'
+        );
+        assert_eq!(
+            RoomId::try_from("!n8f893n9:example.com")
+                .expect("Failed to create RoomId.")
+                .sigil(),
+            '!'
+        );
+        assert_eq!(
+            UserId::try_from("@carl:example.com")
+                .expect("Failed to create UserId.")
+                .sigil(),
+            '@'
+        );
+        assert_eq!(
+            RoomIdOrAliasId::try_from("#ruma:example.com")
+                .expect("Failed to create RoomIdOrAliasId.")
+                .sigil(),
+            RoomAliasId::SIGIL
+        );
+    }
+
+    #[test]
+    fn sigil_constants_match_string_representation() {
+        let room_id = RoomId::new("example.com").expect("Failed to generate RoomId.");
+        let user_id = UserId::new("example.com").expect("Failed to generate UserId.");
+
+        assert!(room_id.as_str().starts_with(RoomId::SIGIL));
+        assert!(user_id.as_str().starts_with(UserId::SIGIL));
+    }
+}
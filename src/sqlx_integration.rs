@@ -0,0 +1,68 @@
+//! Implements traits from sqlx, allowing identifiers to be used as database fields.
+
+use std::convert::TryFrom;
+
+use sqlx::{encode::IsNull, error::BoxDynError, Database, Decode, Encode, Type};
+
+macro_rules! sqlx_impl {
+    ($name:ident) => {
+        impl<DB> Type<DB> for $crate::$name
+        where
+            DB: Database,
+            String: Type<DB>,
+        {
+            fn type_info() -> DB::TypeInfo {
+                <String as Type<DB>>::type_info()
+            }
+
+            fn compatible(ty: &DB::TypeInfo) -> bool {
+                <String as Type<DB>>::compatible(ty)
+            }
+        }
+
+        impl<'q, DB> Encode<'q, DB> for $crate::$name
+        where
+            DB: Database,
+            String: Encode<'q, DB>,
+        {
+            fn encode(
+                self,
+                buf: &mut <DB as Database>::ArgumentBuffer<'q>,
+            ) -> Result<IsNull, BoxDynError> {
+                <String as Encode<'q, DB>>::encode(self.into_string(), buf)
+            }
+
+            fn encode_by_ref(
+                &self,
+                buf: &mut <DB as Database>::ArgumentBuffer<'q>,
+            ) -> Result<IsNull, BoxDynError> {
+                // Some databases keep the argument until the query is executed, which can be
+                // after the identifier is dropped, so it can't be borrowed.
+                <String as Encode<'q, DB>>::encode(self.as_str().to_owned(), buf)
+            }
+        }
+
+        impl<'r, DB> Decode<'r, DB> for $crate::$name
+        where
+            DB: Database,
+            String: Decode<'r, DB>,
+        {
+            fn decode(value: <DB as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+                let string = <String as Decode<'r, DB>>::decode(value)?;
+                Ok(Self::try_from(string)?)
+            }
+        }
+    };
+}
+
+sqlx_impl!(DeviceId);
+sqlx_impl!(EventId);
+sqlx_impl!(GroupId);
+sqlx_impl!(KeyId);
+sqlx_impl!(RoomAlias);
+sqlx_impl!(RoomAliasId);
+sqlx_impl!(RoomId);
+sqlx_impl!(RoomIdOrAliasId);
+sqlx_impl!(RoomVersionId);
+sqlx_impl!(UserId);
+sqlx_impl!(UserLocalpart);
@@ -0,0 +1,374 @@
+//! Third-party identifiers, like e-mail addresses and phone numbers.
+
+use alloc::string::{String, ToString};
+use core::{
+    convert::TryFrom,
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    deserialize_id,
+    error::{Error, ErrorKind},
+    MAX_BYTES,
+};
+
+/// The medium of a third-party identifier, i.e. the kind of address it is.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Medium {
+    /// An e-mail address, like `alice@example.com`.
+    Email,
+    /// A phone number in the international format without the leading `+`, like `15555555555`.
+    Msisdn,
+}
+
+impl Medium {
+    /// Returns the medium as a string slice, as it is used in the Matrix specification.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Medium::Email => "email",
+            Medium::Msisdn => "msisdn",
+        }
+    }
+
+    /// Checks an address of this medium.
+    fn validate_address(self, address: &str) -> Result<(), ErrorKind> {
+        if address.is_empty() {
+            return Err(ErrorKind::MissingAddress);
+        }
+
+        if address.len() > MAX_BYTES {
+            return Err(ErrorKind::MaximumLengthExceeded);
+        }
+
+        let is_valid = match self {
+            Medium::Email => match address.rfind('@') {
+                Some(index) => {
+                    index > 0
+                        && index < address.len() - 1
+                        && !address.chars().any(|c| c.is_whitespace() || c.is_control())
+                }
+                None => false,
+            },
+            // E.164 phone numbers have at most 15 digits.
+            Medium::Msisdn => address.len() <= 15 && address.bytes().all(|b| b.is_ascii_digit()),
+        };
+
+        if is_valid {
+            Ok(())
+        } else {
+            Err(ErrorKind::InvalidAddress)
+        }
+    }
+}
+
+impl Display for Medium {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Medium {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Medium {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_id(deserializer, "a third-party identifier medium as a string")
+    }
+}
+
+impl TryFrom<&str> for Medium {
+    type Error = Error;
+
+    /// Attempts to parse a medium, which must be `email` or `msisdn`.
+    fn try_from(medium: &str) -> Result<Self, Error> {
+        match medium {
+            "email" => Ok(Medium::Email),
+            "msisdn" => Ok(Medium::Msisdn),
+            _ => Err(Error::new(ErrorKind::UnknownMedium, medium)),
+        }
+    }
+}
+
+impl TryFrom<String> for Medium {
+    type Error = Error;
+
+    /// Attempts to parse a medium from an owned string.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn try_from(medium: String) -> Result<Self, Error> {
+        Self::try_from(medium.as_str())
+    }
+}
+
+impl FromStr for Medium {
+    type Err = Error;
+
+    /// Attempts to parse a medium.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+/// A third-party identifier, consisting of a medium and an address, like an e-mail address.
+///
+/// Third-party identifiers aren't Matrix identifiers, but are used alongside them, e.g. to invite
+/// users by their e-mail address. A `ThirdPartyId` is created from its parts or converted from a
+/// string of the medium, a literal colon and the address, which is also its string
+/// representation. The Matrix APIs transmit the medium and the address as separate fields; `Medium`
+/// can be deserialized on its own for that purpose.
+///
+/// E-mail addresses must contain an `@` with something on both sides and no whitespace. Phone
+/// numbers must consist of at most 15 digits. Addresses are kept exactly as given.
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use ruma_identifiers::{Medium, ThirdPartyId};
+/// let third_party_id = ThirdPartyId::try_from("email:alice@example.com").unwrap();
+/// assert_eq!(third_party_id.medium(), Medium::Email);
+/// assert_eq!(third_party_id.address(), "alice@example.com");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ThirdPartyId {
+    /// The kind of address.
+    medium: Medium,
+    /// The address itself.
+    address: String,
+}
+
+impl ThirdPartyId {
+    /// Creates a third-party identifier from its medium and address, validating the address.
+    pub fn new(medium: Medium, address: &str) -> Result<Self, Error> {
+        medium
+            .validate_address(address)
+            .map_err(|kind| Error::new(kind, address))?;
+
+        Ok(Self {
+            medium,
+            address: address.to_string(),
+        })
+    }
+
+    /// Returns the medium of the third-party identifier.
+    pub fn medium(&self) -> Medium {
+        self.medium
+    }
+
+    /// Returns the address of the third-party identifier.
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+}
+
+impl Display for ThirdPartyId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}:{}", self.medium, self.address)
+    }
+}
+
+impl Serialize for ThirdPartyId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ThirdPartyId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_id(deserializer, "a third-party identifier as a string")
+    }
+}
+
+impl TryFrom<&str> for ThirdPartyId {
+    type Error = Error;
+
+    /// Attempts to create a new third-party identifier from a string representation.
+    ///
+    /// The string must consist of the medium, a literal colon, and the address.
+    fn try_from(third_party_id: &str) -> Result<Self, Error> {
+        let colon_idx = third_party_id
+            .find(':')
+            .ok_or_else(|| Error::new(ErrorKind::MissingDelimiter, third_party_id))?;
+        let medium = Medium::try_from(&third_party_id[..colon_idx])
+            .map_err(|err| err.with_input(third_party_id))?;
+
+        Self::new(medium, &third_party_id[colon_idx + 1..])
+            .map_err(|err| err.with_input(third_party_id))
+    }
+}
+
+impl TryFrom<String> for ThirdPartyId {
+    type Error = Error;
+
+    /// Attempts to create a new third-party identifier from an owned string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn try_from(third_party_id: String) -> Result<Self, Error> {
+        Self::try_from(third_party_id.as_str())
+    }
+}
+
+impl FromStr for ThirdPartyId {
+    type Err = Error;
+
+    /// Attempts to create a new third-party identifier from a string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use serde_json::{from_str, to_string};
+
+    use super::{Medium, ThirdPartyId};
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn valid_email_third_party_id() {
+        let third_party_id = ThirdPartyId::new(Medium::Email, "alice@example.com")
+            .expect("Failed to create ThirdPartyId.");
+
+        assert_eq!(third_party_id.medium(), Medium::Email);
+        assert_eq!(third_party_id.address(), "alice@example.com");
+        assert_eq!(third_party_id.to_string(), "email:alice@example.com");
+    }
+
+    #[test]
+    fn valid_msisdn_third_party_id() {
+        let third_party_id =
+            ThirdPartyId::try_from("msisdn:15555555555").expect("Failed to create ThirdPartyId.");
+
+        assert_eq!(third_party_id.medium(), Medium::Msisdn);
+        assert_eq!(third_party_id.address(), "15555555555");
+    }
+
+    #[test]
+    fn third_party_id_round_trip() {
+        let third_party_id = ThirdPartyId::try_from("email:\"a:b\"@example.com")
+            .expect("Failed to create ThirdPartyId.");
+
+        assert_eq!(third_party_id.address(), "\"a:b\"@example.com");
+        assert_eq!(
+            third_party_id
+                .to_string()
+                .parse::<ThirdPartyId>()
+                .expect("Failed to parse ThirdPartyId."),
+            third_party_id
+        );
+    }
+
+    #[test]
+    fn serialize_valid_third_party_id() {
+        assert_eq!(
+            to_string(
+                &ThirdPartyId::new(Medium::Msisdn, "15555555555")
+                    .expect("Failed to create ThirdPartyId.")
+            )
+            .expect("Failed to convert ThirdPartyId to JSON."),
+            r#""msisdn:15555555555""#
+        );
+        assert_eq!(
+            to_string(&Medium::Email).expect("Failed to convert Medium to JSON."),
+            r#""email""#
+        );
+    }
+
+    #[test]
+    fn deserialize_valid_third_party_id() {
+        assert_eq!(
+            from_str::<ThirdPartyId>(r#""email:alice@example.com""#)
+                .expect("Failed to convert JSON to ThirdPartyId"),
+            ThirdPartyId::new(Medium::Email, "alice@example.com")
+                .expect("Failed to create ThirdPartyId.")
+        );
+        assert_eq!(
+            from_str::<Medium>(r#""msisdn""#).expect("Failed to convert JSON to Medium"),
+            Medium::Msisdn
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid_third_party_id() {
+        assert!(from_str::<ThirdPartyId>(r#""email:""#)
+            .unwrap_err()
+            .to_string()
+            .contains("address is missing"));
+        assert!(from_str::<Medium>(r#""bitcoin""#).is_err());
+    }
+
+    #[test]
+    fn unknown_third_party_id_medium() {
+        for third_party_id in &[
+            "bitcoin:1abc",
+            "Email:alice@example.com",
+            ":alice@example.com",
+        ] {
+            let error = ThirdPartyId::try_from(*third_party_id).unwrap_err();
+
+            assert_eq!(error.kind(), ErrorKind::UnknownMedium);
+            assert_eq!(error.input(), *third_party_id);
+        }
+    }
+
+    #[test]
+    fn missing_third_party_id_delimiter() {
+        assert_eq!(
+            ThirdPartyId::try_from("alice@example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingDelimiter
+        );
+    }
+
+    #[test]
+    fn empty_third_party_id_address() {
+        assert_eq!(
+            ThirdPartyId::try_from("email:").unwrap_err().kind(),
+            ErrorKind::MissingAddress
+        );
+        assert_eq!(
+            ThirdPartyId::new(Medium::Msisdn, "").unwrap_err().kind(),
+            ErrorKind::MissingAddress
+        );
+    }
+
+    #[test]
+    fn invalid_third_party_id_address() {
+        for (medium, address) in &[
+            (Medium::Email, "alice"),
+            (Medium::Email, "@example.com"),
+            (Medium::Email, "alice@"),
+            (Medium::Email, "al ice@example.com"),
+            (Medium::Msisdn, "+15555555555"),
+            (Medium::Msisdn, "1555555555555555"),
+        ] {
+            assert_eq!(
+                ThirdPartyId::new(*medium, address).unwrap_err().kind(),
+                ErrorKind::InvalidAddress
+            );
+        }
+    }
+}
@@ -1,22 +1,56 @@
 //! Matrix user identifiers.
 
-use std::{
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::{
+    cmp::Ordering,
     convert::TryFrom,
     fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
 };
 
 #[cfg(feature = "diesel")]
 use diesel::sql_types::Text;
+#[cfg(feature = "std")]
+use rand::thread_rng;
+use rand::Rng;
+use ruma_identifiers_validation::user_id::{self as validation, validate_localpart};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use url::Host;
 
-use crate::{deserialize_id, display, error::Error, generate_localpart, parse_id};
+use crate::{
+    alphabet::Alphabet, canonical_host, deserialize_id, error::Error, format_id,
+    generate_id_localpart, host::Host, id_from_host, id_from_parts, parse_id, parse_owned_id,
+    server_name::ServerName, user_localpart::UserLocalpart, SIGIL_BYTES,
+};
+
+/// The number of characters in the localpart of a generated user ID.
+const GENERATED_LENGTH: usize = 12;
 
 /// A Matrix user ID.
 ///
 /// A `UserId` is generated randomly or converted from a string slice, and can be converted back
 /// into a string as needed.
 ///
+/// Besides its components, a `UserId` stores its canonical string representation. This takes up
+/// roughly twice the memory, but allows it to be borrowed as a `&str`, so a `UserId` can be looked
+/// up in a `HashMap` or `HashSet` with a string slice. Since the localpart is downcased, the string
+/// slice has to be downcased as well to be found.
+///
+/// Besides the localparts allowed by the current specification, historical ones are accepted as
+/// well, since older homeservers issued user IDs with uppercase letters and any other printable
+/// ASCII character except for the colon. Such an ID can be told apart with `is_historical`.
+/// Uppercase letters are downcased either way, so a localpart that only differs from a conforming
+/// one in case is not considered historical.
+///
+/// As required by the specification, an entire user ID, i.e. the @ sigil, the localpart, the colon
+/// and the server name taken together, must not exceed 255 bytes. There is no separate limit for
+/// the localpart, which can take up whatever the server name leaves. The limit is checked against
+/// both the string as given and its canonical representation, which can be longer: an IPv4
+/// address in a shortened notation like `1.1` is written as `1.0.0.1`, for example. So every user
+/// ID that is accepted can be parsed again from its string representation.
+///
 /// ```
 /// # use std::convert::TryFrom;
 /// # use ruma_identifiers::UserId;
@@ -25,16 +59,16 @@ use crate::{deserialize_id, display, error::Error, generate_localpart, parse_id}
 ///     "@carl:example.com"
 /// );
 /// ```
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "diesel", derive(FromSqlRow, QueryId, AsExpression, SqlType))]
 #[cfg_attr(feature = "diesel", sql_type = "Text")]
 pub struct UserId {
-    /// The hostname of the homeserver.
-    hostname: Host,
-    /// The user's unique ID.
-    localpart: String,
-    /// The network port of the homeserver.
-    port: u16,
+    /// The user ID in its canonical string representation.
+    full_id: String,
+    /// The index of the colon separating the localpart and the server name in `full_id`.
+    colon_idx: usize,
+    /// The server name of the homeserver.
+    server_name: ServerName,
     /// Whether this user id is a historical one.
     ///
     /// A historical user id is one that is not legal per the regular user id rules, but was
@@ -45,41 +79,256 @@ pub struct UserId {
 
 impl UserId {
     /// Attempts to generate a `UserId` for the given origin server with a localpart consisting of
-    /// 12 random ASCII characters.
+    /// 12 random lowercase ASCII letters and digits.
     ///
     /// Fails if the given homeserver cannot be parsed as a valid host.
+    #[cfg(feature = "std")]
     pub fn new(homeserver_host: &str) -> Result<Self, Error> {
-        let user_id = format!(
-            "@{}:{}",
-            generate_localpart(12).to_lowercase(),
-            homeserver_host
-        );
-        let (localpart, host, port) = parse_id('@', &user_id)?;
+        Self::new_with_length(homeserver_host, GENERATED_LENGTH)
+    }
+
+    /// Attempts to generate a `UserId` for the given origin server with a localpart consisting of
+    /// `length` random lowercase ASCII letters and digits.
+    ///
+    /// Fails if the given homeserver cannot be parsed as a valid host, if `length` is zero, or if
+    /// the user ID would exceed 255 bytes.
+    #[cfg(feature = "std")]
+    pub fn new_with_length(homeserver_host: &str, length: usize) -> Result<Self, Error> {
+        Self::generate(
+            &mut thread_rng(),
+            homeserver_host,
+            length,
+            Alphabet::Alphanumeric,
+        )
+    }
+
+    /// Attempts to generate a `UserId` for the given origin server with a localpart consisting of
+    /// 12 random lowercase ASCII letters and digits drawn from the given random number generator.
+    ///
+    /// With a seeded generator, this produces reproducible user IDs, e.g. for tests. Unless the
+    /// generator is cryptographically secure, the user IDs are predictable.
+    ///
+    /// Fails if the given homeserver cannot be parsed as a valid host.
+    pub fn new_with_rng(homeserver_host: &str, rng: &mut impl Rng) -> Result<Self, Error> {
+        Self::generate(
+            rng,
+            homeserver_host,
+            GENERATED_LENGTH,
+            Alphabet::Alphanumeric,
+        )
+    }
+
+    /// Attempts to generate a `UserId` for the given origin server with a localpart consisting of
+    /// 12 random characters from the given alphabet, e.g. to match the other identifiers of the
+    /// homeserver. `new` is equivalent to passing `Alphabet::Alphanumeric`.
+    ///
+    /// The localpart is downcased after generating it, like for `new`.
+    ///
+    /// Fails if the homeserver cannot be parsed as a valid host.
+    #[cfg(feature = "std")]
+    pub fn new_with_alphabet(homeserver_host: &str, alphabet: Alphabet) -> Result<Self, Error> {
+        Self::generate(
+            &mut thread_rng(),
+            homeserver_host,
+            GENERATED_LENGTH,
+            alphabet,
+        )
+    }
+
+    /// Generates a `UserId` with a random localpart of the given length.
+    fn generate(
+        rng: &mut impl Rng,
+        homeserver_host: &str,
+        length: usize,
+        alphabet: Alphabet,
+    ) -> Result<Self, Error> {
+        let localpart =
+            generate_id_localpart(rng, length, alphabet, homeserver_host)?.to_lowercase();
+        let (full_id, colon_idx, server_name) =
+            id_from_parts('@', &localpart, homeserver_host, |_| Ok(()))
+                .map_err(|err| err.with_input(homeserver_host))?;
 
         Ok(Self {
-            hostname: host,
-            localpart: localpart.to_string(),
-            port,
+            full_id,
+            colon_idx,
+            server_name,
             is_historical: false,
         })
     }
 
+    /// Creates a `UserId` from an already validated localpart and the server name of the
+    /// originating homeserver.
+    ///
+    /// Unlike parsing a string, this only validates the server name.
+    pub fn from_parts(localpart: UserLocalpart, server_name: &str) -> Result<Self, Error> {
+        let (full_id, colon_idx, server_name) =
+            id_from_parts('@', localpart.as_str(), server_name, |_| Ok(()))?;
+
+        Ok(Self {
+            full_id,
+            colon_idx,
+            server_name,
+            is_historical: localpart.is_historical(),
+        })
+    }
+
+    /// Creates a `UserId` from an already validated localpart and an already parsed host and port
+    /// of the originating homeserver.
+    ///
+    /// Of the host and port, only the checks of `ServerName::from_host` apply.
+    pub fn from_host(localpart: UserLocalpart, host: Host, port: u16) -> Result<Self, Error> {
+        let (full_id, colon_idx, server_name) =
+            id_from_host('@', localpart.as_str(), host, port, |_| Ok(()))?;
+
+        Ok(Self {
+            full_id,
+            colon_idx,
+            server_name,
+            is_historical: localpart.is_historical(),
+        })
+    }
+
+    /// Creates a copy of the user ID with the same localpart, but the given server name.
+    ///
+    /// Fails if the server name is invalid.
+    pub fn with_server_name(&self, server_name: &str) -> Result<Self, Error> {
+        let (full_id, colon_idx, server_name) =
+            id_from_parts('@', self.localpart(), server_name, |_| Ok(()))?;
+
+        Ok(Self {
+            full_id,
+            colon_idx,
+            server_name,
+            is_historical: self.is_historical,
+        })
+    }
+
     /// Returns a `Host` for the user ID, containing the server name (minus the port) of the
     /// originating homeserver.
     ///
     /// The host can be either a domain name, an IPv4 address, or an IPv6 address.
     pub fn hostname(&self) -> &Host {
-        &self.hostname
+        self.server_name.host()
+    }
+
+    /// Whether the server name of the user ID is an IPv4 or IPv6 address rather than a domain
+    /// name.
+    pub fn is_ip_literal(&self) -> bool {
+        self.server_name.is_ip_literal()
+    }
+
+    /// Returns the host of the user ID's server name as a string slice, exactly as it appears in
+    /// the canonical string representation, i.e. without the port. IPv6 addresses are enclosed in
+    /// brackets.
+    pub fn host_str(&self) -> &str {
+        canonical_host(&self.full_id[self.colon_idx + 1..])
+    }
+
+    /// Returns the user ID as a string slice.
+    ///
+    /// The canonical string representation is stored when the user ID is created, so this doesn't
+    /// allocate, returns the same string as `to_string`, and can be called repeatedly for free.
+    pub fn as_str(&self) -> &str {
+        &self.full_id
+    }
+
+    /// Converts the user ID into its string representation, without allocating.
+    ///
+    /// This returns the same string as `as_str` and `to_string`, but takes the stored string
+    /// instead of copying it.
+    pub fn into_string(self) -> String {
+        self.full_id
     }
 
     /// Returns the user's localpart.
     pub fn localpart(&self) -> &str {
-        &self.localpart
+        &self.full_id[SIGIL_BYTES..self.colon_idx]
+    }
+
+    /// Whether the user ID has the given localpart, regardless of its server name.
+    ///
+    /// Both localparts are downcased, so this compares them without allocating or converting
+    /// either of them.
+    pub fn has_localpart(&self, localpart: &UserLocalpart) -> bool {
+        self.localpart() == localpart.as_str()
     }
 
     /// Returns the port the originating homeserver can be accessed on.
     pub fn port(&self) -> u16 {
-        self.port
+        self.server_name.port()
+    }
+
+    /// Returns the port of the originating homeserver if it was given explicitly, even if it is
+    /// `DEFAULT_PORT`, or `None` if the user ID doesn't specify one.
+    pub fn explicit_port(&self) -> Option<u16> {
+        self.server_name.explicit_port()
+    }
+
+    /// Returns the server name of the originating homeserver, i.e. its hostname followed by the
+    /// port if it isn't `DEFAULT_PORT`.
+    pub fn server_name(&self) -> String {
+        self.server_name.to_string()
+    }
+
+    /// Returns the localpart and the server name of the user ID in one call.
+    ///
+    /// Like `server_name`, the server name omits the port if it is `DEFAULT_PORT`.
+    pub fn parts(&self) -> (&str, String) {
+        (self.localpart(), self.server_name())
+    }
+
+    /// Returns the string representation of the ID, including the port if it was given explicitly,
+    /// even if it is the default port.
+    ///
+    /// Only the port is preserved, the rest of the ID is canonical. See
+    /// `ServerName::to_string_preserving_port`.
+    pub fn to_string_preserving_port(&self) -> String {
+        format!(
+            "@{}:{}",
+            self.localpart(),
+            self.server_name.to_string_preserving_port()
+        )
+    }
+
+    /// Converts the user ID into its localpart, reusing the stored string instead of allocating a
+    /// new one.
+    pub fn into_localpart(self) -> UserLocalpart {
+        self.into_parts().0
+    }
+
+    /// Converts the user ID into its localpart and the server name of the homeserver, reusing the
+    /// stored string for the localpart instead of allocating a new one.
+    pub fn into_parts(self) -> (UserLocalpart, ServerName) {
+        let mut localpart = self.full_id;
+        localpart.truncate(self.colon_idx);
+        localpart.drain(..SIGIL_BYTES);
+
+        (
+            UserLocalpart::from_valid(localpart, self.is_historical),
+            self.server_name,
+        )
+    }
+
+    /// Returns the user ID with all but the first two characters of the localpart masked, e.g.
+    /// `@ca***:example.com` for `@carl:example.com`, for logging it without revealing the user.
+    ///
+    /// This is equivalent to `redacted_with(2)`.
+    pub fn redacted(&self) -> String {
+        self.redacted_with(2)
+    }
+
+    /// Returns the user ID with all but the first `revealed` characters of the localpart masked.
+    ///
+    /// The masked characters are replaced by `***` regardless of their number, so the length of
+    /// the localpart isn't revealed either. At least one character is always masked, so a
+    /// localpart that isn't longer than `revealed` is never shown in full. The sigil and the
+    /// server name are kept as they are.
+    pub fn redacted_with(&self, revealed: usize) -> String {
+        let localpart = self.localpart();
+        // Localparts only consist of ASCII characters, so this is a character boundary.
+        let revealed = revealed.min(localpart.len() - 1);
+
+        format!("@{}***:{}", &localpart[..revealed], self.server_name())
     }
 
     /// Whether this user ID is a historical one, i.e. one that doesn't conform to the latest
@@ -92,7 +341,21 @@ impl UserId {
 
 impl Display for UserId {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        display(f, '@', &self.localpart, &self.hostname, self.port)
+        f.write_str(&self.full_id)
+    }
+}
+
+impl PartialOrd for UserId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// User IDs are ordered lexicographically by their string representation, not by
+/// their individual components.
+impl Ord for UserId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
     }
 }
 
@@ -101,7 +364,7 @@ impl Serialize for UserId {
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        serializer.serialize_str(self.as_str())
     }
 }
 
@@ -120,45 +383,72 @@ impl TryFrom<&str> for UserId {
     /// Attempts to create a new Matrix user ID from a string representation.
     ///
     /// The string must include the leading @ sigil, the localpart, a literal colon, and a valid
-    /// server name.
+    /// server name, and must not exceed 255 bytes.
     fn try_from(user_id: &str) -> Result<Self, Error> {
-        let (localpart, host, port) = parse_id('@', user_id)?;
-        let downcased_localpart = localpart.to_lowercase();
-
-        // See https://matrix.org/docs/spec/appendices#user-identifiers
-        let is_fully_conforming = downcased_localpart.bytes().all(|b| match b {
-            b'0'..=b'9' | b'a'..=b'z' | b'-' | b'.' | b'=' | b'_' | b'/' => true,
-            _ => false,
-        });
-
-        // If it's not fully conforming, check if it contains characters that are also disallowed
-        // for historical user IDs. If there are, return an error.
-        // See https://matrix.org/docs/spec/appendices#historical-user-ids
-        if !is_fully_conforming
-            && downcased_localpart
-                .bytes()
-                .any(|b| b < 0x21 || b == b':' || b > 0x7E)
-        {
-            return Err(Error::InvalidCharacters);
-        }
+        let (localpart, server_name) = parse_id('@', user_id)?;
+        let is_historical =
+            validate_localpart(localpart).map_err(|kind| Error::new(kind, user_id))?;
+
+        let (full_id, colon_idx) = format_id('@', &localpart.to_ascii_lowercase(), &server_name);
+
+        Ok(Self {
+            full_id,
+            colon_idx,
+            server_name,
+            is_historical,
+        })
+    }
+}
+
+impl TryFrom<String> for UserId {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix user ID from an owned string representation.
+    ///
+    /// This performs the same checks as the `TryFrom<&str>` implementation, but reuses the string
+    /// if it already is the canonical string representation of the user ID, apart from the case of
+    /// the localpart, which is downcased in place.
+    fn try_from(user_id: String) -> Result<Self, Error> {
+        let (mut full_id, colon_idx, server_name, is_historical) =
+            parse_owned_id('@', user_id, validate_localpart)?;
+        full_id[SIGIL_BYTES..colon_idx].make_ascii_lowercase();
 
         Ok(Self {
-            hostname: host,
-            port,
-            localpart: downcased_localpart,
-            is_historical: !is_fully_conforming,
+            full_id,
+            colon_idx,
+            server_name,
+            is_historical,
         })
     }
 }
 
+/// Checks whether a string is a valid user ID, without constructing a `UserId`.
+///
+/// This performs the same checks as `UserId::try_from`, including accepting historical user IDs.
+pub fn is_valid_user_id(user_id: &str) -> bool {
+    validation::validate(user_id).is_ok()
+}
+
+impl FromStr for UserId {
+    type Err = Error;
+
+    /// Attempts to create a new Matrix user ID from a string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::convert::TryFrom;
+    use std::{convert::TryFrom, net::Ipv6Addr};
 
-    use serde_json::{from_str, to_string};
+    use rand::{rngs::StdRng, SeedableRng};
+    use serde_json::{from_str, from_value, to_string, Value};
 
-    use super::UserId;
-    use crate::error::Error;
+    use super::{is_valid_user_id, UserId};
+    use crate::{error::ErrorKind, Alphabet, Host, UserLocalpart, DEFAULT_PORT};
 
     #[test]
     fn valid_user_id() {
@@ -174,6 +464,43 @@ mod tests {
         assert!(user_id.is_historical());
     }
 
+    #[test]
+    fn valid_historical_user_id_with_punctuation() {
+        let user_id = UserId::try_from(r##"@Carl!"#$%&'()*+,;<>?@[\]^`{|}~:example.com"##)
+            .expect("Failed to create UserId.");
+
+        assert_eq!(user_id.localpart(), r##"carl!"#$%&'()*+,;<>?@[\]^`{|}~"##);
+        assert!(user_id.is_historical());
+        assert!(is_valid_user_id(user_id.as_str()));
+    }
+
+    #[test]
+    fn uppercase_user_id_is_not_historical() {
+        let user_id =
+            UserId::try_from("@CARL.Foo_=/-:example.com").expect("Failed to create UserId.");
+
+        assert_eq!(user_id.localpart(), "carl.foo_=/-");
+        assert!(!user_id.is_historical());
+    }
+
+    #[test]
+    fn parse_valid_user_id() {
+        assert_eq!(
+            "@carl:example.com"
+                .parse::<UserId>()
+                .expect("Failed to parse UserId."),
+            UserId::try_from("@carl:example.com").expect("Failed to create UserId.")
+        );
+    }
+
+    #[test]
+    fn parse_invalid_user_id() {
+        assert_eq!(
+            "@te\nst:example.com".parse::<UserId>().unwrap_err().kind(),
+            ErrorKind::InvalidCharacters
+        );
+    }
+
     #[test]
     fn downcase_user_id() {
         assert_eq!(
@@ -194,11 +521,132 @@ mod tests {
         assert_eq!(user_id.len(), 25);
     }
 
+    #[test]
+    fn generate_seeded_user_id() {
+        let first = UserId::new_with_rng("example.com", &mut StdRng::seed_from_u64(42))
+            .expect("Failed to generate UserId.");
+        let second = UserId::new_with_rng("example.com", &mut StdRng::seed_from_u64(42))
+            .expect("Failed to generate UserId.");
+        let other = UserId::new_with_rng("example.com", &mut StdRng::seed_from_u64(43))
+            .expect("Failed to generate UserId.");
+
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+        assert!(UserId::try_from(first.as_str()).is_ok());
+    }
+
     #[test]
     fn generate_random_invalid_user_id() {
         assert!(UserId::new("").is_err());
     }
 
+    #[test]
+    fn generate_random_user_id_with_length() {
+        let user_id =
+            UserId::new_with_length("example.com", 32).expect("Failed to generate UserId.");
+
+        assert_eq!(user_id.localpart().len(), 32);
+        assert_eq!(user_id.localpart(), user_id.localpart().to_lowercase());
+    }
+
+    #[test]
+    fn generate_random_user_id_with_invalid_length() {
+        assert_eq!(
+            UserId::new_with_length("example.com", 0)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingLocalpart
+        );
+        assert_eq!(
+            UserId::new_with_length("example.com", 250)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+        assert_eq!(
+            UserId::new_with_length("example.com", usize::MAX)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+    }
+
+    #[test]
+    fn user_id_at_maximum_length() {
+        // 1 byte for the sigil and 12 bytes for ":example.com" leave 242 bytes for the localpart.
+        let user_id = format!("@{}:example.com", "a".repeat(242));
+        assert_eq!(user_id.len(), 255);
+
+        assert_eq!(
+            UserId::try_from(user_id.as_str())
+                .expect("Failed to create UserId.")
+                .as_str()
+                .len(),
+            255
+        );
+        assert!(UserId::try_from(user_id.clone()).is_ok());
+        assert!(UserId::from_parts(
+            UserLocalpart::try_from("a".repeat(242).as_str())
+                .expect("Failed to create UserLocalpart."),
+            "example.com"
+        )
+        .is_ok());
+        assert!(is_valid_user_id(&user_id));
+    }
+
+    #[test]
+    fn user_id_exceeding_maximum_length() {
+        let user_id = format!("@{}:example.com", "a".repeat(243));
+        assert_eq!(user_id.len(), 256);
+
+        assert_eq!(
+            UserId::try_from(user_id.as_str()).unwrap_err().kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+        assert_eq!(
+            UserId::try_from(user_id.clone()).unwrap_err().kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+        assert_eq!(
+            UserId::from_parts(
+                UserLocalpart::try_from("a".repeat(243).as_str())
+                    .expect("Failed to create UserLocalpart."),
+                "example.com"
+            )
+            .unwrap_err()
+            .kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+        assert!(!is_valid_user_id(&user_id));
+    }
+
+    #[test]
+    fn user_id_exceeding_maximum_length_after_normalization() {
+        // `1.1` is written as `1.0.0.1`, so the canonical form is four bytes longer.
+        let user_id = format!("@{}:1.1", "a".repeat(249));
+        assert_eq!(user_id.len(), 254);
+
+        assert_eq!(
+            UserId::try_from(user_id.as_str()).unwrap_err().kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+        assert_eq!(
+            UserId::try_from(user_id.clone()).unwrap_err().kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+        assert!(!is_valid_user_id(&user_id));
+
+        let user_id = UserId::try_from(format!("@{}:1.1", "a".repeat(246)))
+            .expect("Failed to create UserId.");
+
+        assert_eq!(user_id.as_str().len(), 255);
+        assert_eq!(
+            from_str::<UserId>(&to_string(&user_id).expect("Failed to convert UserId to JSON."))
+                .expect("Failed to convert JSON to UserId."),
+            user_id
+        );
+    }
+
     #[test]
     fn serialize_valid_user_id() {
         assert_eq!(
@@ -216,16 +664,76 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deserialize_user_id_from_owned_string() {
+        assert_eq!(
+            from_value::<UserId>(Value::String("@carl:example.com".to_string()))
+                .expect("Failed to convert JSON to UserId"),
+            UserId::try_from("@carl:example.com").expect("Failed to create UserId.")
+        );
+    }
+
+    #[test]
+    fn user_id_from_owned_string() {
+        let raw_id = "@CARL:example.com".to_string();
+        let raw_ptr = raw_id.as_ptr();
+        let user_id = UserId::try_from(raw_id).expect("Failed to create UserId.");
+
+        assert_eq!(user_id.as_str(), "@carl:example.com");
+        assert_eq!(user_id.as_str().as_ptr(), raw_ptr);
+        assert_eq!(user_id.localpart(), "carl");
+        assert_eq!(
+            UserId::try_from("@carl:example.com:8448".to_string())
+                .expect("Failed to create UserId."),
+            UserId::try_from("@carl:example.com").expect("Failed to create UserId.")
+        );
+        assert!(UserId::try_from("@a%b[irc]:example.com".to_string())
+            .expect("Failed to create UserId.")
+            .is_historical());
+
+        let error = UserId::try_from("@ca rl:example.com".to_string()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidCharacters);
+        assert_eq!(error.input(), "@ca rl:example.com");
+    }
+
+    #[test]
+    fn deserialize_invalid_user_id_from_owned_string() {
+        let error =
+            from_value::<UserId>(Value::String("@ca rl:example.com".to_string())).unwrap_err();
+
+        assert!(error.to_string().contains(
+            r#"invalid Matrix ID: localpart contains invalid characters: "@ca rl:example.com""#
+        ));
+    }
+
+    #[test]
+    fn deserialize_invalid_user_id() {
+        assert_eq!(
+            from_str::<UserId>(r#""carl:example.com""#)
+                .unwrap_err()
+                .to_string(),
+            "invalid Matrix ID: leading sigil is missing: \"carl:example.com\", \
+             expected a Matrix user ID as a string at line 1 column 18"
+        );
+    }
+
     #[test]
     fn valid_user_id_with_explicit_standard_port() {
         assert_eq!(
-            UserId::try_from("@carl:example.com:443")
+            UserId::try_from("@carl:example.com:8448")
                 .expect("Failed to create UserId.")
                 .to_string(),
             "@carl:example.com"
         );
     }
 
+    #[test]
+    fn valid_user_id_with_https_port() {
+        let user_id = UserId::try_from("@carl:example.com:443").expect("Failed to create UserId.");
+        assert_eq!(user_id.to_string(), "@carl:example.com:443");
+        assert_eq!(user_id.port(), 443);
+    }
+
     #[test]
     fn valid_user_id_with_non_standard_port() {
         let user_id = UserId::try_from("@carl:example.com:5000").expect("Failed to create UserId.");
@@ -233,40 +741,543 @@ mod tests {
         assert!(!user_id.is_historical());
     }
 
+    #[test]
+    fn valid_user_id_with_ipv6_host() {
+        let user_id = UserId::try_from("@alice:[::1]").expect("Failed to create UserId.");
+
+        assert_eq!(user_id.localpart(), "alice");
+        assert_eq!(
+            user_id.hostname(),
+            &Host::<String>::Ipv6(Ipv6Addr::LOCALHOST)
+        );
+        assert_eq!(user_id.port(), DEFAULT_PORT);
+        assert_eq!(user_id.to_string(), "@alice:[::1]");
+    }
+
+    #[test]
+    fn server_name_of_user_id() {
+        assert_eq!(
+            UserId::try_from("@carl:example.com")
+                .expect("Failed to create UserId.")
+                .server_name(),
+            "example.com"
+        );
+        assert_eq!(
+            UserId::try_from("@carl:example.com:5000")
+                .expect("Failed to create UserId.")
+                .server_name(),
+            "example.com:5000"
+        );
+    }
+
+    #[test]
+    fn default_user_id_port() {
+        assert_eq!(
+            UserId::try_from("@carl:example.com")
+                .expect("Failed to create UserId.")
+                .port(),
+            DEFAULT_PORT
+        );
+    }
+
     #[test]
     fn invalid_characters_in_user_id_localpart() {
         assert_eq!(
-            UserId::try_from("@te\nst:example.com").unwrap_err(),
-            Error::InvalidCharacters
+            UserId::try_from("@te\nst:example.com").unwrap_err().kind(),
+            ErrorKind::InvalidCharacters
+        );
+    }
+
+    #[test]
+    fn multibyte_characters_in_user_id_localpart() {
+        assert_eq!(
+            UserId::try_from("@café:example.com").unwrap_err().kind(),
+            ErrorKind::InvalidCharacters
+        );
+        assert_eq!(
+            UserId::try_from("@老虎:example.com").unwrap_err().kind(),
+            ErrorKind::InvalidCharacters
+        );
+    }
+
+    #[test]
+    fn multibyte_characters_in_user_id_host() {
+        let user_id = UserId::try_from("@carl:café.example").expect("Failed to create UserId.");
+
+        assert_eq!(user_id.localpart(), "carl");
+        assert_eq!(user_id.to_string(), "@carl:xn--caf-dma.example");
+    }
+
+    #[test]
+    fn missing_user_id_localpart() {
+        assert_eq!(
+            UserId::try_from("@:example.com").unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
         );
     }
 
     #[test]
     fn missing_user_id_sigil() {
         assert_eq!(
-            UserId::try_from("carl:example.com").unwrap_err(),
-            Error::MissingSigil
+            UserId::try_from("carl:example.com").unwrap_err().kind(),
+            ErrorKind::MissingSigil
         );
     }
 
     #[test]
     fn missing_user_id_delimiter() {
         assert_eq!(
-            UserId::try_from("@carl").unwrap_err(),
-            Error::MissingDelimiter
+            UserId::try_from("@carl").unwrap_err().kind(),
+            ErrorKind::MissingDelimiter
         );
     }
 
     #[test]
     fn invalid_user_id_host() {
-        assert_eq!(UserId::try_from("@carl:/").unwrap_err(), Error::InvalidHost);
+        assert_eq!(
+            UserId::try_from("@carl:/").unwrap_err().kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+
+    #[test]
+    fn invalid_user_id_host_with_path() {
+        assert_eq!(
+            UserId::try_from("@carl:example.com/path")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidHost
+        );
     }
 
     #[test]
     fn invalid_user_id_port() {
+        for id in &[
+            "@carl:example.com:notaport",
+            "@carl:example.com:65536",
+            "@carl:example.com:0",
+        ] {
+            assert_eq!(
+                UserId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::InvalidPort
+            );
+        }
+    }
+
+    #[test]
+    fn user_ids_are_ordered_by_string_representation() {
+        let alice = UserId::try_from("@alice:z.example.com").expect("Failed to create UserId.");
+        let bob = UserId::try_from("@bob:a.example.com").expect("Failed to create UserId.");
+
+        assert!(alice < bob);
+        assert!(bob > alice);
+    }
+
+    #[test]
+    fn user_id_preserving_port() {
+        for id in &[
+            "@carl:example.com",
+            "@carl:example.com:8448",
+            "@carl:example.com:5000",
+        ] {
+            assert_eq!(
+                UserId::try_from(*id)
+                    .expect("Failed to create UserId.")
+                    .to_string_preserving_port(),
+                *id
+            );
+        }
+    }
+
+    #[test]
+    fn user_id_preserving_port_normalizes_host() {
+        assert_eq!(
+            UserId::try_from("@a:EXAMPLE.com.:8448")
+                .expect("Failed to create UserId.")
+                .to_string_preserving_port(),
+            "@a:example.com:8448"
+        );
+        assert_eq!(
+            UserId::try_from("@a:[0:0::1]:5000")
+                .expect("Failed to create UserId.")
+                .to_string_preserving_port(),
+            "@a:[::1]:5000"
+        );
+    }
+
+    #[test]
+    fn is_valid_user_id_matches_try_from() {
+        for user_id in &[
+            "@carl:example.com",
+            "@CARL:example.com",
+            "@a%b[irc]:example.com",
+            "@carl:[::1]:5000",
+            "@ca rl:example.com",
+            "@carl:example.com/path",
+            "@:example.com",
+            "carl:example.com",
+            "@carl",
+        ] {
+            assert_eq!(
+                is_valid_user_id(user_id),
+                UserId::try_from(*user_id).is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn user_id_from_parts() {
+        let localpart = UserLocalpart::try_from("carl").expect("Failed to create UserLocalpart.");
+        let user_id =
+            UserId::from_parts(localpart, "example.com:5000").expect("Failed to create UserId.");
+
+        assert_eq!(
+            user_id,
+            UserId::try_from("@carl:example.com:5000").expect("Failed to create UserId.")
+        );
+        assert_eq!(user_id.localpart(), "carl");
+        assert_eq!(user_id.port(), 5000);
+    }
+
+    #[test]
+    fn user_id_from_parts_with_invalid_server_name() {
+        let localpart = UserLocalpart::try_from("carl").expect("Failed to create UserLocalpart.");
+        let error = UserId::from_parts(localpart, "example.com/path").unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidHost);
+        assert_eq!(error.input(), "@carl:example.com/path");
+    }
+
+    #[test]
+    fn user_id_is_ip_literal() {
+        assert!(!UserId::try_from("@carl:example.com")
+            .expect("Failed to create UserId.")
+            .is_ip_literal());
+        assert!(UserId::try_from("@carl:1.2.3.4:5000")
+            .expect("Failed to create UserId.")
+            .is_ip_literal());
+        assert!(UserId::try_from("@carl:[::1]")
+            .expect("Failed to create UserId.")
+            .is_ip_literal());
+    }
+
+    #[test]
+    fn user_id_parts() {
+        assert_eq!(
+            UserId::try_from("@carl:example.com:8448")
+                .expect("Failed to create UserId.")
+                .parts(),
+            ("carl", "example.com".to_string())
+        );
+        assert_eq!(
+            UserId::try_from("@carl:example.com:5000")
+                .expect("Failed to create UserId.")
+                .parts(),
+            ("carl", "example.com:5000".to_string())
+        );
+    }
+
+    #[test]
+    fn user_id_into_localpart() {
+        let localpart = UserId::try_from("@CARL:example.com")
+            .expect("Failed to create UserId.")
+            .into_localpart();
+
+        assert_eq!(
+            localpart,
+            UserLocalpart::try_from("carl").expect("Failed to create UserLocalpart.")
+        );
+        assert!(!localpart.is_historical());
+        assert!(UserId::try_from("@a%b[irc]:example.com")
+            .expect("Failed to create UserId.")
+            .into_localpart()
+            .is_historical());
+    }
+
+    #[test]
+    fn user_id_into_parts() {
+        let (localpart, server_name) = UserId::try_from("@carl:[::1]:5000")
+            .expect("Failed to create UserId.")
+            .into_parts();
+
+        assert_eq!(localpart.as_str(), "carl");
         assert_eq!(
-            UserId::try_from("@carl:example.com:notaport").unwrap_err(),
-            Error::InvalidHost
+            server_name.host(),
+            &Host::<String>::Ipv6(Ipv6Addr::LOCALHOST)
         );
+        assert_eq!(server_name.port(), 5000);
+    }
+
+    #[test]
+    fn user_id_with_surrounding_whitespace() {
+        for id in &[
+            " @carl:example.com",
+            "@carl:example.com ",
+            "\t@carl:example.com",
+            "@carl:example.com\t",
+        ] {
+            assert_eq!(
+                UserId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::SurroundingWhitespace
+            );
+            assert_eq!(
+                UserId::try_from(id.to_string()).unwrap_err().kind(),
+                ErrorKind::SurroundingWhitespace
+            );
+        }
+    }
+
+    #[test]
+    fn user_id_with_server_name() {
+        let id = UserId::try_from("@carl:example.com").expect("Failed to create UserId.");
+        let moved = id
+            .with_server_name("example.org:5000")
+            .expect("Failed to create UserId.");
+
+        assert_eq!(moved.to_string(), "@carl:example.org:5000");
+        assert_eq!(moved.parts(), ("carl", "example.org:5000".to_string()));
+        assert_eq!(id.to_string(), "@carl:example.com");
+        assert_eq!(
+            id.with_server_name("exa mple.org").unwrap_err().kind(),
+            ErrorKind::InvalidHost
+        );
+    }
+
+    #[test]
+    fn user_id_host_str() {
+        assert_eq!(
+            UserId::try_from("@carl:Example.com")
+                .expect("Failed to create UserId.")
+                .host_str(),
+            "example.com"
+        );
+        assert_eq!(
+            UserId::try_from("@carl:example.com:5000")
+                .expect("Failed to create UserId.")
+                .host_str(),
+            "example.com"
+        );
+        assert_eq!(
+            UserId::try_from("@carl:1.2.3.4:8448")
+                .expect("Failed to create UserId.")
+                .host_str(),
+            "1.2.3.4"
+        );
+        assert_eq!(
+            UserId::try_from("@carl:[::1]")
+                .expect("Failed to create UserId.")
+                .host_str(),
+            "[::1]"
+        );
+        assert_eq!(
+            UserId::try_from("@carl:[0:0::1]:5000")
+                .expect("Failed to create UserId.")
+                .host_str(),
+            "[::1]"
+        );
+    }
+
+    #[test]
+    fn user_id_from_host() {
+        let user_id = UserId::from_host(
+            UserLocalpart::try_from("a%b[irc]").expect("Failed to create UserLocalpart."),
+            Host::Domain("example.com".to_string()),
+            8448,
+        )
+        .expect("Failed to create UserId.");
+
+        assert_eq!(user_id, "@a%b[irc]:example.com");
+        assert_eq!(
+            user_id.to_string_preserving_port(),
+            "@a%b[irc]:example.com:8448"
+        );
+        assert!(user_id.is_historical());
+    }
+
+    #[test]
+    fn generate_random_user_id_with_alphabet() {
+        for &(alphabet, chars) in &[
+            (
+                Alphabet::LowercaseAlphanumeric,
+                "abcdefghijklmnopqrstuvwxyz0123456789",
+            ),
+            (
+                Alphabet::Base32Unambiguous,
+                "0123456789abcdefghjkmnpqrstvwxyz",
+            ),
+        ] {
+            let generated_id = UserId::new_with_alphabet("example.com", alphabet)
+                .expect("Failed to generate UserId.");
+            let id_str = generated_id.to_string();
+
+            assert!(id_str.ends_with(":example.com"));
+            assert_eq!(id_str.len(), 25);
+            assert!(generated_id.localpart().chars().all(|c| chars.contains(c)));
+            assert_eq!(
+                UserId::try_from(id_str.as_str()).expect("Failed to create UserId."),
+                generated_id
+            );
+        }
+    }
+
+    #[test]
+    fn user_id_with_fully_qualified_server_name() {
+        let user_id = UserId::try_from("@alice:example.com.").expect("Failed to create UserId.");
+
+        assert_eq!(user_id.as_str(), "@alice:example.com");
+        assert_eq!(user_id.server_name(), "example.com");
+        assert_eq!(
+            user_id,
+            UserId::try_from("@alice:example.com").expect("Failed to create UserId.")
+        );
+        assert_eq!(
+            UserId::try_from("@alice:example.com.".to_string())
+                .expect("Failed to create UserId.")
+                .as_str(),
+            "@alice:example.com"
+        );
+        assert!(is_valid_user_id("@alice:example.com."));
+    }
+
+    #[test]
+    fn user_id_with_invalid_trailing_dots() {
+        for user_id in &["@alice:.", "@alice:example.com.."] {
+            assert_eq!(
+                UserId::try_from(*user_id).unwrap_err().kind(),
+                ErrorKind::InvalidHost
+            );
+            assert!(!is_valid_user_id(user_id));
+        }
+    }
+
+    #[test]
+    fn user_id_with_internationalized_server_name() {
+        let unicode = UserId::try_from("@alice:café.example").expect("Failed to create UserId.");
+        let punycode =
+            UserId::try_from("@alice:xn--caf-dma.example").expect("Failed to create UserId.");
+
+        assert_eq!(unicode, punycode);
+        assert_eq!(unicode.as_str(), "@alice:xn--caf-dma.example");
+        assert_eq!(unicode.host_str(), "xn--caf-dma.example");
+    }
+
+    #[test]
+    fn sigil_only_user_id() {
+        assert_eq!(
+            UserId::try_from("@").unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+        assert_eq!(
+            UserId::try_from("@".to_string()).unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+        assert!(!is_valid_user_id("@"));
+    }
+
+    #[test]
+    fn user_id_with_leading_multibyte_character() {
+        for id in &["é:example.com", "老虎:example.com", "éa:example.com"] {
+            assert_eq!(
+                UserId::try_from(*id).unwrap_err().kind(),
+                ErrorKind::MissingSigil
+            );
+            assert_eq!(
+                UserId::try_from(id.to_string()).unwrap_err().kind(),
+                ErrorKind::MissingSigil
+            );
+            assert!(!is_valid_user_id(id));
+        }
+    }
+
+    #[test]
+    fn user_id_into_string() {
+        let id = UserId::try_from("@Carl:example.com:8448").expect("Failed to create UserId.");
+        let string = id.to_string();
+
+        assert_eq!(id.into_string(), string);
+    }
+
+    #[test]
+    fn user_id_with_ipv6_zone_identifier() {
+        assert_eq!(
+            UserId::try_from("@alice:[fe80::1%25eth0]")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidHost
+        );
+        assert!(!is_valid_user_id("@alice:[fe80::1%25eth0]"));
+        assert!(UserId::try_from("@alice:[fe80::1]").is_ok());
+    }
+
+    #[test]
+    fn user_id_explicit_port() {
+        let user_id = |s| UserId::try_from(s).expect("Failed to create UserId.");
+
+        assert_eq!(user_id("@carl:example.com").explicit_port(), None);
+        assert_eq!(
+            user_id("@carl:example.com:8448").explicit_port(),
+            Some(8448)
+        );
+        assert_eq!(
+            user_id("@carl:example.com:5000").explicit_port(),
+            Some(5000)
+        );
+        assert_eq!(
+            user_id("@carl:example.com"),
+            user_id("@carl:example.com:8448")
+        );
+    }
+
+    #[test]
+    fn redacted_user_id() {
+        let user_id = UserId::try_from("@carl:example.com:5000").expect("Failed to create UserId.");
+
+        assert_eq!(user_id.redacted(), "@ca***:example.com:5000");
+        assert_eq!(user_id.redacted_with(0), "@***:example.com:5000");
+        assert_eq!(user_id.redacted_with(3), "@car***:example.com:5000");
+    }
+
+    #[test]
+    fn redacted_long_user_id() {
+        let user_id = UserId::try_from(format!("@{}:example.com", "a".repeat(200)))
+            .expect("Failed to create UserId.");
+
+        assert_eq!(user_id.redacted(), "@aa***:example.com");
+    }
+
+    #[test]
+    fn redacted_short_user_id() {
+        let user_id = UserId::try_from("@c:example.com").expect("Failed to create UserId.");
+
+        assert_eq!(user_id.redacted(), "@***:example.com");
+        assert_eq!(
+            UserId::try_from("@ca:example.com")
+                .expect("Failed to create UserId.")
+                .redacted(),
+            "@c***:example.com"
+        );
+        assert_eq!(user_id.redacted_with(5), "@***:example.com");
+    }
+
+    #[test]
+    fn has_localpart_on_different_servers() {
+        let localpart = UserLocalpart::try_from("Carl").expect("Failed to create UserLocalpart.");
+
+        for user_id in &["@carl:example.com", "@CARL:example.org:5000", "@carl:[::1]"] {
+            assert!(UserId::try_from(*user_id)
+                .expect("Failed to create UserId.")
+                .has_localpart(&localpart));
+        }
+    }
+
+    #[test]
+    fn has_different_localpart_on_same_server() {
+        let user_id = UserId::try_from("@carl:example.com").expect("Failed to create UserId.");
+
+        for localpart in &["carla", "car", "alice"] {
+            assert!(!user_id.has_localpart(
+                &UserLocalpart::try_from(*localpart).expect("Failed to create UserLocalpart.")
+            ));
+        }
     }
 }
@@ -0,0 +1,380 @@
+//! Matrix user ID localparts.
+
+use alloc::string::String;
+use core::{
+    convert::TryFrom,
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
+#[cfg(feature = "diesel")]
+use diesel::sql_types::Text;
+use ruma_identifiers_validation::user_id::validate_localpart;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "std")]
+use crate::generate_localpart;
+use crate::{
+    deserialize_id,
+    error::{Error, ErrorKind},
+    user_id::UserId,
+    MAX_BYTES, SIGIL_BYTES,
+};
+
+/// The maximum length of a user ID localpart in bytes.
+///
+/// A localpart can't know the server name it will be combined with, so this leaves room for the
+/// sigil, the colon and a server name of a single character, like `@localpart:a`. Whether a
+/// localpart fits with a specific server name is only checked when creating the `UserId`.
+const MAX_LOCALPART_BYTES: usize = MAX_BYTES - SIGIL_BYTES - 2;
+
+/// The localpart of a Matrix user ID, i.e. the part between the leading @ sigil and the colon.
+///
+/// A `UserLocalpart` is generated randomly or converted from a string slice, and can be converted
+/// back into a string as needed. Like the localpart of a `UserId`, it is downcased.
+///
+/// A localpart may be at most 252 bytes long, which is the longest one that fits into a user ID
+/// of at most 255 bytes, like `@localpart:a` with a single-character server name.
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use ruma_identifiers::UserLocalpart;
+/// assert_eq!(UserLocalpart::try_from("Carl").unwrap().as_str(), "carl");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "diesel", derive(FromSqlRow, QueryId, AsExpression, SqlType))]
+#[cfg_attr(feature = "diesel", sql_type = "Text")]
+pub struct UserLocalpart {
+    /// The downcased localpart.
+    localpart: String,
+    /// Whether this localpart is a historical one.
+    ///
+    /// See `UserId::is_historical`.
+    is_historical: bool,
+}
+
+impl UserLocalpart {
+    /// Generates a random `UserLocalpart` consisting of 12 random lowercase ASCII letters and
+    /// digits.
+    #[cfg(feature = "std")]
+    pub fn new() -> Self {
+        Self {
+            localpart: generate_localpart(12).to_lowercase(),
+            is_historical: false,
+        }
+    }
+
+    /// Creates a `UserLocalpart` from a string that is already known to be a valid, downcased
+    /// localpart, e.g. because it was taken out of a `UserId`.
+    pub(crate) fn from_valid(localpart: String, is_historical: bool) -> Self {
+        Self {
+            localpart,
+            is_historical,
+        }
+    }
+
+    /// Returns the localpart as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.localpart
+    }
+
+    /// Converts the localpart into its string representation, without allocating.
+    ///
+    /// This returns the same string as `as_str` and `to_string`, but takes the stored string
+    /// instead of copying it.
+    pub fn into_string(self) -> String {
+        self.localpart
+    }
+
+    /// Whether this localpart is a historical one, i.e. one that doesn't conform to the latest
+    /// specification of the user ID grammar but is still accepted because it was previously
+    /// allowed.
+    pub fn is_historical(&self) -> bool {
+        self.is_historical
+    }
+
+    /// Creates a `UserId` with this localpart and the given server name of the user's homeserver.
+    ///
+    /// This is equivalent to `UserId::from_parts`: the localpart isn't validated again, only the
+    /// server name is, and whether the localpart is historical carries over to the user ID.
+    pub fn to_user_id(&self, server_name: &str) -> Result<UserId, Error> {
+        UserId::from_parts(self.clone(), server_name)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for UserLocalpart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for UserLocalpart {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(&self.localpart)
+    }
+}
+
+impl Serialize for UserLocalpart {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.localpart)
+    }
+}
+
+impl<'de> Deserialize<'de> for UserLocalpart {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_id(deserializer, "a Matrix user ID localpart as a string")
+    }
+}
+
+/// Validates a borrowed or owned user ID localpart, only allocating if it is valid and borrowed.
+fn parse_localpart<S>(localpart: S) -> Result<UserLocalpart, Error>
+where
+    S: AsRef<str> + Into<String>,
+{
+    let raw_localpart = localpart.as_ref();
+
+    if raw_localpart.is_empty() {
+        return Err(Error::new(ErrorKind::MissingLocalpart, raw_localpart));
+    }
+
+    if raw_localpart.len() > MAX_LOCALPART_BYTES {
+        return Err(Error::new(ErrorKind::MaximumLengthExceeded, raw_localpart));
+    }
+
+    let is_historical =
+        validate_localpart(raw_localpart).map_err(|kind| Error::new(kind, raw_localpart))?;
+
+    let mut localpart = localpart.into();
+    localpart.make_ascii_lowercase();
+
+    Ok(UserLocalpart {
+        localpart,
+        is_historical,
+    })
+}
+
+impl TryFrom<&str> for UserLocalpart {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix user ID localpart from a string representation.
+    ///
+    /// The string must not include the leading @ sigil or the server name.
+    fn try_from(localpart: &str) -> Result<Self, Error> {
+        parse_localpart(localpart)
+    }
+}
+
+impl TryFrom<String> for UserLocalpart {
+    type Error = Error;
+
+    /// Attempts to create a new Matrix user ID localpart from an owned string representation.
+    ///
+    /// This performs the same checks as the `TryFrom<&str>` implementation, but reuses the string,
+    /// downcasing it in place.
+    fn try_from(localpart: String) -> Result<Self, Error> {
+        parse_localpart(localpart)
+    }
+}
+
+impl FromStr for UserLocalpart {
+    type Err = Error;
+
+    /// Attempts to create a new Matrix user ID localpart from a string representation.
+    ///
+    /// This is equivalent to the `TryFrom<&str>` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use serde_json::{from_str, to_string};
+
+    use super::UserLocalpart;
+    use crate::{error::ErrorKind, UserId};
+
+    #[test]
+    fn valid_user_localpart() {
+        assert_eq!(
+            UserLocalpart::try_from("carl")
+                .expect("Failed to create UserLocalpart.")
+                .to_string(),
+            "carl"
+        );
+    }
+
+    #[test]
+    fn valid_historical_user_localpart() {
+        let localpart =
+            UserLocalpart::try_from("a%b[irc]").expect("Failed to create UserLocalpart.");
+
+        assert_eq!(localpart.as_str(), "a%b[irc]");
+        assert!(localpart.is_historical());
+    }
+
+    #[test]
+    fn valid_historical_user_localpart_with_uppercase_and_punctuation() {
+        let localpart =
+            UserLocalpart::try_from("IRC|Carl[away]").expect("Failed to create UserLocalpart.");
+
+        assert_eq!(localpart.as_str(), "irc|carl[away]");
+        assert!(localpart.is_historical());
+    }
+
+    #[test]
+    fn downcase_user_localpart() {
+        assert_eq!(
+            UserLocalpart::try_from("CARL")
+                .expect("Failed to create UserLocalpart.")
+                .as_str(),
+            "carl"
+        );
+    }
+
+    #[test]
+    fn user_localpart_from_owned_string() {
+        let raw_localpart = "CARL".to_string();
+        let raw_ptr = raw_localpart.as_ptr();
+        let localpart =
+            UserLocalpart::try_from(raw_localpart).expect("Failed to create UserLocalpart.");
+
+        assert_eq!(localpart.as_str(), "carl");
+        assert_eq!(localpart.as_str().as_ptr(), raw_ptr);
+    }
+
+    #[test]
+    fn generate_random_valid_user_localpart() {
+        let localpart = UserLocalpart::new();
+
+        assert_eq!(localpart.as_str().len(), 12);
+        assert!(!localpart.is_historical());
+        assert!(UserLocalpart::try_from(localpart.as_str()).is_ok());
+    }
+
+    #[test]
+    fn serialize_valid_user_localpart() {
+        assert_eq!(
+            to_string(&UserLocalpart::try_from("carl").expect("Failed to create UserLocalpart."))
+                .expect("Failed to convert UserLocalpart to JSON."),
+            r#""carl""#
+        );
+    }
+
+    #[test]
+    fn deserialize_valid_user_localpart() {
+        assert_eq!(
+            from_str::<UserLocalpart>(r#""carl""#)
+                .expect("Failed to convert JSON to UserLocalpart"),
+            UserLocalpart::try_from("carl").expect("Failed to create UserLocalpart.")
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid_user_localpart() {
+        let error = from_str::<UserLocalpart>(r#""carl:example.com""#).unwrap_err();
+
+        assert!(error
+            .to_string()
+            .contains("expected a Matrix user ID localpart as a string"));
+    }
+
+    #[test]
+    fn invalid_characters_in_user_localpart() {
+        assert_eq!(
+            UserLocalpart::try_from("ca rl").unwrap_err().kind(),
+            ErrorKind::InvalidCharacters
+        );
+        assert_eq!(
+            UserLocalpart::try_from("carl:example.com")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidCharacters
+        );
+    }
+
+    #[test]
+    fn empty_user_localpart() {
+        assert_eq!(
+            UserLocalpart::try_from("").unwrap_err().kind(),
+            ErrorKind::MissingLocalpart
+        );
+    }
+
+    #[test]
+    fn user_localpart_to_user_id() {
+        let localpart = UserLocalpart::try_from("carl").expect("Failed to create UserLocalpart.");
+        let user_id = localpart
+            .to_user_id("example.com:5000")
+            .expect("Failed to create UserId.");
+
+        assert_eq!(
+            user_id,
+            UserId::try_from("@carl:example.com:5000").expect("Failed to create UserId.")
+        );
+        assert!(!user_id.is_historical());
+        assert!(UserLocalpart::try_from("Ca\"rl")
+            .expect("Failed to create UserLocalpart.")
+            .to_user_id("example.com")
+            .expect("Failed to create UserId.")
+            .is_historical());
+    }
+
+    #[test]
+    fn user_localpart_to_user_id_with_invalid_server_name() {
+        let error = UserLocalpart::try_from("carl")
+            .expect("Failed to create UserLocalpart.")
+            .to_user_id("exa mple.com")
+            .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidHost);
+        assert_eq!(error.input(), "@carl:exa mple.com");
+    }
+
+    #[test]
+    fn user_localpart_into_string() {
+        let id = UserLocalpart::try_from("Carl").expect("Failed to create UserLocalpart.");
+        let string = id.to_string();
+
+        assert_eq!(id.into_string(), string);
+    }
+
+    #[test]
+    fn user_localpart_with_maximum_length() {
+        let localpart = UserLocalpart::try_from("a".repeat(252).as_str())
+            .expect("Failed to create UserLocalpart.");
+
+        assert_eq!(localpart.as_str().len(), 252);
+        assert_eq!(
+            localpart
+                .to_user_id("a")
+                .expect("Failed to create UserId.")
+                .as_str()
+                .len(),
+            255
+        );
+    }
+
+    #[test]
+    fn user_localpart_exceeding_maximum_length() {
+        assert_eq!(
+            UserLocalpart::try_from("a".repeat(253).as_str())
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+        assert_eq!(
+            UserLocalpart::try_from("a".repeat(253)).unwrap_err().kind(),
+            ErrorKind::MaximumLengthExceeded
+        );
+    }
+}
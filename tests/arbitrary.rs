@@ -0,0 +1,111 @@
+//! Checks that the identifiers generated with arbitrary are valid.
+
+#![cfg(feature = "arbitrary")]
+
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+    fmt::{Debug, Display},
+};
+
+use arbitrary::{Arbitrary, Unstructured};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use ruma_identifiers::{
+    Error, EventId, EventIdFormat, Host, RoomAlias, RoomAliasId, RoomId, RoomVersionId, ServerName,
+    UserId, UserLocalpart, DEFAULT_PORT,
+};
+
+/// The number of identifiers generated of each type.
+const ITERATIONS: u64 = 2000;
+
+/// Generates identifiers from random bytes, returning them after checking that each of them is
+/// parsed back into an equal identifier from its string representation.
+fn generate<T>() -> Vec<T>
+where
+    T: for<'a> Arbitrary<'a>
+        + for<'a> TryFrom<&'a str, Error = Error>
+        + Debug
+        + Display
+        + PartialEq,
+{
+    let mut rng = StdRng::seed_from_u64(0);
+
+    (0..ITERATIONS)
+        .map(|i| {
+            // Some of the inputs are too short for the generators, which then use default values.
+            let mut bytes = vec![0; (i % 512) as usize];
+            rng.fill_bytes(&mut bytes);
+
+            let value =
+                T::arbitrary(&mut Unstructured::new(&bytes)).expect("Failed to generate value.");
+            let parsed =
+                T::try_from(value.to_string().as_str()).expect("Failed to parse generated value.");
+            assert_eq!(parsed, value);
+
+            value
+        })
+        .collect()
+}
+
+#[test]
+fn generated_identifiers_are_valid() {
+    generate::<UserLocalpart>();
+    generate::<UserId>();
+    generate::<RoomId>();
+    generate::<RoomAlias>();
+    generate::<RoomAliasId>();
+    generate::<EventId>();
+    generate::<RoomVersionId>();
+}
+
+#[test]
+fn generated_server_names_cover_hosts_and_ports() {
+    let server_names = generate::<ServerName>();
+
+    let hosts: HashSet<_> = server_names
+        .iter()
+        .map(|server_name| match server_name.host() {
+            Host::Domain(_) => "domain",
+            Host::Ipv4(_) => "IPv4",
+            Host::Ipv6(_) => "IPv6",
+        })
+        .collect();
+    assert_eq!(hosts.len(), 3);
+
+    let ports: HashSet<_> = server_names
+        .iter()
+        .map(|server_name| {
+            if server_name.port() != DEFAULT_PORT {
+                "other"
+            } else if server_name
+                .to_string_preserving_port()
+                .ends_with(&format!(":{}", DEFAULT_PORT))
+            {
+                "explicit default"
+            } else {
+                "default"
+            }
+        })
+        .collect();
+    assert_eq!(ports.len(), 3);
+}
+
+#[test]
+fn generated_event_ids_cover_formats() {
+    let event_ids = generate::<EventId>();
+    let has_format = |format, other_format| {
+        event_ids.iter().any(|event_id| {
+            event_id.matches_format(format) && !event_id.matches_format(other_format)
+        })
+    };
+
+    assert!(has_format(EventIdFormat::Original, EventIdFormat::Base64));
+    assert!(has_format(
+        EventIdFormat::Base64,
+        EventIdFormat::UrlSafeBase64
+    ));
+    assert!(has_format(
+        EventIdFormat::UrlSafeBase64,
+        EventIdFormat::Base64
+    ));
+}
@@ -0,0 +1,119 @@
+//! Checks that equivalent spellings of identifiers have the same canonical string representation.
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    convert::TryFrom,
+    hash::{Hash, Hasher},
+};
+
+use ruma_identifiers::{EventId, RoomAliasId, RoomId, ServerName, UserId};
+
+#[test]
+fn equivalent_user_ids_are_canonicalized() {
+    for (spelling, canonical) in &[
+        ("@Carl:Example.com:8448", "@carl:example.com"),
+        ("@carl:example.com.:05000", "@carl:example.com:5000"),
+        ("@carl:café.example", "@carl:xn--caf-dma.example"),
+        ("@carl:127.1", "@carl:127.0.0.1"),
+        ("@carl:[0:0::1]:8448", "@carl:[::1]"),
+    ] {
+        let user_id = UserId::try_from(*spelling).expect("Failed to create UserId.");
+
+        assert_eq!(user_id.as_str(), *canonical);
+        assert_eq!(user_id.to_string(), *canonical);
+        assert_eq!(
+            user_id,
+            UserId::try_from(*canonical).expect("Failed to create UserId.")
+        );
+    }
+}
+
+#[test]
+fn equivalent_ids_have_identical_canonical_output() {
+    assert_eq!(
+        RoomId::try_from("!29fhd83h92h0:EXAMPLE.com:8448")
+            .expect("Failed to create RoomId.")
+            .to_string(),
+        RoomId::try_from("!29fhd83h92h0:example.com")
+            .expect("Failed to create RoomId.")
+            .to_string()
+    );
+    assert_eq!(
+        EventId::try_from("$39hvsi03hlne:xn--caf-dma.example")
+            .expect("Failed to create EventId.")
+            .to_string(),
+        EventId::try_from("$39hvsi03hlne:CAFÉ.example.")
+            .expect("Failed to create EventId.")
+            .to_string()
+    );
+    assert_eq!(
+        ServerName::try_from("[0::1]:8448")
+            .expect("Failed to create ServerName.")
+            .to_string(),
+        "[::1]"
+    );
+}
+
+#[test]
+fn localparts_other_than_user_localparts_are_kept() {
+    assert_eq!(
+        RoomId::try_from("!29FHD83h92h0:Example.com")
+            .expect("Failed to create RoomId.")
+            .as_str(),
+        "!29FHD83h92h0:example.com"
+    );
+    assert_eq!(
+        RoomAliasId::try_from("#Ruma:Example.com")
+            .expect("Failed to create RoomAliasId.")
+            .as_str(),
+        "#Ruma:example.com"
+    );
+}
+
+#[test]
+fn port_is_preserved_on_request() {
+    assert_eq!(
+        UserId::try_from("@carl:example.com:8448")
+            .expect("Failed to create UserId.")
+            .to_string_preserving_port(),
+        "@carl:example.com:8448"
+    );
+}
+
+fn hash<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn domain_case_variants_are_equal_and_hash_identically() {
+    let user_id = UserId::try_from("@alice:Example.COM").expect("Failed to create UserId.");
+    let lowercase_user_id =
+        UserId::try_from("@alice:example.com").expect("Failed to create UserId.");
+
+    assert_eq!(user_id, lowercase_user_id);
+    assert_eq!(hash(&user_id), hash(&lowercase_user_id));
+
+    let server_name =
+        ServerName::try_from("EXAMPLE.com:5000").expect("Failed to create ServerName.");
+    let lowercase_server_name =
+        ServerName::try_from("example.com:5000").expect("Failed to create ServerName.");
+
+    assert_eq!(server_name, lowercase_server_name);
+    assert_eq!(hash(&server_name), hash(&lowercase_server_name));
+}
+
+#[test]
+fn ip_literals_are_unaffected_by_case_normalization() {
+    assert_eq!(
+        UserId::try_from("@alice:127.0.0.1")
+            .expect("Failed to create UserId.")
+            .as_str(),
+        "@alice:127.0.0.1"
+    );
+    assert_eq!(
+        UserId::try_from("@alice:[::ABCD]").expect("Failed to create UserId."),
+        UserId::try_from("@alice:[::abcd]").expect("Failed to create UserId.")
+    );
+}
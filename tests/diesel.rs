@@ -0,0 +1,110 @@
+//! Round-trips identifiers through an SQLite database with Diesel.
+
+#![cfg(feature = "diesel")]
+// `table!` and the derives of diesel 1.x implement traits inside of anonymous constants.
+#![allow(unknown_lints, non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use std::convert::TryFrom;
+
+use diesel::{
+    insert_into, sql_query, Connection, ExpressionMethods, QueryDsl, RunQueryDsl, SqliteConnection,
+};
+use ruma_identifiers::{EventId, RoomAliasId, RoomId, RoomVersionId, UserId};
+
+table! {
+    identifiers (id) {
+        id -> Integer,
+        event_id -> Text,
+        room_id -> Text,
+        room_alias_id -> Text,
+        user_id -> Text,
+        room_version_id -> Text,
+    }
+}
+
+fn connection() -> SqliteConnection {
+    let connection =
+        SqliteConnection::establish(":memory:").expect("Failed to open SQLite database.");
+    sql_query(
+        "CREATE TABLE identifiers (
+            id INTEGER PRIMARY KEY NOT NULL,
+            event_id TEXT NOT NULL,
+            room_id TEXT NOT NULL,
+            room_alias_id TEXT NOT NULL,
+            user_id TEXT NOT NULL,
+            room_version_id TEXT NOT NULL
+        )",
+    )
+    .execute(&connection)
+    .expect("Failed to create table.");
+
+    connection
+}
+
+#[test]
+fn round_trip_through_sqlite() {
+    let connection = connection();
+
+    let event_id =
+        EventId::try_from("$39hvsi03hlne:example.com").expect("Failed to create EventId.");
+    let room_id = RoomId::try_from("!29fhd83h92h0:example.com").expect("Failed to create RoomId.");
+    let room_alias_id =
+        RoomAliasId::try_from("#ruma:example.com").expect("Failed to create RoomAliasId.");
+    let user_id = UserId::try_from("@carl:example.com:5000").expect("Failed to create UserId.");
+    let room_version_id = RoomVersionId::version_5();
+
+    insert_into(identifiers::table)
+        .values((
+            identifiers::event_id.eq(&event_id),
+            identifiers::room_id.eq(&room_id),
+            identifiers::room_alias_id.eq(&room_alias_id),
+            identifiers::user_id.eq(&user_id),
+            identifiers::room_version_id.eq(&room_version_id),
+        ))
+        .execute(&connection)
+        .expect("Failed to insert identifiers.");
+
+    let loaded = identifiers::table
+        .select((
+            identifiers::event_id,
+            identifiers::room_id,
+            identifiers::room_alias_id,
+            identifiers::user_id,
+            identifiers::room_version_id,
+        ))
+        .first::<(EventId, RoomId, RoomAliasId, UserId, RoomVersionId)>(&connection)
+        .expect("Failed to load identifiers.");
+
+    assert_eq!(
+        loaded,
+        (event_id, room_id, room_alias_id, user_id, room_version_id)
+    );
+}
+
+#[test]
+fn loading_invalid_identifier_fails() {
+    let connection = connection();
+
+    sql_query(
+        "INSERT INTO identifiers (event_id, room_id, room_alias_id, user_id, room_version_id)
+        VALUES ('$39hvsi03hlne:example.com', 'not a room ID', '#ruma:example.com',
+            '@carl:example.com', '5')",
+    )
+    .execute(&connection)
+    .expect("Failed to insert identifiers.");
+
+    assert!(identifiers::table
+        .select(identifiers::room_id)
+        .first::<RoomId>(&connection)
+        .is_err());
+    assert_eq!(
+        identifiers::table
+            .select(identifiers::user_id)
+            .first::<UserId>(&connection)
+            .expect("Failed to load UserId."),
+        UserId::try_from("@carl:example.com").expect("Failed to create UserId.")
+    );
+}
@@ -0,0 +1,40 @@
+//! Checks that the public length constants match the limits the identifier types enforce.
+
+use std::convert::TryFrom;
+
+use ruma_identifiers::{ErrorKind, RoomId, UserId, MAX_BYTES, MIN_CHARS, SIGIL_BYTES};
+
+#[test]
+fn minimum_length() {
+    let shortest = "@a:b";
+
+    assert_eq!(shortest.len(), MIN_CHARS);
+    assert!(UserId::try_from(shortest).is_ok());
+    assert_eq!(
+        UserId::try_from(&shortest[..MIN_CHARS - 1])
+            .unwrap_err()
+            .kind(),
+        ErrorKind::MinimumLengthNotSatisfied
+    );
+}
+
+#[test]
+fn maximum_length() {
+    let server_name = ":example.com";
+    let localpart = "a".repeat(MAX_BYTES - SIGIL_BYTES - server_name.len());
+
+    assert!(RoomId::try_from(format!("!{}{}", localpart, server_name)).is_ok());
+    assert_eq!(
+        RoomId::try_from(format!("!a{}{}", localpart, server_name))
+            .unwrap_err()
+            .kind(),
+        ErrorKind::MaximumLengthExceeded
+    );
+}
+
+#[test]
+fn sigil_length() {
+    let room_id = RoomId::try_from("!29fhd83h92h0:example.com").expect("Failed to create RoomId.");
+
+    assert_eq!(&room_id.as_str()[SIGIL_BYTES..], "29fhd83h92h0:example.com");
+}
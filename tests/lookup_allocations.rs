@@ -0,0 +1,62 @@
+//! Checks that looking up identifiers in maps and sets, e.g. by string slice, doesn't allocate.
+//!
+//! This counts the allocations of the whole test binary, so it must not contain other tests that
+//! could run concurrently.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    collections::{BTreeSet, HashMap},
+    convert::TryFrom,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use ruma_identifiers::{RoomId, ServerName, UserId};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn looking_up_identifiers_by_str_does_not_allocate() {
+    let sessions = (0..100)
+        .map(|i| UserId::try_from(format!("@user{}:example.com", i)).map(|user_id| (user_id, i)))
+        .collect::<Result<HashMap<_, _>, _>>()
+        .expect("Failed to create UserId.");
+    let rooms = (0..100)
+        .map(|i| RoomId::try_from(format!("!room{}:example.com", i)))
+        .collect::<Result<BTreeSet<_>, _>>()
+        .expect("Failed to create RoomId.");
+    let servers = (0..100)
+        .map(|i| ServerName::try_from(format!("server{}.example.com:{}", i, 5000 + i)))
+        .collect::<Result<BTreeSet<_>, _>>()
+        .expect("Failed to create ServerName.");
+    let server =
+        ServerName::try_from("server42.example.com:5042").expect("Failed to create ServerName.");
+
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    let session = sessions.get("@user42:example.com").copied();
+    let missing_session = sessions.get("@carl:example.com").copied();
+    let has_room = rooms.contains("!room42:example.com");
+    let has_server = servers.contains(&server);
+    let after = ALLOCATIONS.load(Ordering::SeqCst);
+
+    assert_eq!(after - before, 0);
+    assert_eq!(session, Some(42));
+    assert_eq!(missing_session, None);
+    assert!(has_room);
+    assert!(has_server);
+}
@@ -0,0 +1,126 @@
+//! Checks that the public parsing functions apply the same rules as the identifier types.
+
+use std::{
+    convert::TryFrom,
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
+use ruma_identifiers::{
+    parse_id_parts, parse_server_name, ErrorKind, Host, RoomId, ServerName, UserId, DEFAULT_PORT,
+};
+
+#[test]
+fn parse_valid_server_names() {
+    assert_eq!(
+        parse_server_name("example.com").expect("Failed to parse server name."),
+        (Host::Domain("example.com".to_string()), DEFAULT_PORT)
+    );
+    assert_eq!(
+        parse_server_name("1.1.1.1:5000").expect("Failed to parse server name."),
+        (Host::Ipv4(Ipv4Addr::new(1, 1, 1, 1)), 5000)
+    );
+    assert_eq!(
+        parse_server_name("[::1]:8448").expect("Failed to parse server name."),
+        (Host::Ipv6(Ipv6Addr::LOCALHOST), DEFAULT_PORT)
+    );
+}
+
+#[test]
+fn parse_server_name_matches_server_name() {
+    for server_name in &[
+        "example.com",
+        "EXAMPLE.com:443",
+        "[::1]:5000",
+        "",
+        " example.com",
+        "example.com:0",
+        "example.com:99999",
+        "exa mple.com",
+        "[::1",
+    ] {
+        match ServerName::try_from(*server_name) {
+            Ok(parsed) => assert_eq!(
+                parse_server_name(server_name).expect("Failed to parse server name."),
+                (parsed.host().clone(), parsed.port())
+            ),
+            Err(err) => assert_eq!(
+                parse_server_name(server_name).unwrap_err().kind(),
+                err.kind()
+            ),
+        }
+    }
+}
+
+#[test]
+fn parse_valid_id_parts() {
+    assert_eq!(
+        parse_id_parts('!', "!29fhd83h92h0:example.com:5000").expect("Failed to parse ID."),
+        (
+            "29fhd83h92h0",
+            Host::Domain("example.com".to_string()),
+            5000
+        )
+    );
+    assert_eq!(
+        parse_id_parts('@', "@carl:[::1]").expect("Failed to parse ID."),
+        ("carl", Host::Ipv6(Ipv6Addr::LOCALHOST), DEFAULT_PORT)
+    );
+}
+
+#[test]
+fn parse_id_parts_matches_room_id() {
+    for id in &[
+        "!29fhd83h92h0:example.com",
+        "!29fhd83h92h0:1.1.1.1:443",
+        "29fhd83h92h0:example.com",
+        "!29fhd83h92h0",
+        "!:example.com",
+        "!29fhd83h92h0:",
+        "!29fhd83h92h0:exa mple.com",
+        " !29fhd83h92h0:example.com",
+    ] {
+        match RoomId::try_from(*id) {
+            Ok(room_id) => assert_eq!(
+                parse_id_parts('!', id).expect("Failed to parse ID."),
+                (
+                    room_id.localpart(),
+                    room_id.hostname().clone(),
+                    room_id.port()
+                )
+            ),
+            Err(err) => assert_eq!(parse_id_parts('!', id).unwrap_err().kind(), err.kind()),
+        }
+    }
+}
+
+#[test]
+fn parse_id_parts_does_not_check_user_localparts() {
+    let id = "@ca rl:example.com";
+
+    assert_eq!(
+        UserId::try_from(id).unwrap_err().kind(),
+        ErrorKind::InvalidCharacters
+    );
+    assert_eq!(
+        parse_id_parts('@', id).expect("Failed to parse ID.").0,
+        "ca rl"
+    );
+}
+
+#[test]
+fn parse_id_parts_with_multibyte_sigil() {
+    for (sigil, id) in &[('é', "éa:example.com"), ('老', "老虎:example.com")] {
+        assert_eq!(
+            parse_id_parts(*sigil, id).unwrap_err().kind(),
+            ErrorKind::MissingSigil
+        );
+    }
+}
+
+#[test]
+fn parse_id_parts_with_wrong_sigil() {
+    assert_eq!(
+        parse_id_parts('#', "@carl:example.com").unwrap_err().kind(),
+        ErrorKind::MissingSigil
+    );
+}
@@ -0,0 +1,48 @@
+//! Generates JSON schemas for identifiers with schemars.
+
+#![cfg(feature = "schemars")]
+
+use ruma_identifiers::{EventId, RoomAliasId, RoomId, RoomVersionId, UserId};
+use schemars::{schema::InstanceType, schema_for, JsonSchema};
+use serde_json::{json, to_value};
+
+fn assert_string_schema<T: JsonSchema>(pattern: &str) {
+    let schema = schema_for!(T).schema;
+
+    assert_eq!(schema.instance_type, Some(InstanceType::String.into()));
+    assert_eq!(
+        schema
+            .string
+            .expect("Missing string validation.")
+            .pattern
+            .as_deref(),
+        Some(pattern)
+    );
+    assert!(schema
+        .metadata
+        .expect("Missing metadata.")
+        .description
+        .is_some());
+}
+
+#[test]
+fn identifier_schemas() {
+    assert_string_schema::<EventId>(r"^\$([^:\s]+:\S+|[A-Za-z0-9+/_-]+)$");
+    assert_string_schema::<RoomAliasId>(r"^#[^:\s]+:\S+$");
+    assert_string_schema::<RoomId>(r"^![^:\s]+:\S+$");
+    assert_string_schema::<UserId>(r"^@[!-9;-~]+:\S+$");
+    assert_string_schema::<RoomVersionId>(r"^\S+$");
+}
+
+#[test]
+fn schema_as_json() {
+    let schema = to_value(schema_for!(RoomId)).expect("Failed to convert schema to JSON.");
+
+    assert_eq!(schema["title"], json!("RoomId"));
+    assert_eq!(schema["type"], json!("string"));
+    assert_eq!(schema["maxLength"], json!(null));
+
+    let schema = to_value(schema_for!(RoomVersionId)).expect("Failed to convert schema to JSON.");
+
+    assert_eq!(schema["maxLength"], json!(32));
+}
@@ -0,0 +1,38 @@
+//! Checks that identifiers serialize to exactly their string representation.
+
+use std::{convert::TryFrom, fmt::Display};
+
+use ruma_identifiers::{EventId, RoomAliasId, RoomId, RoomVersionId, UserId};
+use serde::Serialize;
+use serde_json::to_vec;
+
+fn assert_serializes_as_display(value: &(impl Serialize + Display)) {
+    assert_eq!(
+        to_vec(value).expect("Failed to convert identifier to JSON."),
+        to_vec(&value.to_string()).expect("Failed to convert string to JSON.")
+    );
+}
+
+#[test]
+fn serialized_output_matches_display() {
+    assert_serializes_as_display(
+        &EventId::try_from("$39hvsi03hlne:example.com:5000").expect("Failed to create EventId."),
+    );
+    assert_serializes_as_display(
+        &EventId::try_from("$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk")
+            .expect("Failed to create EventId."),
+    );
+    assert_serializes_as_display(
+        &RoomId::try_from("!29fhd83h92h0:[::1]:8448").expect("Failed to create RoomId."),
+    );
+    assert_serializes_as_display(
+        &RoomAliasId::try_from("#老虎Â£я:example.com").expect("Failed to create RoomAliasId."),
+    );
+    assert_serializes_as_display(
+        &UserId::try_from(r#"@CARL"\:example.com"#).expect("Failed to create UserId."),
+    );
+    assert_serializes_as_display(&RoomVersionId::version_5());
+    assert_serializes_as_display(
+        &RoomVersionId::try_from("io.ruma.1").expect("Failed to create RoomVersionId."),
+    );
+}
@@ -0,0 +1,65 @@
+//! Checks that serializing and displaying identifiers doesn't allocate.
+//!
+//! This counts the allocations of the whole test binary, so it must not contain other tests that
+//! could run concurrently.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    convert::TryFrom,
+    fmt::Write,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use ruma_identifiers::{RoomId, RoomVersionId, ServerName, UserId};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn serializing_identifiers_does_not_allocate() {
+    let user_ids = (0..100)
+        .map(|i| UserId::try_from(format!("@user{}:example.com:5000", i)))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("Failed to create UserId.");
+    let room_ids = (0..100)
+        .map(|i| RoomId::try_from(format!("!room{}:example.com", i)))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("Failed to create RoomId.");
+    let server_names =
+        vec![ServerName::try_from("example.com:5000").expect("Failed to create ServerName."); 100];
+    let room_versions = vec![RoomVersionId::version_5(); 100];
+
+    let mut buffer = Vec::with_capacity(64 * 1024);
+    let mut displayed = String::with_capacity(64 * 1024);
+
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    serde_json::to_writer(&mut buffer, &user_ids).expect("Failed to convert UserIds to JSON.");
+    serde_json::to_writer(&mut buffer, &room_ids).expect("Failed to convert RoomIds to JSON.");
+    serde_json::to_writer(&mut buffer, &server_names)
+        .expect("Failed to convert ServerNames to JSON.");
+    serde_json::to_writer(&mut buffer, &room_versions)
+        .expect("Failed to convert RoomVersionIds to JSON.");
+    for (user_id, room_id) in user_ids.iter().zip(&room_ids) {
+        write!(displayed, "{} {}", user_id, room_id).expect("Failed to display identifiers.");
+    }
+    let after = ALLOCATIONS.load(Ordering::SeqCst);
+
+    assert_eq!(after - before, 0);
+    assert!(buffer.starts_with(br#"["@user0:example.com:5000","@user1:example.com:5000""#));
+    assert!(displayed.starts_with("@user0:example.com:5000 !room0:example.com"));
+}
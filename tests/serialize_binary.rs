@@ -0,0 +1,86 @@
+//! Checks that identifiers round-trip through a binary, non-human-readable format as well as
+//! through JSON.
+
+use std::{convert::TryFrom, fmt::Debug};
+
+use ruma_identifiers::{
+    DeviceId, EventId, GroupId, KeyId, MxcUri, RoomAliasId, RoomId, RoomIdOrAliasId, RoomVersionId,
+    ServerName, UserId, UserLocalpart,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+fn assert_round_trips<T>(value: &T)
+where
+    T: Serialize + DeserializeOwned + Debug + PartialEq,
+{
+    let bytes = bincode::serialize(value).expect("Failed to convert identifier to bincode.");
+    assert_eq!(
+        &bincode::deserialize::<T>(&bytes).expect("Failed to convert bincode to identifier."),
+        value
+    );
+
+    let json = serde_json::to_string(value).expect("Failed to convert identifier to JSON.");
+    assert_eq!(
+        &serde_json::from_str::<T>(&json).expect("Failed to convert JSON to identifier."),
+        value
+    );
+}
+
+#[test]
+fn identifiers_round_trip() {
+    assert_round_trips(
+        &EventId::try_from("$39hvsi03hlne:example.com:5000").expect("Failed to create EventId."),
+    );
+    assert_round_trips(
+        &EventId::try_from("$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk")
+            .expect("Failed to create EventId."),
+    );
+    assert_round_trips(&GroupId::try_from("+ruma:example.com").expect("Failed to create GroupId."));
+    assert_round_trips(&KeyId::try_from("ed25519:abc123").expect("Failed to create KeyId."));
+    assert_round_trips(
+        &RoomAliasId::try_from("#老虎Â£я:example.com").expect("Failed to create RoomAliasId."),
+    );
+    assert_round_trips(&RoomId::try_from("!29fhd83h92h0:[::1]").expect("Failed to create RoomId."));
+    assert_round_trips(
+        &RoomIdOrAliasId::try_from("#ruma:example.com").expect("Failed to create RoomIdOrAliasId."),
+    );
+    assert_round_trips(&UserId::try_from("@carl:example.com").expect("Failed to create UserId."));
+    assert_round_trips(&UserLocalpart::try_from("carl").expect("Failed to create UserLocalpart."));
+    assert_round_trips(&DeviceId::try_from("JLAFKJWSCS").expect("Failed to create DeviceId."));
+    assert_round_trips(
+        &ServerName::try_from("example.com:5000").expect("Failed to create ServerName."),
+    );
+    assert_round_trips(
+        &MxcUri::try_from("mxc://example.com/ascERGshawAWawugaAcauga")
+            .expect("Failed to create MxcUri."),
+    );
+}
+
+#[test]
+fn room_version_ids_round_trip() {
+    assert_round_trips(&RoomVersionId::version_5());
+    assert_round_trips(
+        &RoomVersionId::try_from("io.ruma.1").expect("Failed to create RoomVersionId."),
+    );
+}
+
+#[test]
+fn binary_form_is_the_string_representation() {
+    let user_id = UserId::try_from("@carl:example.com:8448").expect("Failed to create UserId.");
+
+    assert_eq!(
+        bincode::serialize(&user_id).expect("Failed to convert UserId to bincode."),
+        bincode::serialize("@carl:example.com").expect("Failed to convert string to bincode.")
+    );
+}
+
+#[test]
+fn invalid_identifiers_are_rejected_in_binary_form() {
+    let bytes = bincode::serialize("carl:example.com").expect("Failed to convert to bincode.");
+
+    assert!(bincode::deserialize::<UserId>(&bytes).is_err());
+    assert!(bincode::deserialize::<RoomVersionId>(
+        &bincode::serialize("").expect("Failed to convert to bincode.")
+    )
+    .is_err());
+}